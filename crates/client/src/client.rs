@@ -7,14 +7,18 @@ use glam::UVec2;
 use riposte_common::{
     assets::Handle,
     bridge::{Bridge, ClientSide},
-    city::BuildTask,
+    city::{BuildTask, Specialist},
+    governor::GovernorSettings,
     lobby::{GameLobby, SlotId},
     mapgen::MapgenSettings,
     player::EconomySettings,
     protocol::{
         client::{
-            ClientGamePacket, ConfigureWorkedTiles, DeclareWar, DoUnitAction, EndTurn, MakePeace,
-            MoveUnits, SaveGame, SetCityBuildTask, SetEconomySettings, SetResearch, SetWorkerTask,
+            AppendBuildQueue, BuyBuildTask, ClientGamePacket, ConfigureWorkedTiles, DeclareWar,
+            DoUnitAction, EndTurn, InsertBuildQueue, MakePeace, MoveUnits, OrderMoveUnits,
+            PromoteUnit, RemoveBuildQueue, ReorderBuildQueue, SaveGame, SetBuildTaskRepeat,
+            SetCityBuildTask, SetCityGovernor, SetCitySpecialists, SetCivics, SetEconomySettings,
+            SetResearch, SetStateReligion, SetWorkerTask, UpgradeUnit,
         },
         game::client::{ClientPacket, UnitAction},
         lobby::{
@@ -24,7 +28,7 @@ use riposte_common::{
         server::{ConfirmMoveUnits, InitialGameData, ServerGamePacket, ServerPacket, UnitsMoved},
         GenericClientPacket, GenericServerPacket,
     },
-    registry::{Civilization, Leader, Registry, Tech},
+    registry::{Civic, Civilization, Leader, Promotion, Registry, Religion, Tech, UnitKind},
     worker::WorkerTask,
     CityId, PlayerId, UnitId,
 };
@@ -209,11 +213,37 @@ impl Client<GameState> {
         self.register_response_future(request_id)
     }
 
+    pub fn order_move_units(
+        &mut self,
+        _game: &Game,
+        unit_ids: impl Iterator<Item = UnitId>,
+        target_pos: UVec2,
+    ) {
+        self.send_message(ClientPacket::OrderMoveUnits(OrderMoveUnits {
+            unit_ids: unit_ids.collect(),
+            target_pos,
+        }));
+    }
+
     pub fn do_unit_action(&mut self, _game: &Game, unit_id: UnitId, action: UnitAction) {
         self.send_message(ClientPacket::DoUnitAction(DoUnitAction { unit_id, action }));
         log::info!("Performing unit action {:?}", action);
     }
 
+    pub fn upgrade_unit(&mut self, _game: &Game, unit_id: UnitId, unit_kind: &Handle<UnitKind>) {
+        self.send_message(ClientPacket::UpgradeUnit(UpgradeUnit {
+            unit_id,
+            unit_kind: unit_kind.clone(),
+        }));
+    }
+
+    pub fn promote_unit(&mut self, _game: &Game, unit_id: UnitId, promotion: &Handle<Promotion>) {
+        self.send_message(ClientPacket::PromoteUnit(PromoteUnit {
+            unit_id,
+            promotion: promotion.clone(),
+        }));
+    }
+
     pub fn set_city_build_task(&mut self, _game: &Game, city_id: CityId, build_task: BuildTask) {
         self.send_message(ClientPacket::SetCityBuildTask(SetCityBuildTask {
             city_id,
@@ -221,15 +251,107 @@ impl Client<GameState> {
         }));
     }
 
+    pub fn buy_build_task(&mut self, _game: &Game, city_id: CityId) {
+        self.send_message(ClientPacket::BuyBuildTask(BuyBuildTask { city_id }));
+    }
+
+    pub fn append_build_queue(
+        &mut self,
+        _game: &Game,
+        city_id: CityId,
+        build_task: BuildTask,
+        repeat: bool,
+    ) {
+        self.send_message(ClientPacket::AppendBuildQueue(AppendBuildQueue {
+            city_id,
+            build_task,
+            repeat,
+        }));
+    }
+
+    pub fn insert_build_queue(
+        &mut self,
+        _game: &Game,
+        city_id: CityId,
+        index: usize,
+        build_task: BuildTask,
+        repeat: bool,
+    ) {
+        self.send_message(ClientPacket::InsertBuildQueue(InsertBuildQueue {
+            city_id,
+            index,
+            build_task,
+            repeat,
+        }));
+    }
+
+    pub fn remove_build_queue(&mut self, _game: &Game, city_id: CityId, index: usize) {
+        self.send_message(ClientPacket::RemoveBuildQueue(RemoveBuildQueue {
+            city_id,
+            index,
+        }));
+    }
+
+    pub fn reorder_build_queue(&mut self, _game: &Game, city_id: CityId, from: usize, to: usize) {
+        self.send_message(ClientPacket::ReorderBuildQueue(ReorderBuildQueue {
+            city_id,
+            from,
+            to,
+        }));
+    }
+
+    pub fn set_build_task_repeat(
+        &mut self,
+        _game: &Game,
+        city_id: CityId,
+        index: usize,
+        repeat: bool,
+    ) {
+        self.send_message(ClientPacket::SetBuildTaskRepeat(SetBuildTaskRepeat {
+            city_id,
+            index,
+            repeat,
+        }));
+    }
+
+    pub fn set_city_governor(&mut self, _game: &Game, city_id: CityId, settings: GovernorSettings) {
+        self.send_message(ClientPacket::SetCityGovernor(SetCityGovernor {
+            city_id,
+            settings,
+        }));
+    }
+
+    pub fn set_city_specialists(
+        &mut self,
+        _game: &Game,
+        city_id: CityId,
+        specialist: Specialist,
+        count: u32,
+    ) {
+        self.send_message(ClientPacket::SetCitySpecialists(SetCitySpecialists {
+            city_id,
+            specialist,
+            count,
+        }));
+    }
+
     pub fn set_research(&mut self, tech: &Handle<Tech>) {
         self.send_message(ClientPacket::SetResearch(SetResearch {
             tech: tech.clone(),
         }));
     }
 
-    pub fn set_economy_settings(&mut self, beaker_percent: u32) {
-        let mut settings = EconomySettings::default();
-        settings.set_beaker_percent(beaker_percent);
+    pub fn set_state_religion(&mut self, religion: Option<&Handle<Religion>>) {
+        self.send_message(ClientPacket::SetStateReligion(SetStateReligion {
+            religion: religion.cloned(),
+        }));
+    }
+
+    pub fn set_civics(&mut self, civics: Vec<Handle<Civic>>) {
+        self.send_message(ClientPacket::SetCivics(SetCivics { civics }));
+    }
+
+    pub fn set_economy_settings(&mut self, settings: EconomySettings) {
         self.send_message(ClientPacket::SetEconomySettings(SetEconomySettings {
             settings,
         }));
@@ -311,6 +433,13 @@ impl Client<GameState> {
                         declared: p.made,
                     }),
                     ServerPacket::CombatEvent(p) => self.handle_combat_event(cx, game, p)?,
+                    ServerPacket::UnitWoken(p) => game.selection_driver_mut().on_unit_woken(p.unit),
+                    ServerPacket::SpyDiscovered(p) => {
+                        game.push_event(GameEvent::SpyDiscovered { discovered: p })
+                    }
+                    ServerPacket::TribalVillageEntered(p) => {
+                        game.push_event(GameEvent::TribalVillageEntered { entered: p })
+                    }
                 }
             }
 
@@ -333,6 +462,7 @@ impl Client<GameState> {
             unit.set_pos_unsafe(packet.new_pos);
             unit.set_movement_left_unsafe(movement_left);
         }
+        game.update_unit_pos_index(&packet.units, old_pos, packet.new_pos);
 
         game.on_units_moved(cx, &packet.units, old_pos, packet.new_pos);
     }
@@ -17,7 +17,10 @@ use glam::{uvec2, vec2};
 use once_cell::sync::OnceCell;
 use riposte_common::{
     assets::Assets,
-    registry::{Building, Civilization, Registry, Resource, Tech, UnitKind},
+    registry::{
+        Building, Civic, Civilization, Handicap, ImprovementKind, Promotion, Registry, Religion,
+        Resource, Tech, TerrainFeature, TerrainKind, UnitKind,
+    },
 };
 use tokio::runtime::{self, Runtime};
 use walkdir::WalkDir;
@@ -135,7 +138,14 @@ impl Context {
             .add_loader("unit", JsonLoader::<UnitKind>::new())
             .add_loader("tech", JsonLoader::<Tech>::new())
             .add_loader("building", JsonLoader::<Building>::new())
-            .add_loader("resource", JsonLoader::<Resource>::new());
+            .add_loader("resource", JsonLoader::<Resource>::new())
+            .add_loader("promotion", JsonLoader::<Promotion>::new())
+            .add_loader("improvement", JsonLoader::<ImprovementKind>::new())
+            .add_loader("terrain", JsonLoader::<TerrainKind>::new())
+            .add_loader("feature", JsonLoader::<TerrainFeature>::new())
+            .add_loader("religion", JsonLoader::<Religion>::new())
+            .add_loader("civic", JsonLoader::<Civic>::new())
+            .add_loader("handicap", JsonLoader::<Handicap>::new());
 
         let registry = Arc::new(Registry::new());
 
@@ -181,7 +191,7 @@ impl Context {
     pub fn load_assets(&mut self) -> anyhow::Result<()> {
         Arc::get_mut(&mut self.registry)
             .unwrap()
-            .load_from_assets(riposte_common::assets::global_assets());
+            .load_from_assets(riposte_common::assets::global_assets())?;
 
         let texture_set = self
             .texture_set_builder
@@ -1,7 +1,6 @@
 pub mod city;
 pub mod combat;
 pub mod event;
-pub mod path;
 pub mod player;
 pub mod selection;
 pub mod stack;
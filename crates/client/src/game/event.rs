@@ -1,7 +1,10 @@
 use std::{cell::RefCell, collections::VecDeque};
 
 use glam::UVec2;
-use riposte_common::{assets::Handle, registry::Tech, CityId, PlayerId, UnitId};
+use riposte_common::{
+    assets::Handle, espionage::SpyDiscovered, registry::Tech, tribal_village::TribalVillageEntered,
+    CityId, PlayerId, UnitId,
+};
 
 /// An event indicates that some piece of game data was updated.
 ///
@@ -41,6 +44,14 @@ pub enum GameEvent {
     TechUnlocked {
         tech: Handle<Tech>,
     },
+    /// A spy was caught in one of our cities.
+    SpyDiscovered {
+        discovered: SpyDiscovered,
+    },
+    /// One of our units entered a tribal village.
+    TribalVillageEntered {
+        entered: TribalVillageEntered,
+    },
 }
 
 #[derive(Default)]
@@ -6,6 +6,7 @@ use duit::{Event, Vec2};
 use float_ord::FloatOrd;
 use glam::UVec2;
 use riposte_common::{
+    path::{Path, UnitMovementRules},
     protocol::server::ConfirmMoveUnits,
     utils::{Version, VersionSnapshot},
     UnitId,
@@ -19,7 +20,7 @@ use crate::{
     context::Context,
 };
 
-use super::{path::Path, Game};
+use super::Game;
 
 /// The time after no units are selected at which we will
 /// attempt to auto-select the next unit group.
@@ -124,21 +125,12 @@ impl SelectedUnits {
 #[derive(Debug)]
 struct UnitGroup {
     units: SmallVec<[UnitId; 1]>,
-
-    /// The target location of this unit group. If set,
-    /// the units will automatically move toward the target
-    /// until they have arrived or encountered an obstacle.
-    target_pos: Option<UVec2>,
-
-    last_move_turn: Option<u32>,
 }
 
 impl UnitGroup {
     pub fn new(units: impl IntoIterator<Item = UnitId>) -> Self {
         Self {
             units: units.into_iter().collect(),
-            target_pos: None,
-            last_move_turn: None,
         }
     }
 
@@ -157,14 +149,17 @@ impl UnitGroup {
     }
 
     /// Returns whether the group is a candidate for being auto-selected.
+    ///
+    /// Units following an order are moved by the server
+    /// and are not auto-selected.
     pub fn should_autoselect(&self, game: &Game) -> bool {
-        self.last_move_turn != Some(game.turn().get())
-            && self.units.iter().any(|&u| {
-                let unit = game.unit(u);
-                !unit.movement_left().is_exhausted()
-                    && !unit.is_fortified()
-                    && !unit.has_worker_task()
-            })
+        self.units.iter().any(|&u| {
+            let unit = game.unit(u);
+            !unit.movement_left().is_exhausted()
+                && !unit.is_fortified()
+                && !unit.has_worker_task()
+                && unit.order().is_none()
+        })
     }
 }
 
@@ -201,6 +196,10 @@ pub struct SelectionDriver {
     movement: MovementDriver,
 
     is_selection_exhausted: bool,
+
+    /// Sentry and alert units that woke up because an enemy
+    /// came into view. These are auto-selected first.
+    woken_units: Vec<UnitId>,
 }
 
 impl SelectionDriver {
@@ -243,6 +242,11 @@ impl SelectionDriver {
         self.remove_unit_from_group(unit);
     }
 
+    pub fn on_unit_woken(&mut self, unit: UnitId) {
+        self.woken_units.push(unit);
+        self.is_selection_exhausted = false;
+    }
+
     /// NB: when a group of units is moved by the player all at once,
     /// this function should not be called until _after_ all
     /// units in the group had their positions updated. Otherwise,
@@ -269,7 +273,13 @@ impl SelectionDriver {
         }
     }
 
-    pub fn update(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>, time: f32) {
+    pub fn update(
+        &mut self,
+        cx: &Context,
+        game: &Game,
+        _client: &mut Client<GameState>,
+        time: f32,
+    ) {
         if game.is_view_locked() {
             return;
         }
@@ -285,7 +295,7 @@ impl SelectionDriver {
         }
 
         if time - self.last_selection_time >= AUTOSELECT_TIME && !game.are_prompts_open {
-            self.do_autoselect(cx, game, client);
+            self.do_autoselect(cx, game);
         }
     }
 
@@ -310,7 +320,20 @@ impl SelectionDriver {
     /// Auto-selects the closest unit group that meets the following conditions:
     /// * It has at least one unit that can still move on this turn; and
     /// * not all units in the stack are fortified.
-    fn do_autoselect(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>) {
+    fn do_autoselect(&mut self, cx: &Context, game: &Game) {
+        // Units that just woke up come first, since an enemy is nearby.
+        while let Some(unit) = self.woken_units.pop() {
+            if let Some(&group_id) = self.unit_to_group.get(unit) {
+                if self.groups[group_id].should_autoselect(game) {
+                    game.view_mut()
+                        .animate_to(cx, self.groups[group_id].pos(game).unwrap());
+                    self.select_unit_group(game, group_id);
+                    self.is_selection_exhausted = false;
+                    return;
+                }
+            }
+        }
+
         let mut candidate_groups = Vec::new();
 
         for (group_id, group) in &self.groups {
@@ -335,29 +358,10 @@ impl SelectionDriver {
                 candidate_groups.len()
             );
 
-            let group = &mut self.groups[best_group_id];
-            let mut should_select = true;
-            if let Some(target) = group.target_pos {
-                if target == group.pos(game).unwrap() {
-                    group.target_pos = None;
-                } else if let Some(mut path) = game.pathfinder_mut().compute_shortest_path(
-                    game,
-                    group.units.iter().map(|u| game.unit(*u)),
-                    group.pos(game).unwrap(),
-                    target,
-                ) {
-                    self.move_units_along_path(game, client, best_group_id, &mut path, false);
-                    should_select = false;
-                    self.groups[best_group_id].last_move_turn = Some(game.turn().get());
-                }
-            }
-
-            if should_select {
-                game.view_mut()
-                    .animate_to(cx, self.groups[best_group_id].pos(game).unwrap());
-                self.select_unit_group(game, best_group_id);
-                self.is_selection_exhausted = false;
-            }
+            game.view_mut()
+                .animate_to(cx, self.groups[best_group_id].pos(game).unwrap());
+            self.select_unit_group(game, best_group_id);
+            self.is_selection_exhausted = false;
         } else {
             self.is_selection_exhausted = true;
         }
@@ -368,10 +372,6 @@ impl SelectionDriver {
         for &unit in group.units() {
             game.selected_units_mut().select(game, unit);
         }
-
-        if let Some(target_pos) = group.target_pos {
-            self.pathfind_to(game, target_pos);
-        }
     }
 
     pub fn handle_event(
@@ -443,7 +443,18 @@ impl SelectionDriver {
         let group_id = self.create_group(game.selected_units().get_all().iter().copied());
 
         if let Some(StagedPath::Complete { mut path }) = self.staged_path.take() {
-            self.move_units_along_path(game, client, group_id, &mut path, true);
+            if path.end().turn > 1 {
+                // We can't finish moving on this turn. Give the units
+                // an order so the server moves them on the following turns.
+                client.order_move_units(
+                    game,
+                    self.groups[group_id].units.iter().copied(),
+                    path.end().pos,
+                );
+                game.selected_units_mut().clear();
+            } else {
+                self.move_units_along_path(game, client, group_id, &mut path);
+            }
         }
     }
 
@@ -453,31 +464,17 @@ impl SelectionDriver {
         client: &mut Client<GameState>,
         group_id: UnitGroupId,
         path: &mut Path,
-        deselect_after_move: bool,
     ) {
-        let group = &mut self.groups[group_id];
-        let end = path.end().pos;
+        let group = &self.groups[group_id];
 
         let mut prev_pos = path.start().pos;
         while let Some(point) = path.next() {
-            if point.turn > 1 {
-                // We can't finish moving on this turn.
-                // Set the group's target position so it
-                // automatically moves toward the target on the next turn.
-                group.target_pos = Some(end);
-                if deselect_after_move {
-                    game.selected_units_mut().clear();
-                }
-                break;
-            }
-
             // If the units will have no movement left, then
             // we automatically deselect them after moving.
-            let should_deselect = deselect_after_move
-                && match path.peek() {
-                    Some(next_point) => next_point.turn > 1,
-                    None => point.movement_left.is_exhausted(),
-                };
+            let should_deselect = match path.peek() {
+                Some(next_point) => next_point.turn > 1,
+                None => point.movement_left.is_exhausted(),
+            };
 
             self.movement.move_units(
                 game,
@@ -513,15 +510,17 @@ impl SelectionDriver {
     fn pathfind_to(&mut self, game: &Game, end: UVec2) {
         let start = game.selected_units().pos(game).unwrap();
 
-        match game.pathfinder_mut().compute_shortest_path(
-            game,
+        let rules = UnitMovementRules::new(
+            game.the_player().id(),
             game.selected_units()
                 .get_all()
                 .iter()
                 .map(|&u| game.unit(u)),
-            start,
-            end,
-        ) {
+        );
+        match game
+            .pathfinder_mut()
+            .compute_shortest_path(game.base(), &rules, start, end)
+        {
             Some(path) => {
                 log::info!("Computed path from {:?} to {:?}", start, end);
                 self.staged_path = Some(StagedPath::Complete { path });
@@ -42,6 +42,11 @@ impl UnitStack {
 
     /// Adds a unit to the stack, then resorts.
     fn add_unit(&mut self, game: &Game, unit: UnitId) {
+        // Other players' spies are never shown.
+        if game.unit(unit).is_hidden_from(game.the_player().id()) {
+            return;
+        }
+
         if !self.units.contains(&unit) {
             self.units.push(unit);
             self.resort(game);
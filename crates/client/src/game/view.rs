@@ -3,7 +3,7 @@ use std::f32::consts::PI;
 use bitflags::bitflags;
 use duit::Event;
 use dume::Canvas;
-use glam::{uvec2, vec2, UVec2, Vec2};
+use glam::{ivec2, uvec2, vec2, IVec2, UVec2, Vec2};
 use splines::{Interpolation, Key, Spline};
 
 use crate::context::Context;
@@ -39,6 +39,9 @@ pub struct View {
     zoom_factor: f32,
     /// Size of the display region in logical pixels
     size: Vec2,
+    /// Width in world space of a map that wraps
+    /// around along the X axis.
+    wrap_width: Option<f32>,
 
     /// The directions we're currently animating the center toward.
     move_dirs: MoveDir,
@@ -64,6 +67,7 @@ impl View {
             center: Vec2::ZERO,
             zoom_factor: 1.,
             size: Vec2::ZERO,
+            wrap_width: None,
 
             move_dirs: MoveDir::empty(),
             move_time: Vec2::ZERO,
@@ -78,15 +82,21 @@ impl View {
         self.center
     }
 
+    /// Sets the width in tiles of a map that wraps
+    /// around along the X axis, or `None` if the map does not wrap.
+    pub fn set_wrap_width(&mut self, map_width: Option<u32>) {
+        self.wrap_width = map_width.map(|width| width as f32 * PIXELS_PER_TILE);
+    }
+
     pub fn set_center_tile(&mut self, center_tile: UVec2) {
         self.center = center_of_tile(center_tile);
     }
 
     pub fn center_tile(&self) -> UVec2 {
-        uvec2(
-            (self.center().x / PIXELS_PER_TILE).max(0.) as u32,
-            (self.center().y / PIXELS_PER_TILE).max(0.) as u32,
-        )
+        self.wrap_tile_pos(ivec2(
+            (self.center().x / PIXELS_PER_TILE).floor() as i32,
+            (self.center().y / PIXELS_PER_TILE).floor() as i32,
+        ))
     }
 
     pub fn zoom_factor(&self) -> f32 {
@@ -105,7 +115,9 @@ impl View {
         let animation_time = 0.5;
 
         let start_pos = self.center;
-        let target_pos = center_of_tile(target_tile);
+        // On a wrapping map, take the short way around.
+        let mut target_pos = center_of_tile(target_tile);
+        target_pos.x = self.nearest_copy_x(target_pos.x);
 
         self.center_animation = Some(Spline::from_vec(vec![
             Key::new(cx.time(), start_pos, Interpolation::Cosine),
@@ -131,7 +143,25 @@ impl View {
 
     /// Gets the offset in screen space logical pixels
     /// of the given tile position.
+    ///
+    /// On a wrapping map, this is the copy of the tile
+    /// closest to the center of the view.
     pub fn screen_offset_for_tile_pos(&self, tile_pos: UVec2) -> Vec2 {
+        self.screen_offset_for_unwrapped_tile_pos(self.unwrapped_tile_pos(tile_pos))
+    }
+
+    /// Gets the copy of the given tile position closest
+    /// to the center of the view. Equal to `tile_pos`
+    /// on a non-wrapping map.
+    pub fn unwrapped_tile_pos(&self, tile_pos: UVec2) -> IVec2 {
+        let x = self.nearest_copy_x(center_of_tile(tile_pos).x) - PIXELS_PER_TILE / 2.;
+        let x = (x / PIXELS_PER_TILE).round() as i32;
+        ivec2(x, tile_pos.y as i32)
+    }
+
+    /// Gets the offset in screen space logical pixels of a tile
+    /// position that may lie past the edge of a wrapping map.
+    pub fn screen_offset_for_unwrapped_tile_pos(&self, tile_pos: IVec2) -> Vec2 {
         vec2(
             tile_pos.x as f32 * PIXELS_PER_TILE - self.center.x + self.size.x / 2.,
             tile_pos.y as f32 * PIXELS_PER_TILE - self.center.y + self.size.y / 2.,
@@ -142,13 +172,41 @@ impl View {
     ///
     /// Useful to detect which tile was clicked.
     pub fn tile_pos_for_screen_offset(&self, screen_offset: Vec2) -> UVec2 {
+        self.wrap_tile_pos(self.unwrapped_tile_pos_for_screen_offset(screen_offset))
+    }
+
+    /// Gets the tile position at the given screen offset
+    /// without wrapping it onto the map.
+    pub fn unwrapped_tile_pos_for_screen_offset(&self, screen_offset: Vec2) -> IVec2 {
         let centered = (screen_offset - self.size / 2.) / self.zoom_factor;
         let translated = centered + self.center;
         let scaled = translated / PIXELS_PER_TILE;
-        uvec2(
-            scaled.x.floor().max(0.) as u32,
-            scaled.y.floor().max(0.) as u32,
-        )
+        ivec2(scaled.x.floor() as i32, scaled.y.floor() as i32)
+    }
+
+    /// Wraps a tile position onto a wrapping map, or clamps
+    /// negative coordinates to zero otherwise.
+    fn wrap_tile_pos(&self, tile_pos: IVec2) -> UVec2 {
+        let x = match self.wrap_width {
+            Some(wrap_width) => {
+                let map_width = (wrap_width / PIXELS_PER_TILE).round() as i32;
+                tile_pos.x.rem_euclid(map_width)
+            }
+            None => tile_pos.x.max(0),
+        };
+        uvec2(x as u32, tile_pos.y.max(0) as u32)
+    }
+
+    /// Shifts a world-space X coordinate by a multiple of the
+    /// map width so that it lies as close as possible to the center.
+    fn nearest_copy_x(&self, x: f32) -> f32 {
+        match self.wrap_width {
+            Some(wrap_width) => {
+                let offset = (x - self.center.x + wrap_width / 2.).rem_euclid(wrap_width);
+                self.center.x + offset - wrap_width / 2.
+            }
+            None => x,
+        }
     }
 
     pub fn update(&mut self, cx: &Context, game: &Game) {
@@ -165,6 +223,12 @@ impl View {
             }
         }
 
+        // Keep the center on the map after panning past the edge
+        // of a wrapping map.
+        if let (Some(wrap_width), None) = (self.wrap_width, &self.center_animation) {
+            self.center.x = self.center.x.rem_euclid(wrap_width);
+        }
+
         if let Some(spline) = &self.zoom_factor_animation {
             match spline.sample(cx.time()) {
                 Some(z) => self.zoom_factor = z,
@@ -9,10 +9,11 @@ use duit::Event;
 use glam::UVec2;
 use riposte_common::{
     game::tile::OutOfBounds,
+    path::Pathfinder,
     protocol::server::InitialGameData,
     registry::{CapabilityType, Registry},
     utils::VersionSnapshot,
-    CityId, Grid, PlayerId, Turn, UnitId,
+    CityId, GameSpeed, Grid, PlayerId, Turn, UnitId,
 };
 use riposte_common::{lobby::GameLobby, river::Rivers, Era};
 
@@ -25,7 +26,6 @@ use super::{
     city::City,
     combat::CombatEvent,
     event::{EventBus, GameEvent},
-    path::Pathfinder,
     player::Player,
     selection::{SelectedUnits, SelectionDriver},
     stack::{StackGrid, UnitStack},
@@ -71,10 +71,16 @@ impl Game {
     pub fn new(registry: Arc<Registry>, map: Grid<RefCell<Tile>>, rivers: Rivers) -> Self {
         let selected_units = SelectedUnits::new();
         let selection_units_version = selected_units.version();
+        let mut view = View::default();
+        view.set_wrap_width(if map.wraps_x() {
+            Some(map.width())
+        } else {
+            None
+        });
         Self {
             base: riposte_common::Game::new(registry, map, rivers, GameLobby::new()),
 
-            view: RefCell::new(View::default()),
+            view: RefCell::new(view),
             stacks: StackGrid::default(),
             current_combat_event: None,
             the_player_id: PlayerId::default(),
@@ -111,6 +117,7 @@ impl Game {
 
         let (width, height) = (data.map.width(), data.map.height());
         let mut game = Self::new(registry, data.map, data.rivers);
+        game.base.set_speed(data.speed);
 
         let stacks = StackGrid::new(width, height);
         game.stacks = stacks;
@@ -335,6 +342,10 @@ impl Game {
         self.base.turn()
     }
 
+    pub fn speed(&self) -> GameSpeed {
+        self.base.speed()
+    }
+
     /// Gets the current era.
     pub fn era(&self) -> Era {
         self.the_player().era()
@@ -422,6 +433,7 @@ impl Game {
             if old_pos != new_pos {
                 let id = unit.id();
                 drop(unit);
+                self.update_unit_pos_index(&[id], old_pos, new_pos);
                 self.on_units_moved(cx, &[id], old_pos, new_pos);
             }
         } else {
@@ -434,6 +446,11 @@ impl Game {
     }
 
     pub fn delete_unit(&mut self, unit: UnitId) {
+        // Units hidden from us, such as other players' spies,
+        // are deleted without ever having been sent.
+        if !self.is_unit_valid(unit) {
+            return;
+        }
         self.on_unit_deleted(unit);
         self.base.remove_unit(unit);
     }
@@ -447,6 +464,14 @@ impl Game {
             .on_unit_moved(cx, unit, pos, pos);
     }
 
+    /// Updates the position index of the base game
+    /// after units were moved with `Unit::set_pos_unsafe`.
+    pub fn update_unit_pos_index(&mut self, units: &[UnitId], old_pos: UVec2, new_pos: UVec2) {
+        for &unit in units {
+            self.base.update_unit_pos_index(unit, old_pos, new_pos);
+        }
+    }
+
     pub fn on_units_moved(&self, cx: &Context, units: &[UnitId], old_pos: UVec2, new_pos: UVec2) {
         self.stacks.on_units_moved(self, units, old_pos, new_pos);
         self.selected_units_mut()
@@ -19,6 +19,24 @@ pub struct GameLobbyWindow {
     pub map_size_picklist: WidgetHandle<PickList>,
     pub map_size_admin: WidgetHandle<Text>,
     pub start_game_button: WidgetHandle<Button>,
+    pub barbarians: WidgetHandle<Text>,
+    pub barbarians_picklist: WidgetHandle<PickList>,
+    pub barbarians_admin: WidgetHandle<Text>,
+    pub difficulty: WidgetHandle<Text>,
+    pub difficulty_picklist: WidgetHandle<PickList>,
+    pub difficulty_admin: WidgetHandle<Text>,
+    pub speed: WidgetHandle<Text>,
+    pub speed_picklist: WidgetHandle<PickList>,
+    pub speed_admin: WidgetHandle<Text>,
+    pub wrap_x: WidgetHandle<Text>,
+    pub wrap_x_picklist: WidgetHandle<PickList>,
+    pub wrap_x_admin: WidgetHandle<Text>,
+    pub land_percent: WidgetHandle<Text>,
+    pub land_percent_picklist: WidgetHandle<PickList>,
+    pub land_percent_admin: WidgetHandle<Text>,
+    pub inland_seas: WidgetHandle<Text>,
+    pub inland_seas_picklist: WidgetHandle<PickList>,
+    pub inland_seas_admin: WidgetHandle<Text>,
 }
 impl ::duit::InstanceHandle for GameLobbyWindow {
     fn name() -> &'static str {
@@ -40,6 +58,24 @@ impl ::duit::InstanceHandle for GameLobbyWindow {
         let mut map_size_picklist = None;
         let mut map_size_admin = None;
         let mut start_game_button = None;
+        let mut barbarians = None;
+        let mut barbarians_picklist = None;
+        let mut barbarians_admin = None;
+        let mut difficulty = None;
+        let mut difficulty_picklist = None;
+        let mut difficulty_admin = None;
+        let mut speed = None;
+        let mut speed_picklist = None;
+        let mut speed_admin = None;
+        let mut wrap_x = None;
+        let mut wrap_x_picklist = None;
+        let mut wrap_x_admin = None;
+        let mut land_percent = None;
+        let mut land_percent_picklist = None;
+        let mut land_percent_admin = None;
+        let mut inland_seas = None;
+        let mut inland_seas_picklist = None;
+        let mut inland_seas_admin = None;
         for (name, widget) in widget_handles {
             match name.as_str() {
                 "add_ai_slot_button" => add_ai_slot_button = Some(widget),
@@ -57,6 +93,24 @@ impl ::duit::InstanceHandle for GameLobbyWindow {
                 "map_size_picklist" => map_size_picklist = Some(widget),
                 "map_size_admin" => map_size_admin = Some(widget),
                 "start_game_button" => start_game_button = Some(widget),
+                "barbarians" => barbarians = Some(widget),
+                "barbarians_picklist" => barbarians_picklist = Some(widget),
+                "barbarians_admin" => barbarians_admin = Some(widget),
+                "difficulty" => difficulty = Some(widget),
+                "difficulty_picklist" => difficulty_picklist = Some(widget),
+                "difficulty_admin" => difficulty_admin = Some(widget),
+                "speed" => speed = Some(widget),
+                "speed_picklist" => speed_picklist = Some(widget),
+                "speed_admin" => speed_admin = Some(widget),
+                "wrap_x" => wrap_x = Some(widget),
+                "wrap_x_picklist" => wrap_x_picklist = Some(widget),
+                "wrap_x_admin" => wrap_x_admin = Some(widget),
+                "land_percent" => land_percent = Some(widget),
+                "land_percent_picklist" => land_percent_picklist = Some(widget),
+                "land_percent_admin" => land_percent_admin = Some(widget),
+                "inland_seas" => inland_seas = Some(widget),
+                "inland_seas_picklist" => inland_seas_picklist = Some(widget),
+                "inland_seas_admin" => inland_seas_admin = Some(widget),
                 _ => {}
             }
         }
@@ -153,6 +207,114 @@ impl ::duit::InstanceHandle for GameLobbyWindow {
                     "start_game_button"
                 )
             })),
+            barbarians: WidgetHandle::new(barbarians.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "barbarians"
+                )
+            })),
+            barbarians_picklist: WidgetHandle::new(barbarians_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "barbarians_picklist"
+                )
+            })),
+            barbarians_admin: WidgetHandle::new(barbarians_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "barbarians_admin"
+                )
+            })),
+            difficulty: WidgetHandle::new(difficulty.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "difficulty"
+                )
+            })),
+            difficulty_picklist: WidgetHandle::new(difficulty_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "difficulty_picklist"
+                )
+            })),
+            difficulty_admin: WidgetHandle::new(difficulty_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "difficulty_admin"
+                )
+            })),
+            speed: WidgetHandle::new(speed.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "speed"
+                )
+            })),
+            speed_picklist: WidgetHandle::new(speed_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "speed_picklist"
+                )
+            })),
+            speed_admin: WidgetHandle::new(speed_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "speed_admin"
+                )
+            })),
+            wrap_x: WidgetHandle::new(wrap_x.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "wrap_x"
+                )
+            })),
+            wrap_x_picklist: WidgetHandle::new(wrap_x_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "wrap_x_picklist"
+                )
+            })),
+            wrap_x_admin: WidgetHandle::new(wrap_x_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "wrap_x_admin"
+                )
+            })),
+            land_percent: WidgetHandle::new(land_percent.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "land_percent"
+                )
+            })),
+            land_percent_picklist: WidgetHandle::new(land_percent_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "land_percent_picklist"
+                )
+            })),
+            land_percent_admin: WidgetHandle::new(land_percent_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "land_percent_admin"
+                )
+            })),
+            inland_seas: WidgetHandle::new(inland_seas.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "inland_seas"
+                )
+            })),
+            inland_seas_picklist: WidgetHandle::new(inland_seas_picklist.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "inland_seas_picklist"
+                )
+            })),
+            inland_seas_admin: WidgetHandle::new(inland_seas_admin.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "inland_seas_admin"
+                )
+            })),
         }
     }
 }
@@ -1019,6 +1181,8 @@ pub struct CityInfoBarWindow {
     pub growth_text: WidgetHandle<Text>,
     pub production_progress_bar: WidgetHandle<ProgressBar>,
     pub production_text: WidgetHandle<Text>,
+    pub buy_button: WidgetHandle<Button>,
+    pub buy_text: WidgetHandle<Text>,
     pub health_text: WidgetHandle<Text>,
     pub health_tooltip_text: WidgetHandle<Text>,
     pub health_sign_text: WidgetHandle<Text>,
@@ -1042,6 +1206,8 @@ impl ::duit::InstanceHandle for CityInfoBarWindow {
         let mut growth_text = None;
         let mut production_progress_bar = None;
         let mut production_text = None;
+        let mut buy_button = None;
+        let mut buy_text = None;
         let mut health_text = None;
         let mut health_tooltip_text = None;
         let mut health_sign_text = None;
@@ -1061,6 +1227,8 @@ impl ::duit::InstanceHandle for CityInfoBarWindow {
                 "growth_text" => growth_text = Some(widget),
                 "production_progress_bar" => production_progress_bar = Some(widget),
                 "production_text" => production_text = Some(widget),
+                "buy_button" => buy_button = Some(widget),
+                "buy_text" => buy_text = Some(widget),
                 "health_text" => health_text = Some(widget),
                 "health_tooltip_text" => health_tooltip_text = Some(widget),
                 "health_sign_text" => health_sign_text = Some(widget),
@@ -1119,6 +1287,18 @@ impl ::duit::InstanceHandle for CityInfoBarWindow {
                     "production_text"
                 )
             })),
+            buy_button: WidgetHandle::new(buy_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "buy_button"
+                )
+            })),
+            buy_text: WidgetHandle::new(buy_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "buy_text"
+                )
+            })),
             health_text: WidgetHandle::new(health_text.unwrap_or_else(|| {
                 panic!(
                     "missing widget with ID '{}' (generated code not up to date)",
@@ -1188,6 +1368,7 @@ pub struct CityCultureWindow {
     pub culture_amount_text: WidgetHandle<Text>,
     pub culture_progress_bar: WidgetHandle<ProgressBar>,
     pub culture_text: WidgetHandle<Text>,
+    pub religions_text: WidgetHandle<Text>,
 }
 impl ::duit::InstanceHandle for CityCultureWindow {
     fn name() -> &'static str {
@@ -1197,11 +1378,13 @@ impl ::duit::InstanceHandle for CityCultureWindow {
         let mut culture_amount_text = None;
         let mut culture_progress_bar = None;
         let mut culture_text = None;
+        let mut religions_text = None;
         for (name, widget) in widget_handles {
             match name.as_str() {
                 "culture_amount_text" => culture_amount_text = Some(widget),
                 "culture_progress_bar" => culture_progress_bar = Some(widget),
                 "culture_text" => culture_text = Some(widget),
+                "religions_text" => religions_text = Some(widget),
                 _ => {}
             }
         }
@@ -1224,6 +1407,12 @@ impl ::duit::InstanceHandle for CityCultureWindow {
                     "culture_text"
                 )
             })),
+            religions_text: WidgetHandle::new(religions_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "religions_text"
+                )
+            })),
         }
     }
 }
@@ -1287,6 +1476,14 @@ pub struct CityEconomyWindow {
     pub beaker_output_text: WidgetHandle<Text>,
     pub gold_output_text: WidgetHandle<Text>,
     pub maintenance_text: WidgetHandle<Text>,
+    pub governor_focus_text: WidgetHandle<Text>,
+    pub balanced_button: WidgetHandle<Button>,
+    pub food_button: WidgetHandle<Button>,
+    pub production_button: WidgetHandle<Button>,
+    pub commerce_button: WidgetHandle<Button>,
+    pub great_growth_button: WidgetHandle<Button>,
+    pub avoid_unhappiness_button: WidgetHandle<Button>,
+    pub avoid_unhappiness_text: WidgetHandle<Text>,
 }
 impl ::duit::InstanceHandle for CityEconomyWindow {
     fn name() -> &'static str {
@@ -1296,11 +1493,27 @@ impl ::duit::InstanceHandle for CityEconomyWindow {
         let mut beaker_output_text = None;
         let mut gold_output_text = None;
         let mut maintenance_text = None;
+        let mut governor_focus_text = None;
+        let mut balanced_button = None;
+        let mut food_button = None;
+        let mut production_button = None;
+        let mut commerce_button = None;
+        let mut great_growth_button = None;
+        let mut avoid_unhappiness_button = None;
+        let mut avoid_unhappiness_text = None;
         for (name, widget) in widget_handles {
             match name.as_str() {
                 "beaker_output_text" => beaker_output_text = Some(widget),
                 "gold_output_text" => gold_output_text = Some(widget),
                 "maintenance_text" => maintenance_text = Some(widget),
+                "governor_focus_text" => governor_focus_text = Some(widget),
+                "balanced_button" => balanced_button = Some(widget),
+                "food_button" => food_button = Some(widget),
+                "production_button" => production_button = Some(widget),
+                "commerce_button" => commerce_button = Some(widget),
+                "great_growth_button" => great_growth_button = Some(widget),
+                "avoid_unhappiness_button" => avoid_unhappiness_button = Some(widget),
+                "avoid_unhappiness_text" => avoid_unhappiness_text = Some(widget),
                 _ => {}
             }
         }
@@ -1323,6 +1536,58 @@ impl ::duit::InstanceHandle for CityEconomyWindow {
                     "maintenance_text"
                 )
             })),
+            governor_focus_text: WidgetHandle::new(governor_focus_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "governor_focus_text"
+                )
+            })),
+            balanced_button: WidgetHandle::new(balanced_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "balanced_button"
+                )
+            })),
+            food_button: WidgetHandle::new(food_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "food_button"
+                )
+            })),
+            production_button: WidgetHandle::new(production_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "production_button"
+                )
+            })),
+            commerce_button: WidgetHandle::new(commerce_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "commerce_button"
+                )
+            })),
+            great_growth_button: WidgetHandle::new(great_growth_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "great_growth_button"
+                )
+            })),
+            avoid_unhappiness_button: WidgetHandle::new(avoid_unhappiness_button.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "avoid_unhappiness_button"
+                    )
+                },
+            )),
+            avoid_unhappiness_text: WidgetHandle::new(avoid_unhappiness_text.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "avoid_unhappiness_text"
+                    )
+                },
+            )),
         }
     }
 }
@@ -1619,11 +1884,22 @@ use duit::*;
 pub struct EconomyWindow {
     pub gold_text: WidgetHandle<Text>,
     pub expenses_text: WidgetHandle<Text>,
+    pub expenses_breakdown_text: WidgetHandle<Text>,
     pub revenue_text: WidgetHandle<Text>,
     pub beaker_percent_text: WidgetHandle<Text>,
     pub beaker_increment_button: WidgetHandle<Button>,
     pub beaker_decrement_button: WidgetHandle<Button>,
     pub beaker_output_text: WidgetHandle<Text>,
+    pub state_religion_text: WidgetHandle<Text>,
+    pub state_religion_button: WidgetHandle<Button>,
+    pub culture_percent_text: WidgetHandle<Text>,
+    pub culture_increment_button: WidgetHandle<Button>,
+    pub culture_decrement_button: WidgetHandle<Button>,
+    pub culture_output_text: WidgetHandle<Text>,
+    pub espionage_percent_text: WidgetHandle<Text>,
+    pub espionage_increment_button: WidgetHandle<Button>,
+    pub espionage_decrement_button: WidgetHandle<Button>,
+    pub espionage_output_text: WidgetHandle<Text>,
 }
 impl ::duit::InstanceHandle for EconomyWindow {
     fn name() -> &'static str {
@@ -1632,20 +1908,42 @@ impl ::duit::InstanceHandle for EconomyWindow {
     fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
         let mut gold_text = None;
         let mut expenses_text = None;
+        let mut expenses_breakdown_text = None;
         let mut revenue_text = None;
         let mut beaker_percent_text = None;
         let mut beaker_increment_button = None;
         let mut beaker_decrement_button = None;
         let mut beaker_output_text = None;
+        let mut state_religion_text = None;
+        let mut state_religion_button = None;
+        let mut culture_percent_text = None;
+        let mut culture_increment_button = None;
+        let mut culture_decrement_button = None;
+        let mut culture_output_text = None;
+        let mut espionage_percent_text = None;
+        let mut espionage_increment_button = None;
+        let mut espionage_decrement_button = None;
+        let mut espionage_output_text = None;
         for (name, widget) in widget_handles {
             match name.as_str() {
                 "gold_text" => gold_text = Some(widget),
                 "expenses_text" => expenses_text = Some(widget),
+                "expenses_breakdown_text" => expenses_breakdown_text = Some(widget),
                 "revenue_text" => revenue_text = Some(widget),
                 "beaker_percent_text" => beaker_percent_text = Some(widget),
                 "beaker_increment_button" => beaker_increment_button = Some(widget),
                 "beaker_decrement_button" => beaker_decrement_button = Some(widget),
                 "beaker_output_text" => beaker_output_text = Some(widget),
+                "state_religion_text" => state_religion_text = Some(widget),
+                "state_religion_button" => state_religion_button = Some(widget),
+                "culture_percent_text" => culture_percent_text = Some(widget),
+                "culture_increment_button" => culture_increment_button = Some(widget),
+                "culture_decrement_button" => culture_decrement_button = Some(widget),
+                "culture_output_text" => culture_output_text = Some(widget),
+                "espionage_percent_text" => espionage_percent_text = Some(widget),
+                "espionage_increment_button" => espionage_increment_button = Some(widget),
+                "espionage_decrement_button" => espionage_decrement_button = Some(widget),
+                "espionage_output_text" => espionage_output_text = Some(widget),
                 _ => {}
             }
         }
@@ -1662,6 +1960,14 @@ impl ::duit::InstanceHandle for EconomyWindow {
                     "expenses_text"
                 )
             })),
+            expenses_breakdown_text: WidgetHandle::new(expenses_breakdown_text.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "expenses_breakdown_text"
+                    )
+                },
+            )),
             revenue_text: WidgetHandle::new(revenue_text.unwrap_or_else(|| {
                 panic!(
                     "missing widget with ID '{}' (generated code not up to date)",
@@ -1696,6 +2002,407 @@ impl ::duit::InstanceHandle for EconomyWindow {
                     "beaker_output_text"
                 )
             })),
+            state_religion_text: WidgetHandle::new(state_religion_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "state_religion_text"
+                )
+            })),
+            state_religion_button: WidgetHandle::new(state_religion_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "state_religion_button"
+                )
+            })),
+            culture_percent_text: WidgetHandle::new(culture_percent_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "culture_percent_text"
+                )
+            })),
+            culture_increment_button: WidgetHandle::new(culture_increment_button.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "culture_increment_button"
+                    )
+                },
+            )),
+            culture_decrement_button: WidgetHandle::new(culture_decrement_button.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "culture_decrement_button"
+                    )
+                },
+            )),
+            culture_output_text: WidgetHandle::new(culture_output_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "culture_output_text"
+                )
+            })),
+            espionage_percent_text: WidgetHandle::new(espionage_percent_text.unwrap_or_else(
+                || {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "espionage_percent_text"
+                    )
+                },
+            )),
+            espionage_increment_button: WidgetHandle::new(
+                espionage_increment_button.unwrap_or_else(|| {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "espionage_increment_button"
+                    )
+                }),
+            ),
+            espionage_decrement_button: WidgetHandle::new(
+                espionage_decrement_button.unwrap_or_else(|| {
+                    panic!(
+                        "missing widget with ID '{}' (generated code not up to date)",
+                        "espionage_decrement_button"
+                    )
+                }),
+            ),
+            espionage_output_text: WidgetHandle::new(espionage_output_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "espionage_output_text"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CityBuildQueueWindow {
+    pub queue_list: WidgetHandle<Flex>,
+    pub options_list: WidgetHandle<Flex>,
+}
+impl ::duit::InstanceHandle for CityBuildQueueWindow {
+    fn name() -> &'static str {
+        "CityBuildQueueWindow"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut queue_list = None;
+        let mut options_list = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "queue_list" => queue_list = Some(widget),
+                "options_list" => options_list = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            queue_list: WidgetHandle::new(queue_list.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "queue_list"
+                )
+            })),
+            options_list: WidgetHandle::new(options_list.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "options_list"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CityBuildQueueEntry {
+    pub task_name: WidgetHandle<Text>,
+    pub repeat_button: WidgetHandle<Button>,
+    pub repeat_text: WidgetHandle<Text>,
+    pub move_up_button: WidgetHandle<Button>,
+    pub remove_button: WidgetHandle<Button>,
+}
+impl ::duit::InstanceHandle for CityBuildQueueEntry {
+    fn name() -> &'static str {
+        "CityBuildQueueEntry"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut task_name = None;
+        let mut repeat_button = None;
+        let mut repeat_text = None;
+        let mut move_up_button = None;
+        let mut remove_button = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "task_name" => task_name = Some(widget),
+                "repeat_button" => repeat_button = Some(widget),
+                "repeat_text" => repeat_text = Some(widget),
+                "move_up_button" => move_up_button = Some(widget),
+                "remove_button" => remove_button = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            task_name: WidgetHandle::new(task_name.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "task_name"
+                )
+            })),
+            repeat_button: WidgetHandle::new(repeat_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "repeat_button"
+                )
+            })),
+            repeat_text: WidgetHandle::new(repeat_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "repeat_text"
+                )
+            })),
+            move_up_button: WidgetHandle::new(move_up_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "move_up_button"
+                )
+            })),
+            remove_button: WidgetHandle::new(remove_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "remove_button"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CitySpecialistsWindow {
+    pub specialists_list: WidgetHandle<Flex>,
+}
+impl ::duit::InstanceHandle for CitySpecialistsWindow {
+    fn name() -> &'static str {
+        "CitySpecialistsWindow"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut specialists_list = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "specialists_list" => specialists_list = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            specialists_list: WidgetHandle::new(specialists_list.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "specialists_list"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CitySpecialistEntry {
+    pub specialist_text: WidgetHandle<Text>,
+    pub remove_button: WidgetHandle<Button>,
+    pub add_button: WidgetHandle<Button>,
+}
+impl ::duit::InstanceHandle for CitySpecialistEntry {
+    fn name() -> &'static str {
+        "CitySpecialistEntry"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut specialist_text = None;
+        let mut remove_button = None;
+        let mut add_button = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "specialist_text" => specialist_text = Some(widget),
+                "remove_button" => remove_button = Some(widget),
+                "add_button" => add_button = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            specialist_text: WidgetHandle::new(specialist_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "specialist_text"
+                )
+            })),
+            remove_button: WidgetHandle::new(remove_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "remove_button"
+                )
+            })),
+            add_button: WidgetHandle::new(add_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "add_button"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CivicsWindow {
+    pub civics_list: WidgetHandle<Flex>,
+    pub anarchy_text: WidgetHandle<Text>,
+    pub revolution_button: WidgetHandle<Button>,
+}
+impl ::duit::InstanceHandle for CivicsWindow {
+    fn name() -> &'static str {
+        "CivicsWindow"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut civics_list = None;
+        let mut anarchy_text = None;
+        let mut revolution_button = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "civics_list" => civics_list = Some(widget),
+                "anarchy_text" => anarchy_text = Some(widget),
+                "revolution_button" => revolution_button = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            civics_list: WidgetHandle::new(civics_list.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "civics_list"
+                )
+            })),
+            anarchy_text: WidgetHandle::new(anarchy_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "anarchy_text"
+                )
+            })),
+            revolution_button: WidgetHandle::new(revolution_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "revolution_button"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct CivicEntry {
+    pub category_text: WidgetHandle<Text>,
+    pub civic_button: WidgetHandle<Button>,
+    pub civic_text: WidgetHandle<Text>,
+}
+impl ::duit::InstanceHandle for CivicEntry {
+    fn name() -> &'static str {
+        "CivicEntry"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut category_text = None;
+        let mut civic_button = None;
+        let mut civic_text = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "category_text" => category_text = Some(widget),
+                "civic_button" => civic_button = Some(widget),
+                "civic_text" => civic_text = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            category_text: WidgetHandle::new(category_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "category_text"
+                )
+            })),
+            civic_button: WidgetHandle::new(civic_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "civic_button"
+                )
+            })),
+            civic_text: WidgetHandle::new(civic_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "civic_text"
+                )
+            })),
+        }
+    }
+}
+use duit::widgets::*;
+use duit::*;
+pub struct SpyDiscoveredPopup {
+    pub message_text: WidgetHandle<Text>,
+    pub close_button: WidgetHandle<Button>,
+}
+impl ::duit::InstanceHandle for SpyDiscoveredPopup {
+    fn name() -> &'static str {
+        "SpyDiscoveredPopup"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut message_text = None;
+        let mut close_button = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "message_text" => message_text = Some(widget),
+                "close_button" => close_button = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            message_text: WidgetHandle::new(message_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "message_text"
+                )
+            })),
+            close_button: WidgetHandle::new(close_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "close_button"
+                )
+            })),
+        }
+    }
+}
+pub struct TribalVillagePopup {
+    pub message_text: WidgetHandle<Text>,
+    pub close_button: WidgetHandle<Button>,
+}
+impl ::duit::InstanceHandle for TribalVillagePopup {
+    fn name() -> &'static str {
+        "TribalVillagePopup"
+    }
+    fn init(widget_handles: Vec<(String, WidgetPodHandle)>) -> Self {
+        let mut message_text = None;
+        let mut close_button = None;
+        for (name, widget) in widget_handles {
+            match name.as_str() {
+                "message_text" => message_text = Some(widget),
+                "close_button" => close_button = Some(widget),
+                _ => {}
+            }
+        }
+        Self {
+            message_text: WidgetHandle::new(message_text.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "message_text"
+                )
+            })),
+            close_button: WidgetHandle::new(close_button.unwrap_or_else(|| {
+                panic!(
+                    "missing widget with ID '{}' (generated code not up to date)",
+                    "close_button"
+                )
+            })),
         }
     }
 }
@@ -1,9 +1,10 @@
 use duit::{Ui, WindowId};
 use glam::vec2;
+use riposte_common::{espionage::SpyDiscovered, tribal_village::TribalVillageEntered};
 
 use crate::{
     game::Game,
-    generated::{ErrorPopup, GenesisPopup},
+    generated::{ErrorPopup, GenesisPopup, SpyDiscoveredPopup, TribalVillagePopup},
     ui::{Center, Z_POPUP},
 };
 
@@ -50,6 +51,52 @@ impl PopupWindows {
             .on_click(move || ClosePopup(window_id));
     }
 
+    pub fn show_spy_discovered_popup(&self, ui: &mut Ui, game: &Game, discovered: &SpyDiscovered) {
+        let (window, root) = ui.create_spec_instance::<SpyDiscoveredPopup>();
+
+        let adjective = game.player(discovered.perpetrator).civ().adjective.clone();
+        let city = game.city(discovered.city).name().to_owned();
+        let message = if discovered.success {
+            format!(
+                "A {} spy carried out {} in {} before we caught them!",
+                adjective,
+                discovered.mission.name(),
+                city
+            )
+        } else {
+            format!(
+                "We caught a {} spy attempting {} in {}.",
+                adjective,
+                discovered.mission.name(),
+                city
+            )
+        };
+        window.message_text.get_mut().set_text(text!("{}", message));
+
+        let window_id = ui.create_window(root, Center::with_size(vec2(400., 200.)), Z_POPUP);
+
+        window
+            .close_button
+            .get_mut()
+            .on_click(move || ClosePopup(window_id));
+    }
+
+    pub fn show_tribal_village_popup(&self, ui: &mut Ui, entered: &TribalVillageEntered) {
+        let (window, root) = ui.create_spec_instance::<TribalVillagePopup>();
+
+        window
+            .message_text
+            .get_mut()
+            .set_text(text!("{}", entered.outcome.description()));
+
+        let window_id = ui.create_window(root, Center::with_size(vec2(400., 200.)), Z_POPUP);
+
+        window
+            .close_button
+            .get_mut()
+            .on_click(move || ClosePopup(window_id));
+    }
+
     pub fn update(&mut self, ui: &mut Ui) {
         loop {
             match ui.pop_message::<ClosePopup>() {
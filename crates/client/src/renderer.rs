@@ -2,7 +2,7 @@ use std::f32::consts::{PI, TAU};
 
 use duit::Vec2;
 use dume::Canvas;
-use glam::{uvec2, vec2, UVec2};
+use glam::{ivec2, vec2, IVec2, UVec2};
 use riposte_common::Visibility;
 
 use crate::{
@@ -14,7 +14,7 @@ use crate::{
         grid_overlay::GridOverlayRenderer, improvement::ImprovementRenderer,
         resource::ResourceRenderer, staged_path::StagedPathOverlay, status_text::StatusTextOverlay,
         terrain::TerrainRenderer, tile_yield::TileYieldRenderer, tree::TreeRenderer,
-        unit::UnitRenderer,
+        tribal_village::TribalVillageRenderer, unit::UnitRenderer,
     },
 };
 
@@ -30,6 +30,7 @@ mod rivers;
 mod terrain;
 mod tile_yield;
 mod tree;
+mod tribal_village;
 mod unit;
 
 mod city_worked_tiles;
@@ -63,6 +64,7 @@ impl GameRenderer {
                 Box::new(ResourceRenderer::new(cx)),
                 Box::new(TreeRenderer::new(cx)),
                 Box::new(ImprovementRenderer::new(cx)),
+                Box::new(TribalVillageRenderer::new(cx)),
                 Box::new(CityRenderer::new(cx)),
                 Box::new(TileYieldRenderer::new(cx)),
                 Box::new(UnitRenderer::new(cx)),
@@ -85,19 +87,24 @@ impl GameRenderer {
 
     fn render_tiles(&mut self, game: &Game, cx: &mut Context) {
         // For each layer, we render each visibile tile.
-        let mut first_tile = game.view().tile_pos_for_screen_offset(Vec2::ZERO);
-        first_tile.x = first_tile.x.saturating_sub(1);
-        first_tile.y = first_tile.y.saturating_sub(1);
+        // Positions may extend past the edges of a wrapping map,
+        // in which case tiles from the other side are drawn.
+        let first_tile =
+            game.view().unwrapped_tile_pos_for_screen_offset(Vec2::ZERO) - IVec2::splat(1);
         let last_tile = game
             .view()
-            .tile_pos_for_screen_offset(game.view().window_size())
-            + UVec2::splat(1);
+            .unwrapped_tile_pos_for_screen_offset(game.view().window_size())
+            + IVec2::splat(1);
 
         game.view().transform_canvas(&mut *cx.canvas_mut());
         for layer in &mut self.tile_layers {
             for x in first_tile.x..=last_tile.x {
                 for y in first_tile.y..=last_tile.y {
-                    let pos = uvec2(x, y);
+                    let unwrapped_pos = ivec2(x, y);
+                    let pos = match game.map().wrap(unwrapped_pos) {
+                        Some(pos) => pos,
+                        None => continue,
+                    };
                     if let Ok(tile) = game.tile(pos) {
                         if game.the_player().visibility_at(pos) == Visibility::Hidden
                             && !game.cheat_mode
@@ -105,8 +112,10 @@ impl GameRenderer {
                             continue;
                         }
 
-                        let translation =
-                            game.view().screen_offset_for_tile_pos(pos) * game.view().zoom_factor();
+                        let translation = game
+                            .view()
+                            .screen_offset_for_unwrapped_tile_pos(unwrapped_pos)
+                            * game.view().zoom_factor();
                         cx.canvas_mut().translate(translation);
                         layer.render(game, cx, pos, &tile);
                         cx.canvas_mut().translate(-translation);
@@ -164,10 +164,11 @@ impl CityRenderer {
 
     fn render_production_progress_bar(&mut self, canvas: &mut Canvas, city: &City) {
         if let Some(task) = city.build_task() {
-            let progress = city.build_task_progress(task) as f32 / task.cost() as f32;
+            let progress =
+                city.build_task_progress(task) as f32 / city.build_task_cost(task) as f32;
             let projected_progress = (city.build_task_progress(task) + city.economy().hammer_yield)
                 as f32
-                / task.cost() as f32;
+                / city.build_task_cost(task) as f32;
             self.render_progress_bar(
                 canvas,
                 vec2(0., 80.0),
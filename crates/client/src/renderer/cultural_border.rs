@@ -69,7 +69,10 @@ impl TileRenderLayer for CulturalBorderRenderer {
         // Check adjacent tiles, and if they have different owners,
         // paint borders along those edges.
         for adjacent in adjacents {
-            let adjacent_tile_pos = (tile_pos.as_i32() + adjacent.offset).as_u32();
+            let adjacent_tile_pos = match game.map().wrap(tile_pos.as_i32() + adjacent.offset) {
+                Some(pos) => pos,
+                None => continue,
+            };
             let adjacent_tile = match game.tile(adjacent_tile_pos) {
                 Ok(t) => t,
                 Err(_) => continue,
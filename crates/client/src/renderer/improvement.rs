@@ -1,8 +1,8 @@
+use ahash::AHashMap;
 use duit::Vec2;
 use dume::{Canvas, TextureId};
 use glam::{vec2, UVec2};
 use palette::Srgba;
-use riposte_common::Improvement;
 
 use crate::{
     context::Context,
@@ -12,26 +12,25 @@ use crate::{
 use super::TileRenderLayer;
 
 pub struct ImprovementRenderer {
-    cottage: TextureId,
-    farm: TextureId,
-    pasture: TextureId,
-    mine: TextureId,
-    plantation: TextureId,
+    /// Icon textures keyed by improvement kind ID.
+    icons: AHashMap<String, TextureId>,
 }
 
 impl ImprovementRenderer {
     pub fn new(cx: &Context) -> Self {
         let canvas = cx.canvas();
-        Self {
-            cottage: canvas.context().texture_for_name("icon/cottage").unwrap(),
-            farm: canvas.context().texture_for_name("icon/farm").unwrap(),
-            pasture: canvas.context().texture_for_name("icon/pasture").unwrap(),
-            mine: canvas.context().texture_for_name("icon/mine").unwrap(),
-            plantation: canvas
-                .context()
-                .texture_for_name("icon/plantation")
-                .unwrap(),
-        }
+        let icons = cx
+            .registry()
+            .improvements()
+            .filter_map(|kind| {
+                let icon = kind.icon.as_ref()?;
+                Some((
+                    kind.id.clone(),
+                    canvas.context().texture_for_name(icon).unwrap(),
+                ))
+            })
+            .collect();
+        Self { icons }
     }
 
     fn render_improvement_icon(&self, canvas: &mut Canvas, icon: TextureId) {
@@ -49,14 +48,10 @@ impl ImprovementRenderer {
         let mut num_connections = 0;
         for adjacent_pos in game.tile_neighbors(tile_pos) {
             let adjacent_tile = game.tile(adjacent_pos).unwrap();
-            if adjacent_tile
-                .improvements()
-                .any(|i| matches!(i, Improvement::Road))
-                || game.city_at_pos(adjacent_pos).is_some()
-            {
+            if adjacent_tile.has_route() || game.city_at_pos(adjacent_pos).is_some() {
                 num_connections += 1;
 
-                let offset = adjacent_pos.as_i32() - tile_pos.as_i32();
+                let offset = game.map().offset(tile_pos, adjacent_pos);
                 canvas
                     .begin_path()
                     .move_to(Vec2::splat(PIXELS_PER_TILE / 2.))
@@ -79,15 +74,10 @@ impl TileRenderLayer for ImprovementRenderer {
     fn render(&mut self, game: &Game, cx: &mut Context, tile_pos: UVec2, tile: &Tile) {
         let mut canvas = cx.canvas_mut();
         for improvement in tile.improvements() {
-            match improvement {
-                Improvement::Farm => self.render_improvement_icon(&mut canvas, self.farm),
-                Improvement::Mine => self.render_improvement_icon(&mut canvas, self.mine),
-                Improvement::Road => self.render_road(game, tile_pos, &mut canvas),
-                Improvement::Pasture => self.render_improvement_icon(&mut canvas, self.pasture),
-                Improvement::Cottage(_) => self.render_improvement_icon(&mut canvas, self.cottage),
-                Improvement::Plantation => {
-                    self.render_improvement_icon(&mut canvas, self.plantation)
-                }
+            if improvement.is_route() {
+                self.render_road(game, tile_pos, &mut canvas);
+            } else if let Some(&icon) = self.icons.get(&improvement.kind().id) {
+                self.render_improvement_icon(&mut canvas, icon);
             }
         }
     }
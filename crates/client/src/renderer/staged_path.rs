@@ -6,16 +6,20 @@ use dume::{
 };
 use glam::UVec2;
 use palette::Srgba;
+use riposte_common::{path::Path, unit::UnitOrder};
 use splines::{Interpolation, Key, Spline};
 
 use crate::{
     context::Context,
-    game::{path::Path, selection::StagedPath, view::PIXELS_PER_TILE, Game},
+    game::{selection::StagedPath, view::PIXELS_PER_TILE, Game},
 };
 
 use super::OverlayRenderLayer;
 
 /// Paints the currently staged path for unit selection / movement.
+///
+/// If no path is staged, paints the path the selected units
+/// are following on the server as part of a move order.
 pub struct StagedPathOverlay {
     digit_blobs: AHashMap<u32, TextBlob>,
 
@@ -73,8 +77,12 @@ impl StagedPathOverlay {
             let next_point = points[1];
             let offset = Vec2::splat(PIXELS_PER_TILE / 2.);
 
-            let this_pos = game.view().screen_offset_for_tile_pos(this_point.pos) + offset;
-            let next_pos = game.view().screen_offset_for_tile_pos(next_point.pos) + offset;
+            // Place the next point relative to this one so that
+            // segments crossing the wrap seam stay short.
+            let this_tile = game.view().unwrapped_tile_pos(this_point.pos);
+            let next_tile = this_tile + game.map().offset(this_point.pos, next_point.pos);
+            let this_pos = game.view().screen_offset_for_unwrapped_tile_pos(this_tile) + offset;
+            let next_pos = game.view().screen_offset_for_unwrapped_tile_pos(next_tile) + offset;
 
             let this_has_marker = this_point.turn != next_point.turn;
             let is_last_point = i == path.points().len() - 2;
@@ -152,8 +160,21 @@ impl OverlayRenderLayer for StagedPathOverlay {
         match game.selection_driver().staged_path() {
             Some(StagedPath::Complete { path }) => self.render_complete_path(game, cx, path),
             Some(StagedPath::Unreachable { pos }) => self.render_unreachable_path(game, cx, *pos),
-            None => {}
+            None => {
+                if let Some(path) = committed_path(game) {
+                    self.render_complete_path(game, cx, &path);
+                }
+            }
         }
         cx.canvas_mut().reset_transform();
     }
 }
+
+/// Gets the path of the selected units' move order, if they have one.
+fn committed_path(game: &Game) -> Option<Path> {
+    let unit = *game.selected_units().get_all().first()?;
+    match game.unit(unit).order() {
+        Some(UnitOrder::MoveTo { path, .. }) => Some(path.clone()),
+        Some(UnitOrder::Explore | UnitOrder::AutomateWorker) | None => None,
+    }
+}
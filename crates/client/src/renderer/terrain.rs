@@ -29,6 +29,8 @@ impl TextureKey {
 pub struct TerrainRenderer {
     textures: AHashMap<TextureKey, TextureId>,
     flood_plains: TextureId,
+    /// Overlay textures for placed terrain features, keyed by feature ID.
+    feature_textures: AHashMap<String, TextureId>,
 }
 
 impl TerrainRenderer {
@@ -68,9 +70,24 @@ impl TerrainRenderer {
             .texture_for_name("texture/tile/flood_plains")
             .unwrap();
 
+        let feature_textures = cx
+            .registry()
+            .terrain_features()
+            .filter_map(|feature| {
+                let texture = feature.texture.as_ref()?;
+                let texture_id = cx
+                    .canvas()
+                    .context()
+                    .texture_for_name(texture)
+                    .unwrap_or_else(|_| panic!("missing feature texture '{}'", texture));
+                Some((feature.id.clone(), texture_id))
+            })
+            .collect();
+
         Self {
             textures,
             flood_plains,
+            feature_textures,
         }
     }
 }
@@ -100,5 +117,11 @@ impl TileRenderLayer for TerrainRenderer {
 
         let mut canvas = cx.canvas_mut();
         canvas.draw_sprite_with_rotation(texture, Vec2::ZERO, PIXELS_PER_TILE, rotation);
+
+        for feature in tile.placed_features() {
+            if let Some(&texture) = self.feature_textures.get(&feature.id) {
+                canvas.draw_sprite(texture, Vec2::ZERO, PIXELS_PER_TILE);
+            }
+        }
     }
 }
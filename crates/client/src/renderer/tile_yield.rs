@@ -55,7 +55,7 @@ impl TileRenderLayer for TileYieldRenderer {
         let spacing = 6.;
         let big_spacing = 20.;
 
-        let tile_yield = tile.tile_yield();
+        let tile_yield = tile.tile_yield(game.base());
         for _ in 0..tile_yield.food {
             self.icons.push(Icon {
                 sprite: self.bread,
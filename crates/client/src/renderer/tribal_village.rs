@@ -0,0 +1,39 @@
+use dume::TextureId;
+use glam::{vec2, UVec2};
+
+use crate::{
+    context::Context,
+    game::{view::PIXELS_PER_TILE, Game, Tile},
+};
+
+use super::TileRenderLayer;
+
+/// Renders tribal villages as a cluster of huts.
+pub struct TribalVillageRenderer {
+    hut: TextureId,
+}
+
+impl TribalVillageRenderer {
+    pub fn new(cx: &Context) -> Self {
+        let hut = cx
+            .canvas()
+            .context()
+            .texture_for_name("icon/house")
+            .unwrap();
+        Self { hut }
+    }
+}
+
+impl TileRenderLayer for TribalVillageRenderer {
+    fn render(&mut self, _game: &Game, cx: &mut Context, _tile_pos: UVec2, tile: &Tile) {
+        if !tile.has_tribal_village() {
+            return;
+        }
+
+        let hut_positions = [vec2(25., 45.), vec2(55., 40.), vec2(40., 60.)];
+        let mut canvas = cx.canvas_mut();
+        for pos in hut_positions {
+            canvas.draw_sprite(self.hut, pos, PIXELS_PER_TILE / 4.);
+        }
+    }
+}
@@ -41,7 +41,7 @@ impl Page {
     ) {
         match self {
             Page::Main(ui) => ui.update(cx, game, client),
-            Page::City(_) => {}
+            Page::City(ui) => ui.update(cx, game, client),
         }
     }
 
@@ -146,6 +146,12 @@ impl GameState {
                 self.handle_player_updated(cx, *player);
             }
             GameEvent::TechUnlocked { tech } => self.handle_tech_unlocked(cx, tech),
+            GameEvent::SpyDiscovered { discovered } => cx
+                .popup_windows()
+                .show_spy_discovered_popup(&mut cx.ui_mut(), &self.game, discovered),
+            GameEvent::TribalVillageEntered { entered } => cx
+                .popup_windows()
+                .show_tribal_village_popup(&mut cx.ui_mut(), entered),
             _ => {}
         }
     }
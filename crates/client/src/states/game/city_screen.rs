@@ -9,17 +9,20 @@ use crate::volumes;
 use crate::{context::Context, state::StateAttachment};
 
 use self::{
-    buildings::BuildingsScreen, culture::CultureScreen, economy::EconomyScreen,
-    info_bar::InfoBarScreen, resources::ResourcesScreen,
+    build_queue::BuildQueueScreen, buildings::BuildingsScreen, culture::CultureScreen,
+    economy::EconomyScreen, info_bar::InfoBarScreen, resources::ResourcesScreen,
+    specialists::SpecialistsScreen,
 };
 
 use riposte_common::CityId;
 
+mod build_queue;
 mod buildings;
 mod culture;
 mod economy;
 mod info_bar;
 mod resources;
+mod specialists;
 
 pub enum Action {
     Close,
@@ -31,11 +34,13 @@ pub struct CityScreen {
 
     city: CityId,
 
+    build_queue: BuildQueueScreen,
     buildings: BuildingsScreen,
     culture: CultureScreen,
     economy: EconomyScreen,
     info_bar: InfoBarScreen,
     resources: ResourcesScreen,
+    specialists: SpecialistsScreen,
 
     ambient_sound: SoundHandle,
 }
@@ -49,11 +54,13 @@ impl CityScreen {
         view.animate_to(cx, city.pos());
         view.animate_zoom_factor_to(cx, 1.);
 
+        let build_queue = BuildQueueScreen::new(cx, &attachment);
         let buildings = BuildingsScreen::new(cx, &attachment);
         let culture = CultureScreen::new(cx, &attachment);
         let economy = EconomyScreen::new(cx, &attachment);
         let info_bar = InfoBarScreen::new(cx, &attachment);
         let resources = ResourcesScreen::new(cx, &attachment);
+        let specialists = SpecialistsScreen::new(cx, &attachment);
 
         let ambient_sound = cx.audio().play_looping(
             "sound/ambient/city1",
@@ -64,17 +71,26 @@ impl CityScreen {
         let mut screen = Self {
             attachment,
             city: city.id(),
+            build_queue,
             buildings,
             culture,
             economy,
             info_bar,
             resources,
+            specialists,
             ambient_sound,
         };
         screen.update_info(cx, game);
         screen
     }
 
+    pub fn update(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>) {
+        self.build_queue.update(cx, game, client, self.city);
+        self.economy.update(cx, game, client, self.city);
+        self.info_bar.update(cx, game, client);
+        self.specialists.update(cx, game, client, self.city);
+    }
+
     pub fn handle_game_event(&mut self, cx: &Context, game: &Game, event: &GameEvent) {
         if let GameEvent::CityUpdated { city } = event {
             if *city == self.city {
@@ -85,11 +101,13 @@ impl CityScreen {
 
     fn update_info(&mut self, cx: &Context, game: &Game) {
         let city = game.city(self.city);
+        self.build_queue.update_info(cx, game, &city);
         self.buildings.update_info(cx, game, &city);
         self.culture.update_info(cx, game, &city);
         self.economy.update_info(cx, game, &city);
         self.info_bar.update_info(cx, game, &city);
         self.resources.update_info(cx, game, &city);
+        self.specialists.update_info(cx, game, &city);
     }
 
     pub fn handle_event(
@@ -0,0 +1,157 @@
+use duit::{Rect, Vec2, WindowPositioner};
+use glam::vec2;
+use riposte_common::CityId;
+use winit::event::ModifiersState;
+
+use crate::{
+    client::{Client, GameState},
+    context::Context,
+    game::{
+        city::{BuildTask, City},
+        Game,
+    },
+    generated::{CityBuildPromptOption, CityBuildQueueEntry, CityBuildQueueWindow},
+    state::StateAttachment,
+    tooltips,
+    ui::Z_FOREGROUND,
+};
+
+pub const SIZE: Vec2 = glam::const_vec2!([400., 400.]);
+
+struct Positioner;
+
+impl WindowPositioner for Positioner {
+    fn compute_position(&self, available_space: Vec2) -> Rect {
+        Rect::new(
+            vec2(available_space.x - SIZE.x, super::resources::SIZE.y),
+            vec2(SIZE.x, available_space.y - super::resources::SIZE.y),
+        )
+    }
+}
+
+enum Message {
+    Append(BuildTask),
+    Insert(usize, BuildTask),
+    ToggleRepeat(usize),
+    MoveUp(usize),
+    Remove(usize),
+}
+
+/// Lists the city's build queue and the tasks that can be added to it.
+pub struct BuildQueueScreen {
+    window: CityBuildQueueWindow,
+}
+
+impl BuildQueueScreen {
+    pub fn new(_cx: &Context, state: &StateAttachment) -> Self {
+        let (window, _) = state.create_window::<CityBuildQueueWindow, _>(Positioner, Z_FOREGROUND);
+
+        Self { window }
+    }
+
+    pub fn update(
+        &mut self,
+        cx: &Context,
+        game: &Game,
+        client: &mut Client<GameState>,
+        city_id: CityId,
+    ) {
+        while let Some(msg) = cx.ui_mut().pop_message::<Message>() {
+            match msg {
+                Message::Append(task) => client.append_build_queue(game, city_id, task, false),
+                Message::Insert(index, task) => {
+                    client.insert_build_queue(game, city_id, index, task, false)
+                }
+                Message::ToggleRepeat(index) => {
+                    let repeat = game
+                        .city(city_id)
+                        .build_queue()
+                        .get(index)
+                        .map(|entry| entry.repeat);
+                    if let Some(repeat) = repeat {
+                        client.set_build_task_repeat(game, city_id, index, !repeat);
+                    }
+                }
+                Message::MoveUp(index) => {
+                    client.reorder_build_queue(game, city_id, index, index - 1)
+                }
+                Message::Remove(index) => client.remove_build_queue(game, city_id, index),
+            }
+        }
+    }
+
+    pub fn update_info(&mut self, cx: &Context, game: &Game, city: &City) {
+        let mut entries = self.window.queue_list.get_mut();
+        entries.clear_children();
+
+        for (index, queued) in city.build_queue().iter().enumerate() {
+            let (entry, widget) = cx.ui_mut().create_spec_instance::<CityBuildQueueEntry>();
+
+            let turns = if index == 0 {
+                city.estimate_remaining_build_time()
+            } else {
+                city.estimate_build_time_for_task(&queued.task)
+            };
+            entry
+                .task_name
+                .get_mut()
+                .set_text(text!("{} ({} turns)", queued.task.name(), turns));
+
+            if let BuildTask::Unit(_) = &queued.task {
+                let repeat_text = if queued.repeat { "Repeat" } else { "Once" };
+                entry
+                    .repeat_text
+                    .get_mut()
+                    .set_text(text!("{}", repeat_text));
+                entry
+                    .repeat_button
+                    .get_mut()
+                    .on_click(move || Message::ToggleRepeat(index));
+            } else {
+                entry.repeat_button.hide();
+            }
+
+            if index == 0 {
+                entry.move_up_button.hide();
+            } else {
+                entry
+                    .move_up_button
+                    .get_mut()
+                    .on_click(move || Message::MoveUp(index));
+            }
+            entry
+                .remove_button
+                .get_mut()
+                .on_click(move || Message::Remove(index));
+
+            entries.add_child(widget);
+        }
+
+        let mut options = self.window.options_list.get_mut();
+        options.clear_children();
+
+        for task in city.possible_build_tasks(game.base()) {
+            let (option, widget) = cx.ui_mut().create_spec_instance::<CityBuildPromptOption>();
+
+            option.option_text.get_mut().set_text(text!(
+                "{} ({})",
+                task.name(),
+                city.estimate_build_time_for_task(&task)
+            ));
+            option
+                .tooltip_text
+                .get_mut()
+                .set_text(tooltips::build_task_tooltip(cx.registry(), &task));
+            // Shift-clicking builds the task next instead of last.
+            option.clickable.get_mut().on_click_with_mods(move |mods: ModifiersState| {
+                if mods.shift() {
+                    Message::Insert(0, task.clone())
+                } else {
+                    Message::Append(task.clone())
+                }
+            });
+
+            options.add_child(widget);
+        }
+    }
+}
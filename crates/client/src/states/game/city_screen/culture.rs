@@ -45,5 +45,25 @@ impl CultureScreen {
             city.num_culture(),
             city.culture_needed()
         ));
+
+        let religions: Vec<String> = city
+            .religions()
+            .map(|religion| {
+                if city.is_holy_city_of(religion) {
+                    format!("{} (Holy City)", religion.name)
+                } else {
+                    religion.name.clone()
+                }
+            })
+            .collect();
+        let religions = if religions.is_empty() {
+            "None".to_owned()
+        } else {
+            religions.join(", ")
+        };
+        self.window
+            .religions_text
+            .get_mut()
+            .set_text(text!("Religions: {}", religions));
     }
 }
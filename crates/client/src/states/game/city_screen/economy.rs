@@ -1,6 +1,8 @@
 use duit::{Align, Vec2};
+use riposte_common::{governor::GovernorFocus, CityId};
 
 use crate::{
+    client::{Client, GameState},
     context::Context,
     game::{city::City, Game},
     generated::CityEconomyWindow,
@@ -8,7 +10,12 @@ use crate::{
     ui::{AlignFixed, Z_FOREGROUND},
 };
 
-pub const SIZE: Vec2 = glam::const_vec2!([400., 300.]);
+pub const SIZE: Vec2 = glam::const_vec2!([400., 420.]);
+
+enum Message {
+    SetFocus(GovernorFocus),
+    ToggleAvoidUnhappiness,
+}
 
 pub struct EconomyScreen {
     window: CityEconomyWindow,
@@ -21,9 +28,53 @@ impl EconomyScreen {
             Z_FOREGROUND,
         );
 
+        window
+            .balanced_button
+            .get_mut()
+            .on_click(|| Message::SetFocus(GovernorFocus::Balanced));
+        window
+            .food_button
+            .get_mut()
+            .on_click(|| Message::SetFocus(GovernorFocus::Food));
+        window
+            .production_button
+            .get_mut()
+            .on_click(|| Message::SetFocus(GovernorFocus::Production));
+        window
+            .commerce_button
+            .get_mut()
+            .on_click(|| Message::SetFocus(GovernorFocus::Commerce));
+        window
+            .great_growth_button
+            .get_mut()
+            .on_click(|| Message::SetFocus(GovernorFocus::GreatGrowth));
+        window
+            .avoid_unhappiness_button
+            .get_mut()
+            .on_click(|| Message::ToggleAvoidUnhappiness);
+
         Self { window }
     }
 
+    pub fn update(
+        &mut self,
+        cx: &Context,
+        game: &Game,
+        client: &mut Client<GameState>,
+        city_id: CityId,
+    ) {
+        while let Some(msg) = cx.ui_mut().pop_message::<Message>() {
+            let mut settings = game.city(city_id).governor();
+            match msg {
+                Message::SetFocus(focus) => settings.focus = focus,
+                Message::ToggleAvoidUnhappiness => {
+                    settings.avoid_unhappiness = !settings.avoid_unhappiness
+                }
+            }
+            client.set_city_governor(game, city_id, settings);
+        }
+    }
+
     pub fn update_info(&mut self, _cx: &Context, _game: &Game, city: &City) {
         self.window
             .beaker_output_text
@@ -37,5 +88,20 @@ impl EconomyScreen {
             "Maintenance: -{} @icon[coin] / turn",
             city.economy().maintenance_cost
         ));
+
+        let governor = city.governor();
+        self.window
+            .governor_focus_text
+            .get_mut()
+            .set_text(text!("Focus: {}", governor.focus.name()));
+        let avoid_unhappiness = if governor.avoid_unhappiness {
+            "Avoid unhappiness: On"
+        } else {
+            "Avoid unhappiness: Off"
+        };
+        self.window
+            .avoid_unhappiness_text
+            .get_mut()
+            .set_text(text!("{}", avoid_unhappiness));
     }
 }
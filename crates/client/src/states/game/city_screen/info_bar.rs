@@ -1,6 +1,10 @@
 use duit::{Align, Vec2};
+use dume::Srgba;
+
+use riposte_common::CityId;
 
 use crate::{
+    client::{Client, GameState},
     context::Context,
     game::{city::City, Game},
     generated::CityInfoBarWindow,
@@ -12,7 +16,9 @@ use crate::{
     ui::{AlignFixed, Z_FOREGROUND},
 };
 
-pub const SIZE: Vec2 = glam::const_vec2!([600., 200.]);
+pub const SIZE: Vec2 = glam::const_vec2!([600., 240.]);
+
+struct BuyBuildTask(CityId);
 
 pub struct InfoBarScreen {
     window: CityInfoBarWindow,
@@ -28,7 +34,13 @@ impl InfoBarScreen {
         Self { window }
     }
 
-    pub fn update_info(&mut self, _cx: &Context, _game: &Game, city: &City) {
+    pub fn update(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>) {
+        while let Some(BuyBuildTask(city)) = cx.ui_mut().pop_message::<BuyBuildTask>() {
+            client.buy_build_task(game, city);
+        }
+    }
+
+    pub fn update_info(&mut self, _cx: &Context, game: &Game, city: &City) {
         self.window
             .city_name
             .get_mut()
@@ -49,8 +61,9 @@ impl InfoBarScreen {
             Some(task) => {
                 let progress = city.build_task_progress(task);
                 (
-                    progress as f32 / task.cost() as f32,
-                    (progress + city.economy().hammer_yield) as f32 / task.cost() as f32,
+                    progress as f32 / city.build_task_cost(task) as f32,
+                    (progress + city.economy().hammer_yield) as f32
+                        / city.build_task_cost(task) as f32,
                 )
             }
             None => (0., 0.),
@@ -93,6 +106,28 @@ impl InfoBarScreen {
             .get_mut()
             .set_text(text!("{}", production_text));
 
+        match city.build_task_buy_cost() {
+            Some(cost) if city.owner() == game.the_player().id() => {
+                self.window.buy_button.unhide();
+                let color = if game.the_player().gold() >= cost {
+                    Srgba::new(255, 255, 255, 255)
+                } else {
+                    Srgba::new(231, 60, 62, 255)
+                };
+                self.window.buy_text.get_mut().set_text(text!(
+                    "@color[{}][Buy ({} @icon[gold])]",
+                    color,
+                    cost
+                ));
+                let city_id = city.id();
+                self.window
+                    .buy_button
+                    .get_mut()
+                    .on_click(move || BuyBuildTask(city_id));
+            }
+            _ => self.window.buy_button.hide(),
+        }
+
         self.window
             .happy_text
             .get_mut()
@@ -0,0 +1,100 @@
+use duit::{Align, Vec2};
+use riposte_common::{
+    city::{Specialist, SpecialistOutput},
+    CityId,
+};
+
+use crate::{
+    client::{Client, GameState},
+    context::Context,
+    game::{city::City, Game},
+    generated::{CitySpecialistEntry, CitySpecialistsWindow},
+    state::StateAttachment,
+    ui::{AlignFixed, Z_FOREGROUND},
+};
+
+pub const SIZE: Vec2 = glam::const_vec2!([400., 250.]);
+
+struct SetSpecialists(Specialist, u32);
+
+/// Lists the city's specialists and allows the player
+/// to assign them.
+pub struct SpecialistsScreen {
+    window: CitySpecialistsWindow,
+}
+
+impl SpecialistsScreen {
+    pub fn new(_cx: &Context, state: &StateAttachment) -> Self {
+        let (window, _) = state.create_window::<CitySpecialistsWindow, _>(
+            AlignFixed::new(SIZE, Align::Center, Align::End),
+            Z_FOREGROUND,
+        );
+
+        Self { window }
+    }
+
+    pub fn update(
+        &mut self,
+        cx: &Context,
+        game: &Game,
+        client: &mut Client<GameState>,
+        city_id: CityId,
+    ) {
+        while let Some(SetSpecialists(specialist, count)) =
+            cx.ui_mut().pop_message::<SetSpecialists>()
+        {
+            client.set_city_specialists(game, city_id, specialist, count);
+        }
+    }
+
+    pub fn update_info(&mut self, cx: &Context, _game: &Game, city: &City) {
+        let mut entries = self.window.specialists_list.get_mut();
+        entries.clear_children();
+
+        for specialist in Specialist::all() {
+            let slots = city.specialist_slots(specialist);
+            if slots == 0 {
+                continue;
+            }
+
+            let (entry, widget) = cx.ui_mut().create_spec_instance::<CitySpecialistEntry>();
+
+            entry.specialist_text.get_mut().set_text(text!(
+                "{}: {} / {} ({})",
+                specialist.name(),
+                city.num_specialists(specialist),
+                slots,
+                output_text(specialist.output())
+            ));
+
+            let manual = city.num_manual_specialists(specialist);
+            entry
+                .add_button
+                .get_mut()
+                .on_click(move || SetSpecialists(specialist, manual + 1));
+            entry
+                .remove_button
+                .get_mut()
+                .on_click(move || SetSpecialists(specialist, manual.saturating_sub(1)));
+
+            entries.add_child(widget);
+        }
+    }
+}
+
+fn output_text(output: SpecialistOutput) -> String {
+    let mut parts = Vec::new();
+    if output.beakers > 0 {
+        parts.push(format!("+{} @icon{{beaker}}", output.beakers));
+    }
+    if output.gold > 0 {
+        parts.push(format!("+{} @icon{{gold}}", output.gold));
+    }
+    if output.culture > 0 {
+        parts.push(format!("+{} @icon{{culture}}", output.culture));
+    }
+    if output.hammers > 0 {
+        parts.push(format!("+{} @icon{{hammer}}", output.hammers));
+    }
+    parts.join(", ")
+}
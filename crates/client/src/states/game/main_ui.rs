@@ -12,11 +12,12 @@ use riposte_common::utils::VersionSnapshot;
 use riposte_common::CityId;
 
 use self::{
-    economy::EconomyScreen, info_bar::InfoBar, player_scores::PlayerScores, research::ResearchBar,
-    tile_tooltip::TileTooltip, turn_indicator::TurnIndicator, unit_actions::UnitActionBar,
-    unit_info::UnitInfo, unit_selection_bar::UnitSelectionBar,
+    civics::CivicsScreen, economy::EconomyScreen, info_bar::InfoBar, player_scores::PlayerScores,
+    research::ResearchBar, tile_tooltip::TileTooltip, turn_indicator::TurnIndicator,
+    unit_actions::UnitActionBar, unit_info::UnitInfo, unit_selection_bar::UnitSelectionBar,
 };
 
+mod civics;
 mod economy;
 mod info_bar;
 mod player_scores;
@@ -39,6 +40,7 @@ pub struct MainUi {
     unit_actions: UnitActionBar,
     research_bar: ResearchBar,
     economy_screen: EconomyScreen,
+    civics_screen: CivicsScreen,
     turn_indicator: TurnIndicator,
     info_bar: InfoBar,
     tile_tooltip: TileTooltip,
@@ -56,6 +58,7 @@ impl MainUi {
         let mut unit_actions = UnitActionBar::new(cx, &attachment);
         let mut research_bar = ResearchBar::new(cx, &attachment);
         let mut economy_screen = EconomyScreen::new(cx, &attachment);
+        let mut civics_screen = CivicsScreen::new(cx, &attachment);
         let mut turn_indicator = TurnIndicator::new(cx, &attachment);
         let mut info_bar = InfoBar::new(cx, &attachment);
         let tile_tooltip = TileTooltip::new(cx, &attachment);
@@ -66,6 +69,7 @@ impl MainUi {
         unit_actions.update_info(cx, game);
         research_bar.update_info(game);
         economy_screen.update_info(game);
+        civics_screen.update_info(cx, game);
         turn_indicator.update_info(game);
         info_bar.update_info(cx, game);
         player_scores.update_info(cx, game);
@@ -76,6 +80,7 @@ impl MainUi {
             unit_actions,
             research_bar,
             economy_screen,
+            civics_screen,
             turn_indicator,
             info_bar,
             tile_tooltip,
@@ -89,6 +94,7 @@ impl MainUi {
     pub fn update(&mut self, cx: &mut Context, game: &Game, client: &mut Client<GameState>) {
         self.unit_actions.update(cx, game, client);
         self.economy_screen.update(cx, game, client);
+        self.civics_screen.update(cx, game, client);
         self.turn_indicator.update(game);
         self.unit_selection_bar.update(cx, game);
         self.info_bar.update(cx, client);
@@ -103,6 +109,7 @@ impl MainUi {
     pub fn handle_game_event(&mut self, cx: &mut Context, game: &Game, event: &GameEvent) {
         self.research_bar.handle_game_event(cx, game, event);
         self.economy_screen.handle_game_event(cx, game, event);
+        self.civics_screen.handle_game_event(cx, game, event);
         self.turn_indicator.handle_game_event(game, event);
         self.info_bar.handle_game_event(cx, game, event);
         self.tile_tooltip.handle_game_event(game, event);
@@ -0,0 +1,150 @@
+use duit::{Align, Vec2};
+use glam::vec2;
+
+use crate::{
+    client::{Client, GameState},
+    context::Context,
+    game::{event::GameEvent, Game},
+    generated::{CivicEntry, CivicsWindow},
+    state::StateAttachment,
+    ui::{AlignFixed, Z_FOREGROUND},
+};
+
+use riposte_common::{
+    assets::Handle,
+    registry::{Civic, CivicCategory},
+};
+
+use super::economy;
+
+pub const SIZE: Vec2 = glam::const_vec2!([275., 230.]);
+
+enum Message {
+    CycleCivic(CivicCategory),
+    Revolution,
+}
+
+/// Lists the player's civics. Clicking on a civic cycles
+/// through the alternatives, and a revolution adopts them.
+pub struct CivicsScreen {
+    window: CivicsWindow,
+    /// Civics selected in the UI but not adopted yet.
+    pending: Vec<Handle<Civic>>,
+}
+
+impl CivicsScreen {
+    pub fn new(_cx: &Context, state: &StateAttachment) -> Self {
+        let (window, _) = state.create_window::<CivicsWindow, _>(
+            AlignFixed::new(SIZE, Align::Start, Align::Start)
+                .with_offset(vec2(0., economy::SIZE.y)),
+            Z_FOREGROUND,
+        );
+
+        window
+            .revolution_button
+            .get_mut()
+            .on_click(|| Message::Revolution);
+
+        Self {
+            window,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>) {
+        while let Some(msg) = cx.ui_mut().pop_message::<Message>() {
+            match msg {
+                Message::CycleCivic(category) => {
+                    let the_player = game.the_player();
+                    let options: Vec<_> = game
+                        .registry()
+                        .civics()
+                        .filter(|civic| civic.category == category)
+                        .filter(|civic| the_player.can_adopt_civic(game.registry(), civic))
+                        .cloned()
+                        .collect();
+                    let current = self
+                        .selected_civic(game, category)
+                        .and_then(|civic| options.iter().position(|c| *c == civic));
+                    let next = match current {
+                        Some(i) => options[(i + 1) % options.len()].clone(),
+                        None => match options.first() {
+                            Some(civic) => civic.clone(),
+                            None => continue,
+                        },
+                    };
+
+                    self.pending.retain(|civic| civic.category != category);
+                    if the_player.civic(category) != Some(&next) {
+                        self.pending.push(next);
+                    }
+                    drop(the_player);
+                    self.update_info(cx, game);
+                }
+                Message::Revolution => {
+                    if !self.pending.is_empty() {
+                        client.set_civics(self.pending.drain(..).collect());
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn handle_game_event(&mut self, cx: &Context, game: &Game, event: &GameEvent) {
+        if let GameEvent::PlayerUpdated { player } = event {
+            if game.the_player().id() == *player {
+                self.update_info(cx, game);
+            }
+        }
+    }
+
+    fn selected_civic(&self, game: &Game, category: CivicCategory) -> Option<Handle<Civic>> {
+        if let Some(civic) = self.pending.iter().find(|civic| civic.category == category) {
+            return Some(civic.clone());
+        }
+        game.the_player().civic(category).cloned()
+    }
+
+    pub fn update_info(&mut self, cx: &Context, game: &Game) {
+        let mut entries = self.window.civics_list.get_mut();
+        entries.clear_children();
+
+        for category in CivicCategory::all() {
+            let civic = match self.selected_civic(game, category) {
+                Some(civic) => civic,
+                None => continue,
+            };
+
+            let (entry, widget) = cx.ui_mut().create_spec_instance::<CivicEntry>();
+            entry
+                .category_text
+                .get_mut()
+                .set_text(text!("{}:", category.name()));
+            if self.pending.contains(&civic) {
+                entry
+                    .civic_text
+                    .get_mut()
+                    .set_text(text!("@color[68,194,113][{}]", civic.name));
+            } else {
+                entry.civic_text.get_mut().set_text(text!("{}", civic.name));
+            }
+            entry
+                .civic_button
+                .get_mut()
+                .on_click(move || Message::CycleCivic(category));
+
+            entries.add_child(widget);
+        }
+
+        let the_player = game.the_player();
+        let anarchy_text = if the_player.is_in_anarchy() {
+            format!("Anarchy: {} turns", the_player.anarchy_turns())
+        } else {
+            String::new()
+        };
+        self.window
+            .anarchy_text
+            .get_mut()
+            .set_text(text!("{}", anarchy_text));
+    }
+}
@@ -10,13 +10,16 @@ use crate::{
     ui::{AlignFixed, Z_FOREGROUND},
 };
 
-pub const SIZE: Vec2 = glam::const_vec2!([275., 150.]);
+use riposte_common::{player::EconomySlider, religion};
+
+pub const SIZE: Vec2 = glam::const_vec2!([275., 295.]);
 
 const SLIDER_INCREMENT: u32 = 10;
 
 enum Message {
-    IncrementBeakerPercent,
-    DecrementBeakerPercent,
+    IncrementSlider(EconomySlider),
+    DecrementSlider(EconomySlider),
+    CycleStateReligion,
 }
 
 /// Lists player economy information, like current gold.
@@ -31,14 +34,34 @@ impl EconomyScreen {
             Z_FOREGROUND,
         );
 
+        for (slider, increment_button, decrement_button) in [
+            (
+                EconomySlider::Beakers,
+                &window.beaker_increment_button,
+                &window.beaker_decrement_button,
+            ),
+            (
+                EconomySlider::Culture,
+                &window.culture_increment_button,
+                &window.culture_decrement_button,
+            ),
+            (
+                EconomySlider::Espionage,
+                &window.espionage_increment_button,
+                &window.espionage_decrement_button,
+            ),
+        ] {
+            increment_button
+                .get_mut()
+                .on_click(move || Message::IncrementSlider(slider));
+            decrement_button
+                .get_mut()
+                .on_click(move || Message::DecrementSlider(slider));
+        }
         window
-            .beaker_increment_button
-            .get_mut()
-            .on_click(|| Message::IncrementBeakerPercent);
-        window
-            .beaker_decrement_button
+            .state_religion_button
             .get_mut()
-            .on_click(|| Message::DecrementBeakerPercent);
+            .on_click(|| Message::CycleStateReligion);
 
         Self { window }
     }
@@ -46,16 +69,36 @@ impl EconomyScreen {
     pub fn update(&mut self, cx: &Context, game: &Game, client: &mut Client<GameState>) {
         while let Some(msg) = cx.ui_mut().pop_message::<Message>() {
             match msg {
-                Message::IncrementBeakerPercent => {
-                    client.set_economy_settings(
-                        (game.the_player().beaker_percent() as u32 + SLIDER_INCREMENT).min(100),
+                Message::IncrementSlider(slider) => {
+                    let the_player = game.the_player();
+                    let mut settings = the_player.economy_settings();
+                    settings.set_percent(
+                        slider,
+                        (settings.percent(slider) + SLIDER_INCREMENT)
+                            .min(the_player.max_slider_percent()),
                     );
+                    client.set_economy_settings(settings);
                 }
-                Message::DecrementBeakerPercent => {
-                    client.set_economy_settings(
-                        (game.the_player().beaker_percent() as u32)
-                            .saturating_sub(SLIDER_INCREMENT),
+                Message::DecrementSlider(slider) => {
+                    let mut settings = game.the_player().economy_settings();
+                    settings.set_percent(
+                        slider,
+                        settings.percent(slider).saturating_sub(SLIDER_INCREMENT),
                     );
+                    client.set_economy_settings(settings);
+                }
+                Message::CycleStateReligion => {
+                    // Cycle through no religion and each religion in our cities.
+                    let options =
+                        religion::adoptable_religions(game.base(), game.the_player().id());
+                    let next = match game.the_player().state_religion() {
+                        Some(current) => options
+                            .iter()
+                            .position(|r| r == current)
+                            .and_then(|i| options.get(i + 1)),
+                        None => options.first(),
+                    };
+                    client.set_state_religion(next);
                 }
             }
         }
@@ -101,6 +144,15 @@ impl EconomyScreen {
             negative_color,
             the_player.expenses()
         ));
+        self.window
+            .expenses_breakdown_text
+            .get_mut()
+            .set_text(text!(
+                "@size[12][Cities: {} | Units: {} | Supply: {}]",
+                the_player.city_maintenance(),
+                the_player.unit_upkeep(),
+                the_player.unit_supply_cost()
+            ));
         self.window.revenue_text.get_mut().set_text(text!(
             "@color[{}][Revenue:] {}",
             positive_color,
@@ -115,5 +167,32 @@ impl EconomyScreen {
             .beaker_percent_text
             .get_mut()
             .set_text(text!("@icon[beaker]: {}%", the_player.beaker_percent()));
+
+        self.window
+            .culture_output_text
+            .get_mut()
+            .set_text(text!("(+{} / turn)", the_player.culture_revenue()));
+        self.window
+            .culture_percent_text
+            .get_mut()
+            .set_text(text!("@icon[culture]: {}%", the_player.culture_percent()));
+
+        self.window
+            .espionage_output_text
+            .get_mut()
+            .set_text(text!("(+{} / turn)", the_player.espionage_revenue()));
+        self.window
+            .espionage_percent_text
+            .get_mut()
+            .set_text(text!("Espionage: {}%", the_player.espionage_percent()));
+
+        let state_religion = match the_player.state_religion() {
+            Some(religion) => religion.name.clone(),
+            None => "No State Religion".to_owned(),
+        };
+        self.window
+            .state_religion_text
+            .get_mut()
+            .set_text(text!("Religion: {}", state_religion));
     }
 }
@@ -46,7 +46,7 @@ impl InfoBar {
     pub fn update_info(&mut self, _cx: &Context, game: &Game) {
         self.window.turn_text.get_mut().set_text(text!(
             "{}    •    Turn {}    •    {:?} Era",
-            game.turn().year(),
+            game.turn().year(game.speed()),
             game.turn(),
             game.era()
         ));
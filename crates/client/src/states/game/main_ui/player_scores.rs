@@ -13,7 +13,7 @@ use crate::{
     utils::convert_color,
 };
 
-use riposte_common::PlayerId;
+use riposte_common::{diplomacy, player::PlayerKind, PlayerId};
 
 pub const WIDTH: f32 = 300.;
 
@@ -66,7 +66,8 @@ impl PlayerScores {
         let mut entries = self.window.scores_column.get_mut();
         entries.clear_children();
 
-        let mut players: Vec<_> = game.players().collect();
+        // The barbarians don't compete for score.
+        let mut players: Vec<_> = game.players().filter(|p| !p.is_barbarian()).collect();
         players.sort_by_key(|p| cmp::Reverse(p.score()));
 
         for player in players {
@@ -88,6 +89,17 @@ impl PlayerScores {
                 username.extend(text!("@color[207,69,32][(WAR)]"));
             }
 
+            // AI leaders show their attitude toward us.
+            if matches!(player.kind(), PlayerKind::Ai) && player.id() != game.the_player().id() {
+                let attitude = diplomacy::attitude(&player, &game.the_player());
+                username.extend(text!(" ({})", attitude));
+            }
+
+            let espionage_points = game.the_player().espionage_points_against(player.id());
+            if espionage_points > 0 {
+                username.extend(text!(" @size[12][{} EP]", espionage_points));
+            }
+
             let mut text = text!("{}:    ", player.score());
             text.extend(username);
 
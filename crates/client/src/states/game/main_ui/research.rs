@@ -46,8 +46,9 @@ impl ResearchBar {
                         research.name,
                         the_player.estimate_current_research_turns()
                     ),
-                    progress as f32 / research.cost as f32,
-                    (progress + the_player.beaker_revenue() as u32) as f32 / research.cost as f32,
+                    progress as f32 / the_player.tech_cost(research) as f32,
+                    (progress + the_player.beaker_revenue() as u32) as f32
+                        / the_player.tech_cost(research) as f32,
                 )
             }
             None => ("Research: None".to_owned(), 0., 0.),
@@ -17,11 +17,19 @@ use crate::{
     },
     generated::{UnitActionBarWindow, UnitActionButton},
     state::StateAttachment,
-    tooltips::improvement::build_improvement_tooltip,
+    tooltips::{improvement::build_improvement_tooltip, promotion::promotion_tooltip},
     ui::Z_FOREGROUND,
 };
 
-use riposte_common::{protocol::client::UnitAction, worker::WorkerTask, Improvement, UnitId};
+use riposte_common::{
+    assets::Handle,
+    espionage::EspionageMission,
+    protocol::client::UnitAction,
+    registry::{CapabilityType, Promotion, UnitKind},
+    unit::UnitOrder,
+    worker::{WorkerTask, CHOP_FOREST_HAMMERS},
+    UnitId,
+};
 
 use super::unit_info;
 
@@ -44,6 +52,13 @@ enum Message {
     FoundCity(UnitId),
     SetWorkerTask(UnitId, WorkerTask),
     Heal(UnitId),
+    Explore(UnitId),
+    AutomateWorker(UnitId),
+    Pillage(UnitId),
+    SpreadReligion(UnitId),
+    EspionageMission(UnitId, EspionageMission),
+    Upgrade(UnitId, Handle<UnitKind>),
+    Promote(UnitId, Handle<Promotion>),
 }
 
 struct PossibleUnitAction {
@@ -75,6 +90,26 @@ fn get_possible_unit_actions(game: &Game, unit: &Unit) -> Vec<PossibleUnitAction
         });
     }
 
+    // Units can explore on their own, and workers can be automated.
+    if !matches!(unit.order(), Some(UnitOrder::Explore)) {
+        actions.push(PossibleUnitAction {
+            text: "Explore".to_owned(),
+            message: Message::Explore(unit.id()),
+            tooltip: None,
+            is_recommended: false,
+        });
+    }
+    if unit.has_capability(CapabilityType::DoWork)
+        && !matches!(unit.order(), Some(UnitOrder::AutomateWorker))
+    {
+        actions.push(PossibleUnitAction {
+            text: "Automate".to_owned(),
+            message: Message::AutomateWorker(unit.id()),
+            tooltip: None,
+            is_recommended: false,
+        });
+    }
+
     // Settlers can found cities.
     if unit.can_found_city(game.base()).is_ok() {
         // Recommend founding a city  if the player has no cities
@@ -87,6 +122,72 @@ fn get_possible_unit_actions(game: &Game, unit: &Unit) -> Vec<PossibleUnitAction
         });
     }
 
+    // Military units can pillage enemy improvements.
+    if let Ok(improvement) = unit.can_pillage(game.base()) {
+        actions.push(PossibleUnitAction {
+            text: format!("Pillage {}", improvement.name()),
+            tooltip: Some(format!("+{} @icon{{gold}}", improvement.pillage_gold())),
+            message: Message::Pillage(unit.id()),
+            is_recommended: false,
+        });
+    }
+
+    // Missionaries can spread the state religion.
+    if let Ok(religion) = unit.can_spread_religion(game.base()) {
+        actions.push(PossibleUnitAction {
+            text: format!("Spread {}", religion.name),
+            tooltip: None,
+            message: Message::SpreadReligion(unit.id()),
+            is_recommended: true,
+        });
+    }
+
+    // Spies can perform missions in foreign cities.
+    for mission in EspionageMission::all() {
+        if unit.can_perform_mission(game.base(), mission).is_err() {
+            continue;
+        }
+        let victim = game.city_at_pos(unit.pos()).unwrap().owner();
+        let points = game.the_player().espionage_points_against(victim);
+        actions.push(PossibleUnitAction {
+            text: format!(
+                "{} ({}%)",
+                mission.name(),
+                (mission.success_chance(points) * 100.).round()
+            ),
+            tooltip: Some(format!(
+                "Costs {} espionage points (have {})",
+                mission.cost(),
+                points
+            )),
+            message: Message::EspionageMission(unit.id(), mission),
+            is_recommended: false,
+        });
+    }
+
+    // Units with enough experience can be promoted.
+    for promotion in unit.available_promotions(game.base()) {
+        actions.push(PossibleUnitAction {
+            text: format!("Promote: {}", promotion.name),
+            tooltip: Some(promotion_tooltip(&promotion)),
+            message: Message::Promote(unit.id(), promotion.clone()),
+            is_recommended: true,
+        });
+    }
+
+    // Units can be upgraded to newer kinds in their line.
+    for upgrade in unit.possible_upgrades(game.base()) {
+        actions.push(PossibleUnitAction {
+            text: format!("Upgrade to {}", upgrade.name),
+            tooltip: Some(format!(
+                "Costs {} @icon{{gold}}",
+                unit.upgrade_cost(&upgrade)
+            )),
+            message: Message::Upgrade(unit.id(), upgrade.clone()),
+            is_recommended: false,
+        });
+    }
+
     // Workers can build improvements.
     if let Some(_worker_cap) = unit
         .capabilities()
@@ -106,7 +207,7 @@ fn get_possible_unit_actions(game: &Game, unit: &Unit) -> Vec<PossibleUnitAction
                         .resource()
                         .map(|r| r.improvement == improvement.name())
                         .unwrap_or(false)
-                        || tile.resource().is_some() && matches!(improvement, Improvement::Road);
+                        || tile.resource().is_some() && improvement.is_route();
                     actions.push(PossibleUnitAction {
                         text: format!("Build {}", improvement.name()),
                         tooltip: Some(build_improvement_tooltip(&tile, &improvement)),
@@ -114,6 +215,21 @@ fn get_possible_unit_actions(game: &Game, unit: &Unit) -> Vec<PossibleUnitAction
                         is_recommended,
                     });
                 }
+                WorkerTask::ChopForest => actions.push(PossibleUnitAction {
+                    text: task.name(),
+                    tooltip: Some(format!(
+                        "+{} @icon{{hammer}} in the nearest city",
+                        CHOP_FOREST_HAMMERS
+                    )),
+                    message: Message::SetWorkerTask(unit.id(), task.clone()),
+                    is_recommended: false,
+                }),
+                WorkerTask::RemoveImprovement(_) => actions.push(PossibleUnitAction {
+                    text: task.name(),
+                    tooltip: None,
+                    message: Message::SetWorkerTask(unit.id(), task.clone()),
+                    is_recommended: false,
+                }),
             }
         }
     }
@@ -143,10 +259,31 @@ impl UnitActionBar {
                     client.set_worker_task(game, unit, &task);
                     game.selected_units_mut().clear();
                 }
+                Message::Upgrade(unit, kind) => {
+                    client.upgrade_unit(game, unit, &kind);
+                }
+                Message::Promote(unit, promotion) => {
+                    client.promote_unit(game, unit, &promotion);
+                }
                 Message::Heal(unit) => {
                     client.do_unit_action(game, unit, UnitAction::FortifyUntilHealed);
                     game.selected_units_mut().clear();
                 }
+                Message::Pillage(unit) => client.do_unit_action(game, unit, UnitAction::Pillage),
+                Message::SpreadReligion(unit) => {
+                    client.do_unit_action(game, unit, UnitAction::SpreadReligion)
+                }
+                Message::EspionageMission(unit, mission) => {
+                    client.do_unit_action(game, unit, UnitAction::EspionageMission(mission))
+                }
+                Message::Explore(unit) => {
+                    client.do_unit_action(game, unit, UnitAction::Explore);
+                    game.selected_units_mut().clear();
+                }
+                Message::AutomateWorker(unit) => {
+                    client.do_unit_action(game, unit, UnitAction::AutomateWorker);
+                    game.selected_units_mut().clear();
+                }
             }
         }
     }
@@ -162,6 +299,8 @@ impl UnitActionBar {
             let action = match key {
                 VirtualKeyCode::F => UnitAction::Fortify,
                 VirtualKeyCode::H => UnitAction::FortifyUntilHealed,
+                VirtualKeyCode::S => UnitAction::Sentry,
+                VirtualKeyCode::A => UnitAction::Alert,
                 VirtualKeyCode::Space => UnitAction::SkipTurn,
                 _ => return,
             };
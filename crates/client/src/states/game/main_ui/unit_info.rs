@@ -49,6 +49,13 @@ impl UnitInfo {
                     Movement: {}", t, unit.movement_text() },
                     None => format!("Movement: {}", unit.movement_text()),
                 };
+                if unit.kind().strength > 0. {
+                    text.push_str(&format!(
+                        "\nExperience: {} / {}",
+                        unit.experience(),
+                        unit.experience_needed_for_promotion()
+                    ));
+                }
                 if let Some(worker_cap) = unit.capabilities().find_map(|w| match w {
                     Capability::Worker(w) => Some(w),
                     _ => None,
@@ -28,11 +28,13 @@ use riposte_common::{
     bridge,
     lobby::{GameLobby, LobbySlot, SlotId, SlotPlayer},
     mapgen::{
-        ContinentsSettings, FlatSettings, LandGeneratorSettings, MapSize, MapgenSettings,
-        NumContinents,
+        ArchipelagoSettings, ContinentsSettings, FlatSettings, FractalSettings,
+        LandGeneratorSettings, LandPercent, MapSize, MapgenSettings, NumContinents,
+        PangaeaSettings,
     },
     protocol::lobby::{CreateSlot, DeleteSlot},
     registry::{Civilization, Leader},
+    GameSpeed,
 };
 use riposte_server::{Server, ServerConfig};
 use slotmap::Key;
@@ -136,6 +138,24 @@ impl GameLobbyState {
                     settings.land = LandGeneratorSettings::Flat(FlatSettings { lakes: true })
                 }))
             });
+            land_type_picklist.add_option(widget(Text::new(text!("Archipelago"))), || {
+                Message::UpdateSettings(Box::new(|settings| {
+                    settings.land = LandGeneratorSettings::Archipelago(ArchipelagoSettings {})
+                }))
+            });
+            land_type_picklist.add_option(widget(Text::new(text!("Pangaea"))), || {
+                Message::UpdateSettings(Box::new(|settings| {
+                    settings.land =
+                        LandGeneratorSettings::Pangaea(PangaeaSettings { inland_seas: true })
+                }))
+            });
+            land_type_picklist.add_option(widget(Text::new(text!("Fractal"))), || {
+                Message::UpdateSettings(Box::new(|settings| {
+                    settings.land = LandGeneratorSettings::Fractal(FractalSettings {
+                        land_percent: LandPercent::Forty,
+                    })
+                }))
+            });
 
             let mut map_size_picklist = window.map_size_picklist.get_mut();
             for map_size in MapSize::iter() {
@@ -149,6 +169,56 @@ impl GameLobbyState {
                 );
             }
 
+            let mut barbarians_picklist = window.barbarians_picklist.get_mut();
+            for barbarians in [true, false] {
+                barbarians_picklist.add_option(
+                    widget(Text::new(text!(
+                        "{}",
+                        if barbarians { "On" } else { "Off" }
+                    ))),
+                    move || {
+                        Message::UpdateSettings(Box::new(move |settings| {
+                            settings.barbarians = barbarians;
+                        }))
+                    },
+                );
+            }
+
+            let mut difficulty_picklist = window.difficulty_picklist.get_mut();
+            for handicap in cx.registry().handicaps() {
+                let id = handicap.id.clone();
+                difficulty_picklist.add_option(
+                    widget(Text::new(text!("{}", handicap.name))),
+                    move || {
+                        let id = id.clone();
+                        Message::UpdateSettings(Box::new(move |settings| {
+                            settings.difficulty = id;
+                        }))
+                    },
+                );
+            }
+
+            let mut speed_picklist = window.speed_picklist.get_mut();
+            for speed in GameSpeed::iter() {
+                speed_picklist.add_option(widget(Text::new(text!("{}", speed))), move || {
+                    Message::UpdateSettings(Box::new(move |settings| {
+                        settings.speed = speed;
+                    }))
+                });
+            }
+
+            let mut wrap_x_picklist = window.wrap_x_picklist.get_mut();
+            for wrap_x in [true, false] {
+                wrap_x_picklist.add_option(
+                    widget(Text::new(text!("{}", if wrap_x { "On" } else { "Off" }))),
+                    move || {
+                        Message::UpdateSettings(Box::new(move |settings| {
+                            settings.wrap_x = wrap_x;
+                        }))
+                    },
+                );
+            }
+
             let mut num_continents_picklist = window.num_continents_picklist.get_mut();
             for num in NumContinents::iter() {
                 num_continents_picklist.add_option(
@@ -163,6 +233,37 @@ impl GameLobbyState {
                     },
                 );
             }
+
+            let mut land_percent_picklist = window.land_percent_picklist.get_mut();
+            for percent in LandPercent::iter() {
+                land_percent_picklist.add_option(
+                    widget(Text::new(text!("{}%", percent as u32))),
+                    move || {
+                        Message::UpdateSettings(Box::new(move |settings| {
+                            if let LandGeneratorSettings::Fractal(settings) = &mut settings.land {
+                                settings.land_percent = percent;
+                            }
+                        }))
+                    },
+                );
+            }
+
+            let mut inland_seas_picklist = window.inland_seas_picklist.get_mut();
+            for inland_seas in [true, false] {
+                inland_seas_picklist.add_option(
+                    widget(Text::new(text!(
+                        "{}",
+                        if inland_seas { "On" } else { "Off" }
+                    ))),
+                    move || {
+                        Message::UpdateSettings(Box::new(move |settings| {
+                            if let LandGeneratorSettings::Pangaea(settings) = &mut settings.land {
+                                settings.inland_seas = inland_seas;
+                            }
+                        }))
+                    },
+                );
+            }
         }
 
         let mut state = Self {
@@ -313,22 +414,62 @@ impl GameLobbyState {
             return;
         }
 
-        let (mut map_size, mut land_type, num_continents) = if self.our_slot().is_admin() {
+        let (
+            mut map_size,
+            mut land_type,
+            num_continents,
+            land_percent,
+            inland_seas,
+            mut barbarians,
+            mut difficulty,
+            mut speed,
+            mut wrap_x,
+        ) = if self.our_slot().is_admin() {
             (
                 self.window.map_size_admin.get_mut(),
                 self.window.land_type_admin.get_mut(),
                 &self.window.num_continents_admin,
+                &self.window.land_percent_admin,
+                &self.window.inland_seas_admin,
+                self.window.barbarians_admin.get_mut(),
+                self.window.difficulty_admin.get_mut(),
+                self.window.speed_admin.get_mut(),
+                self.window.wrap_x_admin.get_mut(),
             )
         } else {
             (
                 self.window.map_size.get_mut(),
                 self.window.land_type.get_mut(),
                 &self.window.num_continents,
+                &self.window.land_percent,
+                &self.window.inland_seas,
+                self.window.barbarians.get_mut(),
+                self.window.difficulty.get_mut(),
+                self.window.speed.get_mut(),
+                self.window.wrap_x.get_mut(),
             )
         };
 
         map_size.set_text(text!("Map Size: {:?}", self.settings.size));
         land_type.set_text(text!("Land Type: {}", self.settings.land));
+        barbarians.set_text(text!(
+            "Barbarians: {}",
+            if self.settings.barbarians {
+                "On"
+            } else {
+                "Off"
+            }
+        ));
+        let difficulty_name = match cx.registry().handicap(&self.settings.difficulty) {
+            Ok(handicap) => handicap.name.clone(),
+            Err(_) => self.settings.difficulty.clone(),
+        };
+        difficulty.set_text(text!("Difficulty: {}", difficulty_name));
+        speed.set_text(text!("Game Speed: {}", self.settings.speed));
+        wrap_x.set_text(text!(
+            "World Wrap: {}",
+            if self.settings.wrap_x { "On" } else { "Off" }
+        ));
 
         if let LandGeneratorSettings::Continents(settings) = &self.settings.land {
             num_continents
@@ -339,6 +480,25 @@ impl GameLobbyState {
             self.window.num_continents_picklist.hide();
         };
 
+        if let LandGeneratorSettings::Fractal(settings) = &self.settings.land {
+            land_percent
+                .get_mut()
+                .set_text(text!("Land: {}%", settings.land_percent as u32));
+            self.window.land_percent_picklist.unhide();
+        } else {
+            self.window.land_percent_picklist.hide();
+        };
+
+        if let LandGeneratorSettings::Pangaea(settings) = &self.settings.land {
+            inland_seas.get_mut().set_text(text!(
+                "Inland Seas: {}",
+                if settings.inland_seas { "On" } else { "Off" }
+            ));
+            self.window.inland_seas_picklist.unhide();
+        } else {
+            self.window.inland_seas_picklist.hide();
+        };
+
         if self.our_slot().is_admin() {
             self.window.non_admin_group.hide();
         } else {
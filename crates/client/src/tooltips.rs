@@ -8,6 +8,7 @@ pub mod building;
 pub mod happiness;
 pub mod health;
 pub mod improvement;
+pub mod promotion;
 pub mod resource;
 pub mod sickness;
 pub mod tech;
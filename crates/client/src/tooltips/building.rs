@@ -93,5 +93,12 @@ fn building_effect_line(effect: &BuildingEffect) -> Text {
         }
         BuildingEffectType::Anger => text!("+{}@icon[anger]", effect.amount),
         BuildingEffectType::Sickness => text!("+{}@icon[sick]", effect.amount),
+        BuildingEffectType::StartingExperience => {
+            text!("+{} experience for new units", effect.amount)
+        }
+        BuildingEffectType::ScientistSlots => text!("+{} scientist slots", effect.amount),
+        BuildingEffectType::MerchantSlots => text!("+{} merchant slots", effect.amount),
+        BuildingEffectType::ArtistSlots => text!("+{} artist slots", effect.amount),
+        BuildingEffectType::EngineerSlots => text!("+{} engineer slots", effect.amount),
     }
 }
@@ -12,6 +12,8 @@ pub fn happiness_tooltip(city: &City) -> String {
             HappinessSource::DifficultyBonus => "Long Live Life!",
             HappinessSource::Buildings => "Buildings are making us happy!",
             HappinessSource::Resources => "We live in luxury!",
+            HappinessSource::StateReligion => "We follow the one true faith!",
+            HappinessSource::Civics => "Our government serves us well!",
         };
         lines.push(format!("+{}@icon{{happy}}: \"{}\"", count, reason));
     }
@@ -12,7 +12,7 @@ pub fn health_tooltip(city: &City) -> String {
             HealthSource::DifficultyBonus => "from free health bonus",
             HealthSource::Resources => "from resources",
             HealthSource::Buildings => "from buildings",
-            HealthSource::Forests => "from local forests",
+            HealthSource::Terrain => "from local terrain",
             HealthSource::FreshWater => "from fresh water",
         };
         lines.push(format!("+{}@icon{{health}} {}", count, reason));
@@ -1,19 +1,24 @@
-use riposte_common::{utils::merge_lines, Improvement};
+use riposte_common::{utils::merge_lines, Improvement, Yield};
 
 use crate::game::Tile;
 
 pub fn build_improvement_tooltip(tile: &Tile, improvement: &Improvement) -> String {
     let mut lines = Vec::new();
-    match improvement {
-        Improvement::Farm => lines.push("+1 @icon{bread}".to_owned()),
-        Improvement::Mine => lines.push("+1 @icon{hammer}".to_owned()),
-        Improvement::Road => lines.push("Unit movement costs reduced by 1/3".to_owned()),
-        Improvement::Pasture => {}
-        Improvement::Cottage(_) => lines.extend(vec![
-            "+1 @icon{coin}".to_owned(),
-            "Grows for increased bonuses".to_owned(),
-        ]),
-        Improvement::Plantation => {}
+    let kind = improvement.kind();
+
+    lines.extend(yield_bonus_lines(&kind.yield_bonus, ""));
+    for bonus in &kind.tech_yield_bonuses {
+        lines.extend(yield_bonus_lines(
+            &bonus.yield_bonus,
+            &format!(" with {}", bonus.tech),
+        ));
+    }
+
+    if kind.is_route {
+        lines.push("Unit movement costs reduced by 1/3".to_owned());
+    }
+    if kind.upgrade.is_some() {
+        lines.push("Grows for increased bonuses".to_owned());
     }
 
     if let Some(resource) = tile.resource() {
@@ -27,3 +32,15 @@ pub fn build_improvement_tooltip(tile: &Tile, improvement: &Improvement) -> Stri
 
     merge_lines(&lines)
 }
+
+fn yield_bonus_lines(bonus: &Yield, suffix: &str) -> Vec<String> {
+    [
+        (bonus.food, "bread"),
+        (bonus.hammers, "hammer"),
+        (bonus.commerce, "coin"),
+    ]
+    .into_iter()
+    .filter(|(amount, _)| *amount > 0)
+    .map(|(amount, icon)| format!("+{} @icon{{{}}}{}", amount, icon, suffix))
+    .collect()
+}
@@ -0,0 +1,26 @@
+use riposte_common::{
+    registry::{Promotion, PromotionEffectType},
+    utils::merge_lines,
+};
+
+pub fn promotion_tooltip(promotion: &Promotion) -> String {
+    let mut lines = Vec::new();
+    for effect in &promotion.effects {
+        let line = match effect.typ {
+            PromotionEffectType::StrengthPercent => {
+                format!("+{}% @icon{{strength}}", effect.amount)
+            }
+            PromotionEffectType::AgainstUnitCategory => match effect.unit_category {
+                Some(category) => format!("+{}% against {:?} units", effect.amount, category),
+                None => continue,
+            },
+            PromotionEffectType::Movement => format!("+{} @icon{{movement}}", effect.amount),
+            PromotionEffectType::HealingPercent => {
+                format!("+{}% healing per turn", effect.amount)
+            }
+        };
+        lines.push(line);
+    }
+
+    merge_lines(&lines)
+}
@@ -11,7 +11,8 @@ pub fn sickness_tooltip(city: &City) -> String {
         let reason = match source {
             SicknessSource::Population => "from overpopulation",
             SicknessSource::Buildings => "from buildings",
-            SicknessSource::FloodPlains => "from flood plains",
+            SicknessSource::Terrain => "from local terrain",
+            SicknessSource::PoisonedWater => "from poisoned water",
         };
         lines.push(format!("+{}@icon{{sick}} {}", count, reason));
     }
@@ -1,16 +1,24 @@
 use dume::Text;
 use riposte_common::{
-    registry::{Registry, Tech},
+    registry::{Registry, Tech, TechEffect, TechEffectType},
+    tile::ROUTE_TILES_PER_MOVEMENT,
     utils::{article, delimit_string},
 };
 
-use crate::{game::Game, utils::merge_text_lines};
+use crate::{
+    game::Game,
+    utils::{delimit_text, merge_text_lines},
+};
 
 pub fn tech_tooltip(registry: &Registry, game: &Game, tech: &Tech) -> Text {
     let mut lines = Vec::new();
 
     // Basic info
-    lines.push(text!("{}, {} @icon[beaker]", tech.name, tech.cost));
+    lines.push(text!(
+        "{}, {} @icon[beaker]",
+        tech.name,
+        game.the_player().tech_cost(tech)
+    ));
 
     let civ = game.the_player().civ().clone();
 
@@ -52,8 +60,34 @@ pub fn tech_tooltip(registry: &Registry, game: &Game, tech: &Tech) -> Text {
     }
 
     // Unlocks improvement...
-    for improvement in &tech.unlocks_improvements {
-        lines.push(text!("Can build {} {}", article(improvement), improvement));
+    for improvement in registry.improvements() {
+        if !improvement.only_by_upgrade && improvement.required_tech == tech.name {
+            lines.push(text!(
+                "Can build {} {}",
+                article(&improvement.name),
+                improvement.name
+            ));
+        }
+    }
+
+    // Tech effects...
+    for effect in &tech.effects {
+        lines.extend(tech_effect_line(registry, effect));
+    }
+    for resource in registry.resources() {
+        if resource.revealed_by == tech.name {
+            lines.push(text!("Reveals {}", resource.name));
+        }
+    }
+    for civic in registry.civics() {
+        if civic.required_tech.as_ref() == Some(&tech.name) {
+            lines.push(text!("Enables {} civic", civic.name));
+        }
+    }
+    for religion in registry.religions() {
+        if religion.founded_by == tech.name {
+            lines.push(text!("First to discover founds {}", religion.name));
+        }
     }
 
     // Tech leads to...
@@ -70,3 +104,43 @@ pub fn tech_tooltip(registry: &Registry, game: &Game, tech: &Tech) -> Text {
 
     merge_text_lines(lines)
 }
+
+fn tech_effect_line(registry: &Registry, effect: &TechEffect) -> Option<Text> {
+    let target_name = |target: &str| match effect.typ {
+        TechEffectType::FeatureYield => registry
+            .terrain_feature(target)
+            .map(|f| f.name.clone())
+            .unwrap_or_else(|_| target.to_owned()),
+        _ => registry
+            .improvement(target)
+            .map(|i| i.name.clone())
+            .unwrap_or_else(|_| target.to_owned()),
+    };
+
+    match effect.typ {
+        TechEffectType::FeatureYield | TechEffectType::ImprovementYield => {
+            let target = target_name(effect.target.as_deref()?);
+            let y = effect.yield_bonus;
+            let mut parts = Vec::new();
+            if y.food > 0 {
+                parts.push(text!("+{}@icon[bread]", y.food));
+            }
+            if y.hammers > 0 {
+                parts.push(text!("+{}@icon[hammer]", y.hammers));
+            }
+            if y.commerce > 0 {
+                parts.push(text!("+{}@icon[coin]", y.commerce));
+            }
+            let mut line = delimit_text(parts, text!(", "));
+            line.extend(text!(" from {}", target));
+            Some(line)
+        }
+        TechEffectType::WorkerSpeedPercent => {
+            Some(text!("Workers build {}% faster", effect.amount))
+        }
+        TechEffectType::RouteMovement => Some(text!(
+            "Units move {} tiles per movement point on roads",
+            ROUTE_TILES_PER_MOVEMENT + effect.amount
+        )),
+    }
+}
@@ -6,8 +6,9 @@ use glam::UVec2;
 use lexical::{format::STANDARD, WriteFloatOptions};
 use riposte_common::assets::Handle;
 use riposte_common::registry::{Resource, UnitKind};
+use riposte_common::tile::FRESH_WATER_FEATURE;
 use riposte_common::unit::MovementPoints;
-use riposte_common::{Improvement, PlayerId, UnitId, Visibility, Yield};
+use riposte_common::{PlayerId, UnitId, Visibility, Yield};
 
 use crate::game::unit::{Capability, Unit};
 use crate::game::{Game, Tile};
@@ -18,10 +19,10 @@ pub fn tile_tooltip(game: &Game, tile: &Tile, pos: UVec2) -> Text {
 
     lines.extend(culture_lines(game, tile));
     lines.extend(units_lines(game, tile, pos));
-    lines.push(header(tile));
-    lines.extend(defense_bonus_line(tile));
-    lines.extend(improvement_lines(tile));
-    lines.push(yield_description_line(&tile.tile_yield()));
+    lines.push(header(game, tile));
+    lines.extend(defense_bonus_line(game, tile));
+    lines.extend(improvement_lines(game, tile));
+    lines.push(yield_description_line(&tile.tile_yield(game.base())));
     if let Some(resource) = tile.resource() {
         lines.extend(resource_line(game, tile, &resource));
     }
@@ -183,25 +184,27 @@ fn units_lines(game: &Game, _tile: &Tile, pos: UVec2) -> Vec<Text> {
     lines
 }
 
-fn header(tile: &Tile) -> Text {
-    let mut header = text!("{:?}", tile.terrain());
-    if tile.is_hilled() {
-        header.extend(text!(", Hills"));
-    }
-    if tile.is_forested() {
-        header.extend(text!(", Forest"));
-    }
-    if tile.is_flood_plains() {
-        header.extend(text!(", Flood Plains"));
+fn header(game: &Game, tile: &Tile) -> Text {
+    let mut header = match game.registry().terrain(tile.terrain()) {
+        Ok(terrain) => text!("{}", terrain.name),
+        Err(_) => text!("{:?}", tile.terrain()),
+    };
+    for feature in tile.features(game.registry()) {
+        if feature.id != FRESH_WATER_FEATURE {
+            header.extend(text!(", {}", feature.name));
+        }
     }
     if tile.has_fresh_water() {
         header.extend(text!("\nFresh Water"));
     }
+    if tile.has_tribal_village() {
+        header.extend(text!("\nTribal Village"));
+    }
     header
 }
 
-fn defense_bonus_line(tile: &Tile) -> Option<Text> {
-    let bonus = tile.defense_bonus();
+fn defense_bonus_line(game: &Game, tile: &Tile) -> Option<Text> {
+    let bonus = tile.defense_bonus(game.base());
     if bonus > 0 {
         Some(text!("Defense bonus: +{}%", bonus))
     } else {
@@ -250,29 +253,24 @@ fn resource_line(game: &Game, tile: &Tile, resource: &Resource) -> Option<Text>
     Some(line)
 }
 
-fn improvement_lines(tile: &Tile) -> Vec<Text> {
+fn improvement_lines(game: &Game, tile: &Tile) -> Vec<Text> {
     let mut lines = Vec::new();
 
     for improvement in tile.improvements() {
-        match improvement {
-            Improvement::Cottage(cottage) => {
-                lines.push(text!("{:?}", cottage.level()));
-                if !tile.is_worked() {
-                    lines
-                        .last_mut()
-                        .unwrap()
-                        .extend(text!(" (City must work to grow)"));
-                } else {
-                    if let Some(next_level) = cottage.next_level() {
-                        lines.last_mut().unwrap().extend(text!(
-                            " ({} turns to {:?})",
-                            cottage.turns_to_next_level(),
-                            next_level
-                        ));
-                    }
-                }
+        lines.push(text!("{}", improvement.name()));
+        if let Some(next_upgrade) = improvement.next_upgrade(game.registry()) {
+            if !tile.is_worked() {
+                lines
+                    .last_mut()
+                    .unwrap()
+                    .extend(text!(" (City must work to grow)"));
+            } else if let Some(turns) = improvement.turns_to_upgrade() {
+                lines.last_mut().unwrap().extend(text!(
+                    " ({} turns to {})",
+                    turns,
+                    next_upgrade.name
+                ));
             }
-            _ => lines.push(text!("{}", improvement.name())),
         }
     }
 
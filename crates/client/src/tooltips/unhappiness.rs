@@ -11,6 +11,8 @@ pub fn unhappiness_tooltip(city: &City) -> String {
         let reason = match source {
             AngerSource::Population => "It's too crowded!",
             AngerSource::Undefended => "We fear for our safety!",
+            AngerSource::ReligiousDiscord => "Our faith is not respected!",
+            AngerSource::Revolt => "Down with the government!",
         };
 
         lines.push(format!("+{} @icon{{unhappy}}: \"{}\"", count, reason));
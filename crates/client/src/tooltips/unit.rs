@@ -43,6 +43,10 @@ pub fn unit_tooltip(registry: &Registry, unit: &UnitKind) -> Text {
                 "Can bombard city defenses ({}%percent / turn)",
                 unit.max_bombard_per_turn
             ),
+            CapabilityType::SpreadReligion => "Can spread the state religion".to_owned(),
+            CapabilityType::Espionage => {
+                "Invisible to other players; can perform espionage missions".to_owned()
+            }
         };
         lines.push(text!("{}", line));
     }
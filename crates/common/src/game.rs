@@ -1,13 +1,22 @@
 //! The Riposte game logic.
 
+pub mod barbarians;
 pub mod city;
+pub mod civics;
 pub mod combat;
 pub mod culture;
+pub mod diplomacy;
+pub mod espionage;
 pub mod event;
+pub mod governor;
 pub mod improvement;
+pub mod order;
+pub mod path;
 pub mod player;
+pub mod religion;
 pub mod river;
 pub mod tile;
+pub mod tribal_village;
 pub mod unit;
 pub mod worker;
 pub mod world;
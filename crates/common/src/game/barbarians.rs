@@ -0,0 +1,575 @@
+//! The barbarians: a player at war with everyone that spawns
+//! roaming units in the wilderness and occasionally founds cities. (Server only.)
+//!
+//! Barbarian units are controlled by a simple aggressive AI that attacks
+//! anything it has a fair chance of beating and otherwise wanders toward
+//! the nearest enemy it can see.
+
+use std::iter;
+
+use ahash::AHashMap;
+use glam::{uvec2, UVec2};
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    assets::Handle,
+    city::BuildTask,
+    event::Event,
+    lobby::SlotId,
+    path::{Pathfinder, UnitMovementRules},
+    player::PlayerKind,
+    registry::{Handicap, Tech, UnitKind},
+    City, Game, Player, PlayerId, Unit, UnitId, Visibility,
+};
+
+/// Turn on which barbarians start to appear.
+const FIRST_SPAWN_TURN: u32 = 20;
+/// Chance per turn that a new barbarian unit appears
+/// while the barbarians are below their unit limit,
+/// before scaling by the difficulty level.
+const SPAWN_CHANCE: f64 = 0.3;
+/// Number of land tiles per barbarian unit allowed on the map in
+/// the Ancient era. Each later era allows another such amount.
+const LAND_TILES_PER_UNIT: usize = 100;
+/// Number of land tiles per barbarian city allowed on the map.
+const LAND_TILES_PER_CITY: usize = 400;
+/// Minimum distance of spawned units from any city.
+const MIN_SPAWN_DISTANCE: i32 = 6;
+/// Chance per turn that a barbarian unit on a suitable
+/// tile settles down and founds a city.
+const CITY_FOUNDING_CHANCE: f64 = 0.02;
+/// Maximum distance at which barbarian units go after enemies.
+const HUNT_DISTANCE: i32 = 8;
+/// Minimum ratio of attacking to defending strength
+/// for a barbarian unit to attack.
+const MIN_ATTACK_ODDS: f64 = 0.8;
+/// Maximum number of tiles a barbarian unit moves in one turn.
+const MAX_STEPS_PER_TURN: usize = 8;
+
+/// Adds the barbarian player to the game, at war with all other players.
+///
+/// `handicap` is the game's difficulty level, which controls how aggressive
+/// the barbarians are.
+///
+/// Returns `None` if the registry has no barbarian civilization.
+pub fn add_barbarian_player(game: &mut Game, handicap: Handle<Handicap>) -> Option<PlayerId> {
+    let civ = game.registry().barbarian_civ()?.clone();
+    let id = game.new_player_id();
+    let leader_name = civ.leaders[0].name.clone();
+    let player = Player::new(
+        game,
+        id,
+        SlotId::default(),
+        PlayerKind::Barbarian,
+        civ,
+        leader_name,
+        handicap,
+        game.map().width(),
+        game.map().height(),
+    );
+    game.add_player(player);
+
+    let others: Vec<PlayerId> = game
+        .players()
+        .map(|p| p.id())
+        .filter(|&p| p != id)
+        .collect();
+    for other in others {
+        game.player_mut(id).declare_permanent_war_on(game, other);
+    }
+
+    Some(id)
+}
+
+/// Gets the barbarian player, if the game has one.
+pub fn barbarian_player(game: &Game) -> Option<PlayerId> {
+    game.players().find(|p| p.is_barbarian()).map(|p| p.id())
+}
+
+/// Runs the barbarians' turn: new units and cities appear, and
+/// barbarian units move and attack.
+///
+/// Should be called at the start of each turn.
+pub fn play_turn(game: &mut Game) {
+    let barbarian = match barbarian_player(game) {
+        Some(player) => player,
+        None => return,
+    };
+
+    update_techs(game, barbarian);
+    update_city_production(game, barbarian);
+
+    if game.turn().get() >= FIRST_SPAWN_TURN {
+        let num_land_tiles = count_land_tiles(game);
+        spawn_units(game, barbarian, num_land_tiles);
+        found_cities(game, barbarian, num_land_tiles);
+    }
+    game.run_deferred_functions();
+
+    let units = game.player(barbarian).units().to_vec();
+    for unit in units {
+        move_unit(game, unit);
+    }
+    game.run_deferred_functions();
+}
+
+/// Grants the barbarians every tech known by at
+/// least half of the other living players.
+fn update_techs(game: &Game, barbarian: PlayerId) {
+    let mut num_players = 0;
+    let mut counts: AHashMap<Handle<Tech>, usize> = AHashMap::new();
+    for player in game.players() {
+        if player.is_barbarian() || !player.is_alive() {
+            continue;
+        }
+        num_players += 1;
+        for tech in player.unlocked_techs() {
+            *counts.entry(tech.clone()).or_default() += 1;
+        }
+    }
+
+    let mut player = game.player_mut(barbarian);
+    for (tech, count) in counts {
+        if count * 2 >= num_players && !player.has_unlocked_tech(&tech) {
+            player.grant_tech(tech);
+        }
+    }
+}
+
+/// Gets the land combat units the barbarians can field,
+/// the strongest first.
+fn available_unit_kinds(game: &Game, barbarian: PlayerId) -> Vec<Handle<UnitKind>> {
+    let player = game.player(barbarian);
+    let mut kinds: Vec<Handle<UnitKind>> = game
+        .registry()
+        .unit_kinds()
+        .filter(|kind| {
+            kind.strength > 0.
+                && !kind.ship
+                && kind.capabilities.is_empty()
+                && kind.only_for_civs.is_empty()
+                && kind.techs.iter().all(|tech| {
+                    game.registry()
+                        .tech(tech)
+                        .map(|tech| player.has_unlocked_tech(&tech))
+                        .unwrap_or(false)
+                })
+        })
+        .cloned()
+        .collect();
+    kinds.sort_by(|a, b| b.strength.total_cmp(&a.strength));
+    kinds
+}
+
+/// Picks a unit kind for a new barbarian unit: one of
+/// those close in strength to the best available.
+fn choose_unit_kind(game: &Game, barbarian: PlayerId) -> Option<Handle<UnitKind>> {
+    let kinds = available_unit_kinds(game, barbarian);
+    let best_strength = kinds.first()?.strength;
+    let candidates: Vec<_> = kinds
+        .into_iter()
+        .filter(|kind| kind.strength * 3. >= best_strength * 2.)
+        .collect();
+    let mut rng = game.rng();
+    candidates.choose(&mut *rng).cloned()
+}
+
+/// Makes idle barbarian cities train units.
+fn update_city_production(game: &Game, barbarian: PlayerId) {
+    for &city_id in game.player(barbarian).cities() {
+        if game.city(city_id).build_task().is_some() {
+            continue;
+        }
+        if let Some(kind) = choose_unit_kind(game, barbarian) {
+            game.city_mut(city_id).set_build_task(BuildTask::Unit(kind));
+            game.push_event(Event::CityChanged(city_id));
+        }
+    }
+}
+
+fn count_land_tiles(game: &Game) -> usize {
+    let mut count = 0;
+    for x in 0..game.map().width() {
+        for y in 0..game.map().height() {
+            if game.tile(uvec2(x, y)).unwrap().terrain().is_passable() {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Gets the distance to the nearest city, in tiles.
+fn distance_to_nearest_city(game: &Game, pos: UVec2) -> i32 {
+    game.cities()
+        .map(|city| game.map().tile_distance(city.pos(), pos) as i32)
+        .min()
+        .unwrap_or(i32::MAX)
+}
+
+/// Returns whether a tile is unowned land that another player
+/// has explored but that none can currently see.
+///
+/// Barbarians don't appear in parts of the map no one has explored yet.
+fn is_wilderness(game: &Game, pos: UVec2) -> bool {
+    let tile = game.tile(pos).unwrap();
+    if !tile.terrain().is_passable() || tile.owner(game).is_some() {
+        return false;
+    }
+
+    let mut is_fogged = false;
+    for player in game.players().filter(|p| !p.is_barbarian()) {
+        match player.visibility_at(pos) {
+            Visibility::Visible => return false,
+            Visibility::Fogged => is_fogged = true,
+            Visibility::Hidden => {}
+        }
+    }
+    is_fogged
+}
+
+/// Spawns a new unit in the wilderness, far from cities.
+///
+/// The number of barbarian units scales with the amount
+/// of land on the map, the most advanced player's era and
+/// the difficulty level.
+fn spawn_units(game: &mut Game, barbarian: PlayerId, num_land_tiles: usize) {
+    let era = game
+        .players()
+        .filter(|p| !p.is_barbarian())
+        .map(|p| p.era())
+        .max();
+    let era = match era {
+        Some(era) => era,
+        None => return,
+    };
+    let aggression = game
+        .player(barbarian)
+        .handicap()
+        .barbarian_aggression_percent;
+    let max_units =
+        num_land_tiles / LAND_TILES_PER_UNIT * (era as usize + 1) * aggression as usize / 100;
+    let spawn_chance = (SPAWN_CHANCE * aggression as f64 / 100.).min(1.);
+    if game.player(barbarian).units().len() >= max_units || !game.rng().gen_bool(spawn_chance) {
+        return;
+    }
+
+    let mut candidates = Vec::new();
+    for x in 0..game.map().width() {
+        for y in 0..game.map().height() {
+            let pos = uvec2(x, y);
+            if is_wilderness(game, pos)
+                && game.units_by_pos(pos).next().is_none()
+                && distance_to_nearest_city(game, pos) >= MIN_SPAWN_DISTANCE
+            {
+                candidates.push(pos);
+            }
+        }
+    }
+
+    let pos = match candidates.choose(&mut *game.rng()) {
+        Some(&pos) => pos,
+        None => return,
+    };
+    let kind = match choose_unit_kind(game, barbarian) {
+        Some(kind) => kind,
+        None => return,
+    };
+
+    log::info!("Barbarian {} appeared at {:?}", kind.name, pos);
+    let id = game.new_unit_id();
+    game.add_unit(Unit::new(id, barbarian, kind, pos));
+    game.defer(move |game| game.player_mut(barbarian).update_visibility(game));
+}
+
+/// Gets the tiles next to `pos` where barbarians could appear.
+pub(crate) fn free_tiles_near(game: &Game, pos: UVec2) -> Vec<UVec2> {
+    game.map()
+        .adjacent(pos)
+        .into_iter()
+        .filter(|&p| {
+            game.tile(p).unwrap().terrain().is_passable()
+                && game.units_by_pos(p).next().is_none()
+                && game.city_at_pos(p).is_none()
+        })
+        .collect()
+}
+
+/// Spawns up to `count` barbarian units next to `pos`.
+pub(crate) fn spawn_units_near(game: &mut Game, pos: UVec2, count: usize) {
+    let barbarian = match barbarian_player(game) {
+        Some(player) => player,
+        None => return,
+    };
+
+    let mut tiles = free_tiles_near(game, pos);
+    tiles.shuffle(&mut *game.rng());
+    for pos in tiles.into_iter().take(count) {
+        let kind = match choose_unit_kind(game, barbarian) {
+            Some(kind) => kind,
+            None => return,
+        };
+        let id = game.new_unit_id();
+        game.add_unit(Unit::new(id, barbarian, kind, pos));
+    }
+    game.defer(move |game| game.player_mut(barbarian).update_visibility(game));
+}
+
+/// Occasionally has a barbarian unit found a city where it stands.
+///
+/// The unit stays in the new city to defend it.
+fn found_cities(game: &mut Game, barbarian: PlayerId, num_land_tiles: usize) {
+    let max_cities = num_land_tiles / LAND_TILES_PER_CITY;
+    if game.player(barbarian).cities().len() >= max_cities {
+        return;
+    }
+
+    let founder = game
+        .player(barbarian)
+        .units()
+        .iter()
+        .copied()
+        .find(|&unit| {
+            let pos = game.unit(unit).pos();
+            is_wilderness(game, pos)
+                && game
+                    .map()
+                    .big_fat_cross(pos)
+                    .into_iter()
+                    .chain(iter::once(pos))
+                    .all(|p| game.city_at_pos(p).is_none())
+        });
+    let founder = match founder {
+        Some(unit) => unit,
+        None => return,
+    };
+    if !game.rng().gen_bool(CITY_FOUNDING_CHANCE) {
+        return;
+    }
+
+    let pos = game.unit(founder).pos();
+    let id = game.new_city_id();
+    let city = {
+        let owner = game.player(barbarian);
+        City::new(id, &owner, pos, owner.next_city_name(game), game)
+    };
+    log::info!("The barbarians founded {}", city.name());
+    game.add_city(city);
+    game.player_mut(barbarian).update_economy(game);
+    game.push_event(Event::PlayerChanged(barbarian));
+
+    // Garrison the city instead of roaming.
+    game.unit_mut(founder).fortify_forever();
+    game.push_event(Event::UnitChanged(founder));
+}
+
+/// Moves a barbarian unit: it attacks adjacent enemies it has a fair
+/// chance of beating, otherwise heads for the nearest enemy city or unit
+/// it can see, and wanders around if there is none.
+///
+/// The last unit in a barbarian city stays there to defend it.
+fn move_unit(game: &mut Game, unit: UnitId) {
+    if !game.is_unit_valid(unit) || game.unit(unit).strength() == 0. || is_last_defender(game, unit)
+    {
+        return;
+    }
+
+    let owner = game.unit(unit).owner();
+    let mut pathfinder = Pathfinder::new();
+    for _ in 0..MAX_STEPS_PER_TURN {
+        if !game.is_unit_valid(unit) || !game.unit(unit).has_movement_left() {
+            break;
+        }
+
+        if let Some(target) = best_attack(game, unit) {
+            game.unit_mut(unit).move_to(game, target);
+            game.run_deferred_functions();
+            // Winners of combat advance into the defeated unit's tile.
+            if game.is_unit_valid(unit) && game.unit(unit).pos() == target {
+                game.push_event(Event::UnitsMovedByOrder(vec![unit], target));
+            }
+            break;
+        }
+
+        let pos = game.unit(unit).pos();
+        let next = match nearest_enemy(game, unit) {
+            Some(target) => {
+                let rules = UnitMovementRules::new(owner, iter::once(game.unit(unit)));
+                pathfinder
+                    .compute_shortest_path(game, &rules, pos, target)
+                    .and_then(|mut path| path.next())
+                    .map(|point| point.pos)
+            }
+            None => None,
+        };
+        let next = match next.or_else(|| wander_target(game, unit)) {
+            Some(next) => next,
+            None => break,
+        };
+
+        // Don't attack against bad odds; wait for a better chance.
+        if game.unit(unit).attack_target(game, next).is_some()
+            || !game.unit(unit).can_move_to(game, next)
+        {
+            break;
+        }
+
+        game.unit_mut(unit).move_to(game, next);
+        game.push_event(Event::UnitsMovedByOrder(vec![unit], next));
+        game.run_deferred_functions();
+    }
+
+    if game.is_unit_valid(unit) {
+        game.push_event(Event::UnitChanged(unit));
+    }
+}
+
+/// Returns whether the unit is the only one left in a barbarian city.
+fn is_last_defender(game: &Game, unit: UnitId) -> bool {
+    let pos = game.unit(unit).pos();
+    game.city_at_pos(pos).is_some() && game.units_by_pos(pos).count() == 1
+}
+
+/// Finds the adjacent tile the unit has the best odds of attacking,
+/// or an undefended enemy city it can capture.
+fn best_attack(game: &Game, unit: UnitId) -> Option<UVec2> {
+    let unit = game.unit(unit);
+    let player = game.player(unit.owner());
+    let mut best: Option<(UVec2, f64)> = None;
+    for pos in game.map().adjacent(unit.pos()) {
+        if !unit.can_move_to(game, pos) {
+            continue;
+        }
+
+        let odds = match unit.attack_target(game, pos) {
+            Some(defender) => {
+                let defender = game.unit(defender);
+                unit.modified_attacking_strength()
+                    / defender.modified_defending_strength(game, &unit)
+            }
+            None => match game.city_at_pos(pos) {
+                Some(city) if player.is_at_war_with(city.owner()) => f64::INFINITY,
+                _ => continue,
+            },
+        };
+        if odds >= MIN_ATTACK_ODDS && !matches!(best, Some((_, best_odds)) if best_odds >= odds) {
+            best = Some((pos, odds));
+        }
+    }
+    best.map(|(pos, _)| pos)
+}
+
+/// Finds the closest enemy city or unit within reach that
+/// the barbarians can see.
+fn nearest_enemy(game: &Game, unit: UnitId) -> Option<UVec2> {
+    let unit = game.unit(unit);
+    let player = game.player(unit.owner());
+    let distance = |pos: UVec2| game.map().tile_distance(pos, unit.pos()) as i32;
+
+    let cities = game
+        .cities()
+        .filter(|city| {
+            player.is_at_war_with(city.owner())
+                && player.visibility_at(city.pos()) != Visibility::Hidden
+        })
+        .map(|city| city.pos());
+    let units = game
+        .units()
+        .filter(|u| u.strength() > 0. && player.is_at_war_with(u.owner()) && player.can_see_unit(u))
+        .map(|u| u.pos());
+
+    cities
+        .chain(units)
+        .filter(|&pos| distance(pos) <= HUNT_DISTANCE)
+        .min_by_key(|&pos| distance(pos))
+}
+
+/// Picks a random adjacent tile to wander to.
+fn wander_target(game: &Game, unit: UnitId) -> Option<UVec2> {
+    let unit = game.unit(unit);
+    let options: Vec<UVec2> = game
+        .map()
+        .adjacent(unit.pos())
+        .into_iter()
+        .filter(|&pos| {
+            unit.can_move_to(game, pos)
+                && unit.attack_target(game, pos).is_none()
+                && game.city_at_pos(pos).is_none()
+        })
+        .collect();
+    let mut rng = game.rng();
+    options.choose(&mut *rng).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing;
+
+    use super::*;
+
+    fn add_barbarians(game: &mut Game) -> PlayerId {
+        let handicap = game.registry().handicap("noble").unwrap();
+        add_barbarian_player(game, handicap).unwrap()
+    }
+
+    #[test]
+    fn barbarians_are_at_war_and_learn_common_techs() {
+        let mut game = testing::new_game(8, 8);
+        let players = ["america", "arabia", "aztec"].map(|civ| testing::add_player(&mut game, civ));
+        let barbarian = add_barbarians(&mut game);
+        assert_eq!(barbarian_player(&game), Some(barbarian));
+
+        for player in players {
+            assert!(game.player(player).is_at_war_with(barbarian));
+            game.player_mut(player).make_peace_with(&game, barbarian);
+            assert!(game.player(barbarian).is_at_war_with(player));
+        }
+
+        // Techs known by at least half of the players are granted.
+        update_techs(&game, barbarian);
+        let registry = game.registry();
+        let barbarian = game.player(barbarian);
+        for tech in ["The Wheel", "Agriculture", "Hunting"] {
+            assert!(barbarian.has_unlocked_tech(&registry.tech(tech).unwrap()));
+        }
+        assert!(!barbarian.has_unlocked_tech(&registry.tech("Mysticism").unwrap()));
+    }
+
+    #[test]
+    fn barbarians_take_undefended_cities_but_keep_their_last_defender() {
+        let mut game = testing::new_game(10, 5);
+        let player = testing::add_player(&mut game, "rome");
+        let barbarian = add_barbarians(&mut game);
+        testing::add_city(&mut game, player, uvec2(1, 2));
+        let target = testing::add_city(&mut game, player, uvec2(4, 2));
+        let raider = testing::add_unit(&mut game, barbarian, "warrior", uvec2(5, 2));
+
+        let camp = testing::add_city(&mut game, barbarian, uvec2(8, 2));
+        let defender = testing::add_unit(&mut game, barbarian, "warrior", uvec2(8, 2));
+        testing::add_unit(&mut game, player, "worker", uvec2(8, 3));
+
+        assert_eq!(best_attack(&game, raider), Some(uvec2(4, 2)));
+        play_turn(&mut game);
+
+        assert_eq!(game.city(target).owner(), barbarian);
+        assert_eq!(game.unit(raider).pos(), uvec2(4, 2));
+        assert!(is_last_defender(&game, defender));
+        assert_eq!(game.unit(defender).pos(), game.city(camp).pos());
+    }
+
+    #[test]
+    fn wilderness_is_explored_but_out_of_sight() {
+        let mut game = testing::new_game(12, 5);
+        let player = testing::add_player(&mut game, "rome");
+        add_barbarians(&mut game);
+        let scout = testing::add_unit(&mut game, player, "warrior", uvec2(2, 2));
+
+        assert!(!is_wilderness(&game, uvec2(2, 2)));
+        assert!(!is_wilderness(&game, uvec2(8, 2)));
+
+        game.remove_unit(scout);
+        game.player_mut(player).update_visibility(&game);
+        assert!(is_wilderness(&game, uvec2(2, 2)));
+        assert!(!is_wilderness(&game, uvec2(8, 2)));
+    }
+}
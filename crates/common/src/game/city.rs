@@ -7,20 +7,36 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     assets::Handle,
+    espionage,
     event::Event,
-    registry::{Building, BuildingEffectType, Resource, UnitKind},
-    utils::{MaybeInfinityU32, UVecExt},
+    registry::{
+        Building, BuildingEffectType, CapabilityType, CivicEffectType, Religion, Resource, UnitKind,
+    },
+    saveload::legacy,
+    utils::MaybeInfinityU32,
     world::Game,
-    Improvement, Player, Terrain, Unit,
+    Player, Terrain, Unit,
 };
 
 use super::{
     culture::{Culture, CultureLevel},
+    governor::{GovernorSettings, TileScorer},
     CityId, PlayerId,
 };
 
 pub const BFC_RADIUS_SQUARED: u32 = 5;
 
+/// Gold paid per remaining hammer when rush-buying a build task.
+const RUSH_BUY_GOLD_PER_HAMMER: u32 = 2;
+/// Divisor of the quadratic term in the rush-buy cost, which
+/// makes expensive tasks disproportionately costly to buy.
+const RUSH_BUY_QUADRATIC_DIVISOR: u32 = 20;
+/// Multiplier applied to the rush-buy cost when no hammers
+/// have been put into the task yet.
+const RUSH_BUY_UNSTARTED_MULTIPLIER: u32 = 2;
+/// Multiplier applied to the rush-buy cost of national wonders.
+const RUSH_BUY_WONDER_MULTIPLIER: u32 = 2;
+
 /// A city in the game.
 ///
 /// All fields are private and encapsulated. Modifying city
@@ -50,14 +66,24 @@ pub struct City {
     /// overriden by the player and thus should not be
     /// modified by the city governor.
     manually_worked_tiles: IndexSet<UVec2, ahash::RandomState>,
+    /// Citizens working as specialists instead of working tiles.
+    specialists: AHashMap<Specialist, u32>,
+    /// The number of specialists of each type the player
+    /// requested, which the governor always keeps.
+    manual_specialists: AHashMap<Specialist, u32>,
 
     /// Food stored to reach the next population level.
     stored_food: u32,
 
+    /// Settings for the governor that chooses worked tiles.
+    governor: GovernorSettings,
+
     /// Stored progress on each possible build task.
     build_task_progress: AHashMap<BuildTask, u32>,
-    /// What the city is currently building.
-    build_task: Option<BuildTask>,
+    /// What the city is building, in order.
+    ///
+    /// The first entry is the current build task.
+    build_queue: Vec<QueuedBuildTask>,
 
     /// Bonus defense from culture
     culture_defense_bonus: u32,
@@ -67,6 +93,16 @@ pub struct City {
     /// Cities connected to this city via trade networks.
     connected_to_cities: AHashSet<CityId>,
 
+    /// Religions present in the city.
+    religions: Vec<Handle<Religion>>,
+    /// Religions founded in this city.
+    holy_city_of: Vec<Handle<Religion>>,
+
+    /// Remaining turns of sickness from a spy poisoning the water.
+    poisoned_turns: u32,
+    /// Remaining turns of anger from a spy inciting a revolt.
+    revolt_turns: u32,
+
     /// Buildings in this city
     buildings: Vec<Handle<Building>>,
     /// Building effects (computed from buildings)
@@ -111,16 +147,29 @@ impl City {
             culture: Culture::new(),
             worked_tiles: IndexSet::default(),
             manually_worked_tiles: IndexSet::default(),
+            specialists: AHashMap::new(),
+            manual_specialists: AHashMap::new(),
             stored_food: 0,
             build_task_progress: AHashMap::new(),
             previous_build_task: None,
-            build_task: None,
+            build_queue: Vec::new(),
+            governor: GovernorSettings::default(),
             culture_defense_bonus: 0,
             resources: AHashSet::new(),
             connected_to_cities: AHashSet::new(),
+            religions: Vec::new(),
+            holy_city_of: Vec::new(),
+            poisoned_turns: 0,
+            revolt_turns: 0,
             buildings: Vec::new(),
             building_effects: AHashMap::new(),
-            economy: CityEconomy::default(),
+            economy: CityEconomy {
+                production_percent: game
+                    .speed()
+                    .scale(owner.handicap_modifiers().production_percent),
+                growth_percent: game.speed().percent(),
+                ..Default::default()
+            },
             happiness_sources: Vec::new(),
             anger_sources: Vec::new(),
             health_sources: Vec::new(),
@@ -159,8 +208,94 @@ impl City {
         city
     }
 
+    /// Converts a city from an unversioned save file.
+    pub(crate) fn from_legacy(legacy: legacy::City) -> Self {
+        let legacy::City {
+            on_server,
+            id,
+            owner,
+            pos,
+            name,
+            population,
+            is_capital,
+            is_coastal,
+            culture,
+            worked_tiles,
+            manually_worked_tiles,
+            stored_food,
+            build_task_progress,
+            build_task,
+            culture_defense_bonus,
+            resources,
+            connected_to_cities,
+            buildings,
+            building_effects,
+            economy,
+            previous_build_task,
+            happiness_sources,
+            anger_sources,
+            health_sources,
+            sickness_sources,
+        } = legacy;
+        Self {
+            on_server,
+            id,
+            owner,
+            pos,
+            name,
+            population,
+            is_capital,
+            is_coastal,
+            culture,
+            worked_tiles,
+            manually_worked_tiles,
+            specialists: AHashMap::new(),
+            manual_specialists: AHashMap::new(),
+            stored_food,
+            governor: GovernorSettings::default(),
+            build_task_progress,
+            build_queue: build_task
+                .into_iter()
+                .map(|task| QueuedBuildTask {
+                    task,
+                    repeat: false,
+                })
+                .collect(),
+            culture_defense_bonus,
+            resources,
+            connected_to_cities,
+            buildings,
+            building_effects,
+            economy: CityEconomy {
+                gold: economy.gold,
+                beakers: economy.beakers,
+                culture: 0.,
+                espionage: 0.,
+                commerce_yield: economy.commerce_yield,
+                hammer_yield: economy.hammer_yield,
+                food_yield: economy.food_yield,
+                overflow_hammers: economy.overflow_hammers,
+                culture_per_turn: economy.culture_per_turn,
+                specialist_beakers: 0,
+                specialist_gold: 0,
+                maintenance_cost: economy.maintenance_cost,
+                production_percent: 100,
+                growth_percent: 100,
+            },
+            previous_build_task,
+            happiness_sources,
+            anger_sources,
+            health_sources,
+            sickness_sources,
+            religions: Vec::new(),
+            holy_city_of: Vec::new(),
+            poisoned_turns: 0,
+            revolt_turns: 0,
+        }
+    }
+
     pub fn food_needed_for_growth(&self) -> u32 {
-        30 + 3 * self.population.get()
+        (30 + 3 * self.population.get()) * self.economy.growth_percent / 100
     }
 
     pub fn food_consumed_per_turn(&self) -> u32 {
@@ -200,7 +335,26 @@ impl City {
     }
 
     pub fn build_task(&self) -> Option<&BuildTask> {
-        self.build_task.as_ref()
+        self.build_queue.first().map(|entry| &entry.task)
+    }
+
+    pub fn build_queue(&self) -> &[QueuedBuildTask] {
+        &self.build_queue
+    }
+
+    pub fn governor(&self) -> GovernorSettings {
+        self.governor
+    }
+
+    /// Gets a scorer that rates additional tile yields by how much
+    /// this city needs them, given the tiles it currently works.
+    pub fn tile_scorer(&self) -> TileScorer {
+        TileScorer::new(
+            self.governor,
+            self.economy.food_yield as i32 - self.food_consumed_per_turn() as i32,
+            self.num_happiness() <= self.num_anger(),
+            self.num_health() <= self.num_sickness(),
+        )
     }
 
     pub fn build_task_progress(&self, task: &BuildTask) -> u32 {
@@ -266,6 +420,23 @@ impl City {
         self.manually_worked_tiles.iter().map(|p| p.clone().into())
     }
 
+    pub fn num_specialists(&self, specialist: Specialist) -> u32 {
+        self.specialists.get(&specialist).copied().unwrap_or(0)
+    }
+
+    pub fn num_manual_specialists(&self, specialist: Specialist) -> u32 {
+        self.manual_specialists
+            .get(&specialist)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Gets the maximum number of specialists of the given type,
+    /// as determined by the city's buildings.
+    pub fn specialist_slots(&self, specialist: Specialist) -> u32 {
+        self.building_effect(specialist.slots_effect())
+    }
+
     pub fn is_tile_manually_worked(&self, tile: UVec2) -> bool {
         self.manual_worked_tiles().any(|t| t == tile)
     }
@@ -274,6 +445,59 @@ impl City {
         self.connected_to_cities.contains(&peer)
     }
 
+    pub fn religions(&self) -> impl Iterator<Item = &Handle<Religion>> {
+        self.religions.iter()
+    }
+
+    pub fn has_religion(&self, religion: &Handle<Religion>) -> bool {
+        self.religions.contains(religion)
+    }
+
+    pub fn is_holy_city_of(&self, religion: &Handle<Religion>) -> bool {
+        self.holy_city_of.contains(religion)
+    }
+
+    /// Adds a religion to the city. Does nothing if
+    /// the religion is already present.
+    pub fn add_religion(&mut self, religion: Handle<Religion>) {
+        if !self.has_religion(&religion) {
+            self.religions.push(religion);
+        }
+    }
+
+    /// Makes this city the holy city of a newly founded religion.
+    pub fn make_holy_city_of(&mut self, religion: Handle<Religion>) {
+        self.add_religion(religion.clone());
+        if !self.is_holy_city_of(&religion) {
+            self.holy_city_of.push(religion);
+        }
+    }
+
+    pub fn is_poisoned(&self) -> bool {
+        self.poisoned_turns > 0
+    }
+
+    pub fn is_in_revolt(&self) -> bool {
+        self.revolt_turns > 0
+    }
+
+    pub(crate) fn poison_water(&mut self) {
+        self.poisoned_turns = espionage::POISON_TURNS;
+    }
+
+    pub(crate) fn incite_revolt(&mut self) {
+        self.revolt_turns = espionage::REVOLT_TURNS;
+    }
+
+    /// Destroys half the progress on the current build task.
+    pub(crate) fn sabotage_production(&mut self) {
+        if let Some(entry) = self.build_queue.first() {
+            if let Some(progress) = self.build_task_progress.get_mut(&entry.task) {
+                *progress /= 2;
+            }
+        }
+    }
+
     pub fn building_effect(&self, effect: BuildingEffectType) -> u32 {
         self.building_effects.get(&effect).copied().unwrap_or(0)
     }
@@ -319,26 +543,97 @@ impl City {
     }
 
     pub fn gold_per_turn(&self) -> u32 {
-        self.economy().commerce_yield as u32 - self.beakers_per_turn()
+        self.economy.gold.floor() as u32
     }
 
     pub fn culture_defense_bonus(&self) -> u32 {
         self.culture_defense_bonus
     }
 
+    /// Gets the number of hammers the city needs to complete `task`.
+    pub fn build_task_cost(&self, task: &BuildTask) -> u32 {
+        task.cost() * self.economy.production_percent / 100
+    }
+
     pub fn estimate_build_time_for_task(&self, task: &BuildTask) -> MaybeInfinityU32 {
         MaybeInfinityU32::new(
-            task.cost() - self.build_task_progress(task) + self.economy().hammer_yield - 1,
+            self.build_task_cost(task) - self.build_task_progress(task)
+                + self.economy().hammer_yield
+                - 1,
         ) / (self.economy().hammer_yield)
     }
 
     pub fn estimate_remaining_build_time(&self) -> MaybeInfinityU32 {
-        match &self.build_task {
+        match self.build_task() {
             Some(task) => self.estimate_build_time_for_task(task),
             None => MaybeInfinityU32::new(0),
         }
     }
 
+    /// Computes the gold cost to complete the current build task immediately.
+    ///
+    /// Returns `None` if there is no build task or it cannot be bought.
+    pub fn build_task_buy_cost(&self) -> Option<u32> {
+        let task = self.build_task()?;
+        if let BuildTask::Building(b) = task {
+            if b.is_world_wonder() {
+                return None;
+            }
+        }
+
+        let progress = self.build_task_progress(task);
+        let remaining = self.build_task_cost(task).saturating_sub(progress);
+        let mut cost = RUSH_BUY_GOLD_PER_HAMMER * remaining
+            + remaining * remaining / RUSH_BUY_QUADRATIC_DIVISOR;
+
+        if progress == 0 {
+            cost *= RUSH_BUY_UNSTARTED_MULTIPLIER;
+        }
+        if let BuildTask::Building(b) = task {
+            if b.is_wonder() {
+                cost *= RUSH_BUY_WONDER_MULTIPLIER;
+            }
+        }
+
+        Some(cost)
+    }
+
+    /// Determines whether the current build task can be bought
+    /// with gold, returning the cost if so.
+    pub fn can_buy_build_task(&self, game: &Game) -> Result<u32, CannotBuyBuildTask> {
+        let task = self.build_task().ok_or(CannotBuyBuildTask::NoBuildTask)?;
+        let cost = self
+            .build_task_buy_cost()
+            .ok_or_else(|| CannotBuyBuildTask::WorldWonder(task.name().to_owned()))?;
+
+        if game.player(self.owner).gold() < cost {
+            return Err(CannotBuyBuildTask::NotEnoughGold(cost));
+        }
+
+        Ok(cost)
+    }
+
+    /// Completes the current build task immediately in exchange for gold.
+    pub fn buy_build_task(&mut self, game: &Game) -> Result<(), CannotBuyBuildTask> {
+        assert!(self.on_server);
+        let cost = self.can_buy_build_task(game)?;
+
+        game.player_mut(self.owner).spend_gold(cost);
+        game.push_event(Event::PlayerChanged(self.owner));
+
+        log::info!(
+            "{} bought {:?} for {} gold",
+            self.name,
+            self.build_task(),
+            cost
+        );
+        self.finish_build_task(game);
+
+        game.push_event(Event::CityChanged(self.id));
+
+        Ok(())
+    }
+
     pub fn turns_needed_for_growth(&self) -> u32 {
         (self.food_needed_for_growth() - self.stored_food() + self.economy().food_yield
             - self.food_consumed_per_turn()
@@ -410,6 +705,15 @@ impl City {
             return false;
         }
 
+        // Missionaries spread the state religion, so the
+        // city needs to have it.
+        if kind.capabilities.contains(&CapabilityType::SpreadReligion) {
+            match owner.state_religion() {
+                Some(religion) if self.has_religion(religion) => {}
+                _ => return false,
+            }
+        }
+
         true
     }
 
@@ -450,8 +754,146 @@ impl City {
         true
     }
 
+    /// Replaces the current build task, keeping the rest of the queue.
     pub fn set_build_task(&mut self, task: BuildTask) {
-        self.build_task = Some(task);
+        let entry = QueuedBuildTask {
+            task,
+            repeat: false,
+        };
+        match self.build_queue.first_mut() {
+            Some(current) => *current = entry,
+            None => self.build_queue.push(entry),
+        }
+    }
+
+    fn check_can_queue(
+        &self,
+        game: &Game,
+        task: &BuildTask,
+        repeat: bool,
+    ) -> Result<(), BuildQueueError> {
+        let can_build = match task {
+            BuildTask::Unit(u) => self.can_build_unit(game, u),
+            BuildTask::Building(b) => self.can_build_building(game, b),
+        };
+        if !can_build {
+            return Err(BuildQueueError::CannotBuild(task.name().to_owned()));
+        }
+        if repeat && !matches!(task, BuildTask::Unit(_)) {
+            return Err(BuildQueueError::CannotRepeat);
+        }
+        Ok(())
+    }
+
+    /// Adds a task to the end of the build queue.
+    pub fn append_to_build_queue(
+        &mut self,
+        game: &Game,
+        task: BuildTask,
+        repeat: bool,
+    ) -> Result<(), BuildQueueError> {
+        self.insert_into_build_queue(game, self.build_queue.len(), task, repeat)
+    }
+
+    /// Inserts a task into the build queue at `index`.
+    ///
+    /// Inserting at index 0 makes the task the current build task.
+    pub fn insert_into_build_queue(
+        &mut self,
+        game: &Game,
+        index: usize,
+        task: BuildTask,
+        repeat: bool,
+    ) -> Result<(), BuildQueueError> {
+        if index > self.build_queue.len() {
+            return Err(BuildQueueError::IndexOutOfBounds(index));
+        }
+        self.check_can_queue(game, &task, repeat)?;
+        self.build_queue
+            .insert(index, QueuedBuildTask { task, repeat });
+        Ok(())
+    }
+
+    /// Removes the task at `index` from the build queue.
+    ///
+    /// Progress on the task is kept in case it is queued again.
+    pub fn remove_from_build_queue(&mut self, index: usize) -> Result<(), BuildQueueError> {
+        if index >= self.build_queue.len() {
+            return Err(BuildQueueError::IndexOutOfBounds(index));
+        }
+        self.build_queue.remove(index);
+        Ok(())
+    }
+
+    /// Sets whether the task at `index` is built continuously.
+    pub fn set_build_queue_repeat(
+        &mut self,
+        index: usize,
+        repeat: bool,
+    ) -> Result<(), BuildQueueError> {
+        let entry = self
+            .build_queue
+            .get_mut(index)
+            .ok_or(BuildQueueError::IndexOutOfBounds(index))?;
+        if repeat && !matches!(entry.task, BuildTask::Unit(_)) {
+            return Err(BuildQueueError::CannotRepeat);
+        }
+        entry.repeat = repeat;
+        Ok(())
+    }
+
+    /// Moves the task at index `from` to index `to` in the build queue.
+    pub fn move_in_build_queue(&mut self, from: usize, to: usize) -> Result<(), BuildQueueError> {
+        let len = self.build_queue.len();
+        if from >= len {
+            return Err(BuildQueueError::IndexOutOfBounds(from));
+        }
+        if to >= len {
+            return Err(BuildQueueError::IndexOutOfBounds(to));
+        }
+        let entry = self.build_queue.remove(from);
+        self.build_queue.insert(to, entry);
+        Ok(())
+    }
+
+    pub fn set_governor(&mut self, game: &Game, settings: GovernorSettings) {
+        self.governor = settings;
+
+        self.update_worked_tiles(game);
+        self.update_economy(game);
+        game.push_event(Event::CityChanged(self.id));
+
+        let owner = self.owner;
+        game.defer(move |game| {
+            game.player_mut(owner).update_economy(game);
+            game.push_event(Event::PlayerChanged(owner));
+        });
+    }
+
+    /// Sets the number of specialists of the given type
+    /// that the governor must keep.
+    pub fn set_manual_specialists(&mut self, game: &Game, specialist: Specialist, count: u32) {
+        let others: u32 = self
+            .manual_specialists
+            .iter()
+            .filter(|(s, _)| **s != specialist)
+            .map(|(_, count)| *count)
+            .sum();
+        let citizens = self.num_workable_tiles().saturating_sub(1);
+        let count = count
+            .min(self.specialist_slots(specialist))
+            .min(citizens.saturating_sub(others));
+        self.manual_specialists.insert(specialist, count);
+
+        self.update_worked_tiles(game);
+        self.update_economy(game);
+        game.push_event(Event::CityChanged(self.id));
+
+        let owner = self.owner;
+        game.defer(move |game| {
+            game.player_mut(owner).update_economy(game);
+            game.push_event(Event::PlayerChanged(owner));
+        });
     }
 
     pub fn set_tile_manually_worked(&mut self, game: &Game, pos: UVec2, worked: bool) {
@@ -483,6 +925,8 @@ impl City {
         self.update_worked_tiles(game);
         self.work_tiles(game);
         self.update_trade_networks(game);
+        self.poisoned_turns = self.poisoned_turns.saturating_sub(1);
+        self.revolt_turns = self.revolt_turns.saturating_sub(1);
         self.update_statuses(game);
         self.update_economy(game);
         self.check_build_task_prerequisites(game);
@@ -494,116 +938,184 @@ impl City {
     }
 
     fn check_build_task_prerequisites(&mut self, game: &Game) {
-        // If we can no longer build the current task, then it has to fail.
-        if let Some(task) = &self.build_task {
+        // Queued tasks we can no longer build are skipped.
+        // If the current task is among them, then it has failed.
+        let mut index = 0;
+        while index < self.build_queue.len() {
+            let task = &self.build_queue[index].task;
             let failure = match task {
                 BuildTask::Unit(u) => !self.can_build_unit(game, u),
                 BuildTask::Building(b) => !self.can_build_building(game, b),
             };
             if failure {
                 log::info!("{} has failed to build {:?}", self.name(), task);
-                self.previous_build_task = Some(PreviousBuildTask {
-                    success: false,
-                    task: task.clone(),
-                });
-                self.build_task = None;
+                let entry = self.build_queue.remove(index);
+                if index == 0 {
+                    self.previous_build_task = Some(PreviousBuildTask {
+                        success: false,
+                        task: entry.task,
+                    });
+                }
+            } else {
+                index += 1;
             }
         }
     }
 
     fn make_build_task_progress(&mut self, game: &Game) {
-        if let Some(task) = &self.build_task {
+        if let Some(entry) = self.build_queue.first() {
+            let task = &entry.task;
+            let cost = self.build_task_cost(task);
             let progress = self.build_task_progress.entry(task.clone()).or_insert(0);
             *progress += self.economy.hammer_yield;
             *progress += self.economy.overflow_hammers;
             self.economy.overflow_hammers = 0;
             let progress = *progress;
 
-            if progress >= task.cost() {
-                // Done. Set the current task to None, the previous task to Some, and add overflow hammers.
+            if progress >= cost {
+                // Done. Advance the queue, set the previous task to Some, and add overflow hammers.
                 log::info!("{} finished building {:?}", self.name, task);
-                self.complete_build_task(task, game);
-
-                self.economy.overflow_hammers = progress - task.cost();
-                self.build_task_progress.remove(task);
-
-                self.previous_build_task = Some(PreviousBuildTask {
-                    success: true,
-                    task: task.clone(),
-                });
-                self.build_task = None;
+                self.economy.overflow_hammers = progress - cost;
+                self.finish_build_task(game);
             }
         }
     }
 
+    /// Adds hammers toward the build task on the next turn,
+    /// e.g. from chopping a forest.
+    pub fn add_bonus_hammers(&mut self, hammers: u32) {
+        self.economy.overflow_hammers += hammers;
+    }
+
+    /// Completes the current build task and advances the build queue.
+    ///
+    /// Repeating tasks stay at the front of the queue.
+    fn finish_build_task(&mut self, game: &Game) {
+        let entry = if self.build_queue[0].repeat {
+            self.build_queue[0].clone()
+        } else {
+            self.build_queue.remove(0)
+        };
+        self.complete_build_task(&entry.task, game);
+        self.build_task_progress.remove(&entry.task);
+        self.previous_build_task = Some(PreviousBuildTask {
+            success: true,
+            task: entry.task,
+        });
+    }
+
     fn complete_build_task(&self, task: &BuildTask, game: &Game) {
         let id = self.id;
         match task.clone() {
             BuildTask::Unit(kind) => game.defer(move |game| {
                 let unit_id = game.new_unit_id();
                 let this = game.city(id);
-                let unit = Unit::new(unit_id, this.owner(), kind, this.pos());
+                let mut unit = Unit::new(unit_id, this.owner(), kind, this.pos());
+                unit.add_experience(this.building_effect(BuildingEffectType::StartingExperience));
                 drop(this);
                 game.add_unit(unit);
             }),
             BuildTask::Building(b) => game.defer(move |game| {
-                game.city_mut(id).buildings.push(b);
+                game.city_mut(id).add_building(b);
             }),
         }
     }
 
     /// Updates the current worked tiles.
     ///
-    /// * recalculates a score for each tile, and works the top-scoring tiles
+    /// * recalculates a score for each tile based on the governor settings, and works the top-scoring tiles
     /// * prioritizes manually worked tiles over automatic scoring
     /// * if there are too many manually worked tiles, then excess ones are removed
     fn update_worked_tiles(&mut self, game: &Game) {
-        // Reset the set of worked tiles.
+        // Reset the set of worked tiles and specialists.
         for tile in self.worked_tiles.drain(..) {
             game.clear_tile_worker(tile);
         }
+        self.specialists.clear();
 
-        let mut entries = Vec::new();
+        let mut forced = Vec::new();
+        let mut candidates = Vec::new();
 
         for &tile in &self.manually_worked_tiles {
-            entries.push((tile, true));
+            forced.push(tile);
         }
 
         for tile in game.map().big_fat_cross(self.pos) {
-            if self.can_work_tile(game, tile) && !entries.contains(&(tile, true)) {
-                entries.push((tile, false));
+            if self.can_work_tile(game, tile) && !forced.contains(&tile) {
+                candidates.push(tile);
             }
             game.push_event(Event::TileChanged(tile));
         }
 
-        #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-        struct Score {
-            forced: bool,
-            food: u32,
-            hammers_and_commerce: u32,
-        }
-        entries.sort_by_key(|(pos, forced)| {
-            let tile = game.tile(*pos).unwrap();
-            let tile_yield = tile.tile_yield();
-            Score {
-                forced: *forced,
-                food: tile_yield.food,
-                hammers_and_commerce: tile_yield.hammers + tile_yield.commerce,
-            }
-        });
+        let tile_yield = |pos: UVec2| game.tile(pos).unwrap().tile_yield(game);
 
         // The city's own position is always worked.
         self.worked_tiles.insert(self.pos);
         game.set_tile_worker(self.pos, self.id);
 
-        // The top entries get worked.
-        for (pos, _) in entries
-            .into_iter()
-            .rev()
-            .take(self.num_workable_tiles().saturating_sub(1) as usize)
-        {
+        let mut scorer = TileScorer::new(
+            self.governor,
+            tile_yield(self.pos).food as i32 - self.food_consumed_per_turn() as i32,
+            self.num_happiness() <= self.num_anger(),
+            self.num_health() <= self.num_sickness(),
+        );
+
+        let mut citizens = self.num_workable_tiles().saturating_sub(1);
+
+        // Manual specialists and manually worked tiles are prioritized over the governor.
+        for specialist in Specialist::all() {
+            let count = self
+                .num_manual_specialists(specialist)
+                .min(self.specialist_slots(specialist))
+                .min(citizens);
+            if count > 0 {
+                self.specialists.insert(specialist, count);
+                citizens -= count;
+            }
+        }
+
+        for pos in forced {
+            if citizens == 0 {
+                break;
+            }
+            scorer.add_worked(tile_yield(pos));
             self.worked_tiles.insert(pos);
             game.set_tile_worker(pos, self.id);
+            citizens -= 1;
+        }
+
+        // Remaining citizens are assigned one at a time, since the
+        // value of food depends on the tiles already chosen.
+        while citizens > 0 {
+            let best_tile = candidates
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| (i, scorer.score(tile_yield(*pos))))
+                .max_by_key(|(_, score)| *score);
+            let best_specialist = Specialist::all()
+                .into_iter()
+                .filter(|s| self.num_specialists(*s) < self.specialist_slots(*s))
+                .map(|s| (s, scorer.score_specialist(s)))
+                .max_by_key(|(_, score)| *score);
+
+            match (best_tile, best_specialist) {
+                (Some((_, tile_score)), Some((specialist, specialist_score)))
+                    if specialist_score > tile_score =>
+                {
+                    *self.specialists.entry(specialist).or_insert(0) += 1;
+                }
+                (Some((index, _)), _) => {
+                    let pos = candidates.swap_remove(index);
+                    scorer.add_worked(tile_yield(pos));
+                    self.worked_tiles.insert(pos);
+                    game.set_tile_worker(pos, self.id);
+                }
+                (None, Some((specialist, _))) => {
+                    *self.specialists.entry(specialist).or_insert(0) += 1;
+                }
+                (None, None) => break,
+            }
+            citizens -= 1;
         }
     }
 
@@ -627,7 +1139,7 @@ impl City {
             }
         }
 
-        if pos.distance_squared(self.pos) > BFC_RADIUS_SQUARED {
+        if game.map().distance_squared(pos, self.pos) > BFC_RADIUS_SQUARED {
             return false;
         }
 
@@ -637,8 +1149,9 @@ impl City {
     pub fn transfer_control(&mut self, game: &Game, to_player: PlayerId) {
         self.owner = to_player;
         self.is_capital = false;
-        self.build_task = None;
+        self.build_queue.clear();
         self.build_task_progress.clear();
+        self.manual_specialists.clear();
         self.population =
             NonZeroU32::new(self.population.get() - 1).unwrap_or(NonZeroU32::new(1).unwrap());
 
@@ -659,9 +1172,15 @@ impl City {
         self.economy.hammer_yield = 1;
         self.economy.food_yield = 1;
         self.economy.commerce_yield = 1.;
+        self.economy.production_percent = game.speed().scale(
+            game.player(self.owner)
+                .handicap_modifiers()
+                .production_percent,
+        );
+        self.economy.growth_percent = game.speed().percent();
 
         for &tile in &self.worked_tiles {
-            let tile_yield = game.tile(tile).unwrap().tile_yield();
+            let tile_yield = game.tile(tile).unwrap().tile_yield(game);
             self.economy.hammer_yield += tile_yield.hammers;
             self.economy.food_yield += tile_yield.food;
             self.economy.commerce_yield += tile_yield.commerce as f64;
@@ -671,6 +1190,15 @@ impl City {
             self.economy.commerce_yield += 8.;
         }
 
+        self.economy.specialist_beakers = 0;
+        self.economy.specialist_gold = 0;
+        for (&specialist, &count) in &self.specialists {
+            let output = specialist.output();
+            self.economy.hammer_yield += output.hammers * count;
+            self.economy.specialist_beakers += output.beakers * count;
+            self.economy.specialist_gold += output.gold * count;
+        }
+
         self.economy.maintenance_cost = self.maintenance_cost(game);
     }
 
@@ -680,7 +1208,7 @@ impl City {
             0.
         } else {
             let capital = game.city(capital).pos();
-            let dist = self.pos.as_f64().distance(capital.as_f64());
+            let dist = game.map().distance(self.pos, capital);
             (0.125 / 2. * dist) * (7. + self.population().get() as f64)
         };
         let number_of_cities_cost = 0.6
@@ -693,15 +1221,22 @@ impl City {
             cost *= 0.9;
         }
 
-        cost
+        cost * game
+            .player(self.owner)
+            .civic_multiplier(CivicEffectType::MaintenancePercent)
     }
 
-    fn update_culture_per_turn(&mut self) {
-        self.economy.culture_per_turn = 0;
+    pub(crate) fn update_culture_per_turn(&mut self) {
+        // Culture from commerce is computed by the owner's economy sliders.
+        self.economy.culture_per_turn = self.economy.culture.floor() as u32;
 
         if self.is_capital {
             self.economy.culture_per_turn += 2;
         }
+
+        for (&specialist, &count) in &self.specialists {
+            self.economy.culture_per_turn += specialist.output().culture * count;
+        }
     }
 
     fn update_culture_borders(&mut self, game: &Game) {
@@ -721,7 +1256,7 @@ impl City {
             // This implements a Civ4 mechanic.
             // See: https://www.civfanatics.com/civ4/strategy/game-mechanics/culture-mechanics-disassembled/
             let mut culture_added = self.economy.culture_per_turn;
-            let distance = pos.as_f64().distance(self.pos.as_f64()).floor() as u32;
+            let distance = game.map().distance(pos, self.pos).floor() as u32;
             if distance < border_radius {
                 culture_added += (border_radius - distance) * 20;
             }
@@ -781,7 +1316,12 @@ impl City {
 
             // Check for resource
             if let Some(resource) = tile.resource() {
-                if tile.is_resource_improved() && tile.owner(game) == Some(self.owner) {
+                if tile.is_resource_improved()
+                    && tile.owner(game) == Some(self.owner)
+                    && game
+                        .player(self.owner)
+                        .has_revealed_resource(game.registry(), resource)
+                {
                     self.resources.insert(resource.clone());
                 }
             }
@@ -795,8 +1335,7 @@ impl City {
 
             for neighbor in game.map().adjacent(pos) {
                 let neighbor_tile = game.tile(neighbor).unwrap();
-                if (neighbor_tile.has_improvement(Improvement::Road)
-                    || game.city_id_at_pos(neighbor).is_some())
+                if (neighbor_tile.has_route() || game.city_id_at_pos(neighbor).is_some())
                     && neighbor_tile
                         .owner(game)
                         .map(|owner| !game.player(owner).is_at_war_with(self.owner))
@@ -810,17 +1349,19 @@ impl City {
         }
     }
 
-    fn update_statuses(&mut self, game: &Game) {
-        self.update_happiness();
+    /// Recomputes the city's happiness, anger, health and sickness.
+    pub fn update_statuses(&mut self, game: &Game) {
+        self.update_happiness(game);
         self.update_anger(game);
         self.update_health(game);
         self.update_sickness(game);
     }
 
-    fn update_happiness(&mut self) {
+    fn update_happiness(&mut self, game: &Game) {
         self.happiness_sources.clear();
 
-        for _ in 0..4 {
+        let free_happiness = game.player(self.owner).handicap_modifiers().free_happiness;
+        for _ in 0..free_happiness {
             self.happiness_sources
                 .push(HappinessSource::DifficultyBonus);
         }
@@ -838,15 +1379,31 @@ impl City {
         if self.is_capital() {
             self.happiness_sources.push(HappinessSource::Buildings); // palace
         }
-    }
 
-    fn update_anger(&mut self, game: &Game) {
-        self.anger_sources.clear();
+        let owner = game.player(self.owner);
+        if let Some(religion) = owner.state_religion() {
+            if self.has_religion(religion) {
+                let bonus = 1 + owner
+                    .civic_effect_amount(CivicEffectType::StateReligionHappiness)
+                    .max(0);
+                for _ in 0..bonus {
+                    self.happiness_sources.push(HappinessSource::StateReligion);
+                }
+            }
+        }
 
-        for _ in 0..self.population.get() {
-            self.anger_sources.push(AngerSource::Population);
+        let (num_our_units, _) = self.num_military_units(game);
+        let civic_happiness = owner.civic_effect_amount(CivicEffectType::Happiness)
+            + owner.civic_effect_amount(CivicEffectType::HappinessPerMilitaryUnit)
+                * num_our_units as i32;
+        for _ in 0..civic_happiness.max(0) {
+            self.happiness_sources.push(HappinessSource::Civics);
         }
+    }
 
+    /// Counts military units in the city, returning
+    /// (units of the owner, units of other players).
+    fn num_military_units(&self, game: &Game) -> (u32, u32) {
         let mut num_our_units = 0;
         let mut num_enemy_units = 0;
         for unit in game.units() {
@@ -861,35 +1418,51 @@ impl City {
                 }
             }
         }
+        (num_our_units, num_enemy_units)
+    }
+
+    fn update_anger(&mut self, game: &Game) {
+        self.anger_sources.clear();
+
+        for _ in 0..self.population.get() {
+            self.anger_sources.push(AngerSource::Population);
+        }
 
+        let (num_our_units, num_enemy_units) = self.num_military_units(game);
         if num_our_units == 0 {
             self.anger_sources.push(AngerSource::Undefended);
             if num_enemy_units > 0 {
                 self.anger_sources.push(AngerSource::Undefended);
             }
         }
+
+        // Religions other than the state religion cause discord.
+        if let Some(state_religion) = game.player(self.owner).state_religion() {
+            for religion in &self.religions {
+                if religion != state_religion {
+                    self.anger_sources.push(AngerSource::ReligiousDiscord);
+                }
+            }
+        }
+
+        if self.is_in_revolt() {
+            for _ in 0..espionage::REVOLT_ANGER {
+                self.anger_sources.push(AngerSource::Revolt);
+            }
+        }
     }
 
     fn update_health(&mut self, game: &Game) {
         self.health_sources.clear();
 
-        for _ in 0..2 {
+        let free_health = game.player(self.owner).handicap_modifiers().free_health;
+        for _ in 0..free_health {
             self.health_sources.push(HealthSource::DifficultyBonus);
         }
 
-        let mut num_forest_tiles = 0;
-        for pos in game.map().big_fat_cross(self.pos) {
-            let tile = game.tile(pos).unwrap();
-            if tile.owner(game) != Some(self.owner) {
-                continue;
-            }
-            if tile.is_forested() {
-                num_forest_tiles += 1;
-            }
-        }
-
-        for _ in 0..num_forest_tiles / 2 {
-            self.health_sources.push(HealthSource::Forests);
+        let (terrain_health, _) = self.terrain_health_and_sickness(game);
+        for _ in 0..terrain_health.floor() as u32 {
+            self.health_sources.push(HealthSource::Terrain);
         }
 
         if game.tile(self.pos).unwrap().has_fresh_water() {
@@ -920,25 +1493,70 @@ impl City {
             self.sickness_sources.push(SicknessSource::Buildings);
         }
 
-        let mut num_flood_plains = 0;
+        let (_, terrain_sickness) = self.terrain_health_and_sickness(game);
+        for _ in 0..terrain_sickness.floor() as u32 {
+            self.sickness_sources.push(SicknessSource::Terrain);
+        }
+
+        if self.is_poisoned() {
+            for _ in 0..espionage::POISON_SICKNESS {
+                self.sickness_sources.push(SicknessSource::PoisonedWater);
+            }
+        }
+    }
+
+    /// Sums the health and sickness from terrain and features
+    /// on owned tiles in the city's radius.
+    fn terrain_health_and_sickness(&self, game: &Game) -> (f32, f32) {
+        let mut health = 0.;
+        let mut sickness = 0.;
         for pos in game.map().big_fat_cross(self.pos) {
             let tile = game.tile(pos).unwrap();
-            if tile.owner(game) == Some(self.owner) && tile.is_flood_plains() {
-                num_flood_plains += 1;
+            if tile.owner(game) == Some(self.owner) {
+                let (h, s) = tile.health_and_sickness(game);
+                health += h;
+                sickness += s;
             }
         }
-        for _ in 0..(num_flood_plains / 2) {
-            self.sickness_sources.push(SicknessSource::FloodPlains);
-        }
+        (health, sickness)
     }
 
     fn work_tiles(&mut self, game: &Game) {
         for &pos in &self.worked_tiles {
-            game.tile_mut(pos).unwrap().work();
+            game.tile_mut(pos).unwrap().work(game);
         }
     }
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum CannotBuyBuildTask {
+    #[error("the city is not building anything")]
+    NoBuildTask,
+    #[error("{0} is a world wonder and cannot be bought")]
+    WorldWonder(String),
+    #[error("not enough gold (need {0})")]
+    NotEnoughGold(u32),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BuildQueueError {
+    #[error("cannot build {0} in this city")]
+    CannotBuild(String),
+    #[error("only units can be built repeatedly")]
+    CannotRepeat,
+    #[error("no build queue entry at index {0}")]
+    IndexOutOfBounds(usize),
+}
+
+/// An entry in a city's build queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedBuildTask {
+    pub task: BuildTask,
+    /// Whether the task should be built continuously
+    /// instead of being removed from the queue when completed.
+    pub repeat: bool,
+}
+
 /// The most recent build task completed in a city.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PreviousBuildTask {
@@ -977,9 +1595,11 @@ impl BuildTask {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CityEconomy {
-    // gold + beakers = commerce
+    // gold + beakers + culture + espionage = commerce
     pub gold: f64,
     pub beakers: f64,
+    pub culture: f64,
+    pub espionage: f64,
 
     pub commerce_yield: f64,
     pub hammer_yield: u32,
@@ -989,7 +1609,89 @@ pub struct CityEconomy {
 
     pub culture_per_turn: u32,
 
+    /// Beakers and gold from specialists, which aren't
+    /// split by the player's economy sliders.
+    pub specialist_beakers: u32,
+    pub specialist_gold: u32,
+
     pub maintenance_cost: f64,
+
+    /// Cost of build tasks as a percent of their base cost,
+    /// from the owner's difficulty level and the game speed.
+    pub production_percent: u32,
+    /// Food needed to grow as a percent of the base amount,
+    /// from the game speed.
+    pub growth_percent: u32,
+}
+
+/// A citizen that produces output directly
+/// instead of working a tile.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Specialist {
+    Scientist,
+    Merchant,
+    Artist,
+    Engineer,
+}
+
+impl Specialist {
+    pub fn all() -> [Specialist; 4] {
+        [
+            Specialist::Scientist,
+            Specialist::Merchant,
+            Specialist::Artist,
+            Specialist::Engineer,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Specialist::Scientist => "Scientist",
+            Specialist::Merchant => "Merchant",
+            Specialist::Artist => "Artist",
+            Specialist::Engineer => "Engineer",
+        }
+    }
+
+    /// The building effect that grants slots for this specialist.
+    pub fn slots_effect(self) -> BuildingEffectType {
+        match self {
+            Specialist::Scientist => BuildingEffectType::ScientistSlots,
+            Specialist::Merchant => BuildingEffectType::MerchantSlots,
+            Specialist::Artist => BuildingEffectType::ArtistSlots,
+            Specialist::Engineer => BuildingEffectType::EngineerSlots,
+        }
+    }
+
+    pub fn output(self) -> SpecialistOutput {
+        match self {
+            Specialist::Scientist => SpecialistOutput {
+                beakers: 3,
+                ..Default::default()
+            },
+            Specialist::Merchant => SpecialistOutput {
+                gold: 3,
+                ..Default::default()
+            },
+            Specialist::Artist => SpecialistOutput {
+                culture: 3,
+                ..Default::default()
+            },
+            Specialist::Engineer => SpecialistOutput {
+                hammers: 2,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Output of a single specialist per turn.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SpecialistOutput {
+    pub beakers: u32,
+    pub gold: u32,
+    pub culture: u32,
+    pub hammers: u32,
 }
 
 /// A source of happiness in a city.
@@ -998,6 +1700,8 @@ pub enum HappinessSource {
     DifficultyBonus,
     Buildings,
     Resources,
+    StateReligion,
+    Civics,
 }
 
 /// A source of anger in a city.
@@ -1005,6 +1709,9 @@ pub enum HappinessSource {
 pub enum AngerSource {
     Population,
     Undefended,
+    ReligiousDiscord,
+    /// A spy incited a revolt.
+    Revolt,
 }
 
 /// A source of health in a city.
@@ -1014,7 +1721,8 @@ pub enum HealthSource {
     FreshWater,
     Resources,
     Buildings,
-    Forests,
+    /// Forests and other terrain features.
+    Terrain,
 }
 
 /// A source of sickness in a city.
@@ -1022,5 +1730,89 @@ pub enum HealthSource {
 pub enum SicknessSource {
     Population,
     Buildings,
-    FloodPlains,
+    /// Flood plains and other terrain features.
+    Terrain,
+    /// A spy poisoned the water supply.
+    PoisonedWater,
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn world_wonders_cannot_be_bought() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "britain");
+        let city = testing::add_city(&mut game, player, uvec2(2, 2));
+
+        let registry = testing::registry();
+        let stonehenge = registry.building("Stonehenge").unwrap();
+        assert!(stonehenge.is_world_wonder());
+
+        let mut city = game.city_mut(city);
+        city.set_build_task(BuildTask::Building(stonehenge));
+        assert_eq!(city.build_task_buy_cost(), None);
+
+        city.set_build_task(BuildTask::Building(registry.building("Walls").unwrap()));
+        assert!(city.build_task_buy_cost().is_some());
+    }
+
+    #[test]
+    fn build_queue_entries_can_be_repeated_and_reordered() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "rome");
+        let city = testing::add_city(&mut game, player, uvec2(2, 2));
+
+        let registry = testing::registry();
+        game.player_mut(player)
+            .grant_tech(registry.tech("Masonry").unwrap());
+        let warrior = BuildTask::Unit(registry.unit_kind("warrior").unwrap());
+        let walls = BuildTask::Building(registry.building("Walls").unwrap());
+
+        let mut city = game.city_mut(city);
+        city.append_to_build_queue(&game, warrior, true).unwrap();
+        assert!(matches!(
+            city.append_to_build_queue(&game, walls.clone(), true),
+            Err(BuildQueueError::CannotRepeat)
+        ));
+        city.append_to_build_queue(&game, walls, false).unwrap();
+
+        assert!(matches!(
+            city.set_build_queue_repeat(1, true),
+            Err(BuildQueueError::CannotRepeat)
+        ));
+        assert!(matches!(
+            city.set_build_queue_repeat(2, true),
+            Err(BuildQueueError::IndexOutOfBounds(2))
+        ));
+        city.set_build_queue_repeat(0, false).unwrap();
+        assert!(!city.build_queue()[0].repeat);
+
+        city.move_in_build_queue(1, 0).unwrap();
+        assert_eq!(city.build_task().unwrap().name(), "Walls");
+    }
+
+    #[test]
+    fn manual_specialists_are_limited_by_slots_and_citizens() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "rome");
+        let city = testing::add_city(&mut game, player, uvec2(2, 2));
+
+        let registry = testing::registry();
+        let mut city = game.city_mut(city);
+        city.set_manual_specialists(&game, Specialist::Scientist, 1);
+        assert_eq!(city.num_specialists(Specialist::Scientist), 0);
+
+        city.add_building(registry.building("Library").unwrap());
+        city.set_manual_specialists(&game, Specialist::Scientist, 2);
+        // A size 1 city only has one citizen to spare.
+        assert_eq!(city.num_manual_specialists(Specialist::Scientist), 1);
+        assert_eq!(city.num_specialists(Specialist::Scientist), 1);
+        assert_eq!(city.worked_tiles().count(), 1);
+    }
 }
@@ -0,0 +1,16 @@
+//! Rules for switching civics.
+
+/// Number of turns of anarchy after a player switches civics.
+///
+/// Cities produce no commerce during anarchy.
+pub const ANARCHY_TURNS: u32 = 2;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotSetCivics {
+    #[error("civics cannot be changed during anarchy")]
+    InAnarchy,
+    #[error("missing the tech required for {0}")]
+    MissingTech(String),
+    #[error("more than one civic was given for the {0} category")]
+    DuplicateCategory(&'static str),
+}
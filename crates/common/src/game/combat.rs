@@ -5,6 +5,10 @@ use crate::{Game, Unit, UnitId};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 
+const ATTACKER_BASE_EXPERIENCE: f64 = 4.;
+const DEFENDER_BASE_EXPERIENCE: f64 = 2.;
+const MAX_EXPERIENCE_PER_COMBAT: u32 = 10;
+
 /// A combat event that occurred between two units.
 ///
 /// Combat is simulated in _rounds_, where in each round
@@ -98,7 +102,7 @@ impl<'a> CombatSimulator<'a> {
             attacker_health: attacker.health(),
             defender_health: defender.health(),
             defender_strength: defender.modified_defending_strength(game, &attacker),
-            attacker_strength: attacker.strength(),
+            attacker_strength: attacker.modified_attacking_strength(),
             attacker,
             defender,
             rounds: Vec::new(),
@@ -129,9 +133,13 @@ impl<'a> CombatSimulator<'a> {
         let loser_id = loser.id();
         self.game.defer(move |game| game.remove_unit(loser_id));
 
+        let experience = self.experience_for_winner(winner.id() == self.attacker.id());
+
         let winner_id = winner.id();
         self.game.defer(move |game| {
-            game.unit_mut(winner_id).set_health(winner_health);
+            let mut winner = game.unit_mut(winner_id);
+            winner.set_health(winner_health);
+            winner.add_experience(experience);
         });
 
         // If there are no enemy units left on the target stack,
@@ -163,6 +171,31 @@ impl<'a> CombatSimulator<'a> {
         }
     }
 
+    /// Computes the experience awarded to the winner of the combat.
+    ///
+    /// Winning against the odds awards more experience. Attackers
+    /// gain more than defenders.
+    fn experience_for_winner(&self, attacker_won: bool) -> u32 {
+        // No experience for capturing units that can't fight.
+        if self.defender.strength() == 0. {
+            return 0;
+        }
+
+        let (base, odds_ratio) = if attacker_won {
+            (
+                ATTACKER_BASE_EXPERIENCE,
+                self.defender_strength / self.attacker_strength,
+            )
+        } else {
+            (
+                DEFENDER_BASE_EXPERIENCE,
+                self.attacker_strength / self.defender_strength,
+            )
+        };
+
+        ((base * odds_ratio).floor() as u32).clamp(1, MAX_EXPERIENCE_PER_COMBAT)
+    }
+
     fn do_round(&mut self) {
         let r = self.attacker_strength / self.defender_strength;
 
@@ -186,3 +219,36 @@ impl<'a> CombatSimulator<'a> {
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn winning_against_the_odds_awards_more_experience() {
+        let mut game = testing::new_game(6, 6);
+        let attacker_player = testing::add_player(&mut game, "rome");
+        let defender_player = testing::add_player(&mut game, "greece");
+        let attacker = testing::add_unit(&mut game, attacker_player, "warrior", uvec2(1, 1));
+        let weak = testing::add_unit(&mut game, defender_player, "warrior", uvec2(2, 1));
+        let strong = testing::add_unit(&mut game, defender_player, "archer", uvec2(1, 2));
+        let settler = testing::add_unit(&mut game, defender_player, "settler", uvec2(0, 1));
+
+        let even_fight = CombatSimulator::new(&game, attacker, weak).experience_for_winner(true);
+        let uphill_fight =
+            CombatSimulator::new(&game, attacker, strong).experience_for_winner(true);
+        let capture = CombatSimulator::new(&game, attacker, settler).experience_for_winner(true);
+
+        assert!(uphill_fight > even_fight);
+        assert!(even_fight >= 1);
+        assert_eq!(capture, 0);
+
+        // Defenders gain less than attackers for the same odds.
+        let defended = CombatSimulator::new(&game, attacker, weak).experience_for_winner(false);
+        assert!(defended < even_fight);
+    }
+}
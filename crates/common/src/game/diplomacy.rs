@@ -0,0 +1,86 @@
+//! Attitudes of leaders toward other players.
+
+use std::fmt::Display;
+
+use crate::Player;
+
+/// Something that affects a leader's attitude toward another player.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AttitudeModifier {
+    /// Both players have the same state religion.
+    SharedReligion,
+    /// The players have different state religions.
+    DifferentReligion,
+}
+
+impl AttitudeModifier {
+    pub fn description(self) -> &'static str {
+        match self {
+            AttitudeModifier::SharedReligion => "We share the same faith",
+            AttitudeModifier::DifferentReligion => "You are heathens",
+        }
+    }
+}
+
+/// A leader's overall attitude toward another player.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Attitude {
+    Furious,
+    Annoyed,
+    Cautious,
+    Pleased,
+    Friendly,
+}
+
+impl Attitude {
+    pub fn from_score(score: i32) -> Self {
+        match score {
+            i32::MIN..=-3 => Attitude::Furious,
+            -2..=-1 => Attitude::Annoyed,
+            0 => Attitude::Cautious,
+            1..=2 => Attitude::Pleased,
+            3.. => Attitude::Friendly,
+        }
+    }
+}
+
+impl Display for Attitude {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Attitude::Furious => "Furious",
+            Attitude::Annoyed => "Annoyed",
+            Attitude::Cautious => "Cautious",
+            Attitude::Pleased => "Pleased",
+            Attitude::Friendly => "Friendly",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Gets the modifiers to `player`'s attitude toward `other`
+/// along with their values.
+pub fn attitude_modifiers(player: &Player, other: &Player) -> Vec<(AttitudeModifier, i32)> {
+    let mut modifiers = Vec::new();
+
+    if let (Some(ours), Some(theirs)) = (player.state_religion(), other.state_religion()) {
+        // More religious leaders care more about faith.
+        let weight = 1 + player.leader().religious as i32 / 4;
+        if ours == theirs {
+            modifiers.push((AttitudeModifier::SharedReligion, weight));
+        } else {
+            modifiers.push((AttitudeModifier::DifferentReligion, -weight));
+        }
+    }
+
+    modifiers
+}
+
+/// Gets `player`'s overall attitude toward `other`.
+pub fn attitude(player: &Player, other: &Player) -> Attitude {
+    Attitude::from_score(
+        attitude_modifiers(player, other)
+            .into_iter()
+            .map(|(_, value)| value)
+            .sum(),
+    )
+}
@@ -0,0 +1,221 @@
+//! Espionage missions performed by spies in foreign cities.
+//!
+//! Missions spend the espionage points the spy's owner has
+//! accumulated against the city's owner. A mission can only be
+//! attempted once its cost has been saved up, and stockpiling
+//! more points improves the odds of success. A spy that fails
+//! is discovered, and the victim learns who sent it.
+
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{assets::Handle, event::Event, registry::Tech, CityId, Game, PlayerId, UnitId};
+
+/// Number of turns a city's water stays poisoned.
+pub const POISON_TURNS: u32 = 5;
+/// Sickness added to a city while its water is poisoned.
+pub const POISON_SICKNESS: u32 = 3;
+/// Number of turns a city stays in revolt.
+pub const REVOLT_TURNS: u32 = 3;
+/// Anger added to a city while it is in revolt.
+pub const REVOLT_ANGER: u32 = 3;
+
+const MAX_SUCCESS_CHANCE: f64 = 0.9;
+/// Chance that a spy is discovered even when its mission succeeds.
+const DISCOVERED_ON_SUCCESS_CHANCE: f64 = 0.25;
+
+/// A mission a spy can perform in a foreign city.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EspionageMission {
+    /// Learn a tech the city's owner knows and we don't.
+    StealTech,
+    /// Destroy half the progress on the city's current build task.
+    SabotageProduction,
+    /// Make the city sick for a few turns.
+    PoisonWater,
+    /// Make the city angry for a few turns.
+    InciteRevolt,
+}
+
+impl EspionageMission {
+    pub fn all() -> impl Iterator<Item = EspionageMission> {
+        [
+            EspionageMission::StealTech,
+            EspionageMission::SabotageProduction,
+            EspionageMission::PoisonWater,
+            EspionageMission::InciteRevolt,
+        ]
+        .into_iter()
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EspionageMission::StealTech => "Steal Tech",
+            EspionageMission::SabotageProduction => "Sabotage Production",
+            EspionageMission::PoisonWater => "Poison Water",
+            EspionageMission::InciteRevolt => "Incite Revolt",
+        }
+    }
+
+    /// Espionage points spent on the mission. They must have been
+    /// accumulated against the victim before the mission can be attempted.
+    pub fn cost(self) -> u32 {
+        match self {
+            EspionageMission::StealTech => 120,
+            EspionageMission::SabotageProduction => 60,
+            EspionageMission::PoisonWater => 80,
+            EspionageMission::InciteRevolt => 150,
+        }
+    }
+
+    /// Gets the chance that the mission succeeds given the
+    /// espionage points accumulated against the victim.
+    ///
+    /// Having exactly the cost gives even odds, which improve
+    /// as more points are stockpiled.
+    pub fn success_chance(self, points: u32) -> f64 {
+        (points as f64 / (2 * self.cost()) as f64).min(MAX_SUCCESS_CHANCE)
+    }
+}
+
+/// A spy that was discovered in one of the victim's cities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpyDiscovered {
+    pub perpetrator: PlayerId,
+    pub victim: PlayerId,
+    pub city: CityId,
+    pub mission: EspionageMission,
+    /// Whether the mission succeeded before the spy was caught.
+    pub success: bool,
+}
+
+/// Gets the techs `victim` knows that `thief` doesn't.
+pub fn stealable_techs(game: &Game, thief: PlayerId, victim: PlayerId) -> Vec<Handle<Tech>> {
+    let thief = game.player(thief);
+    let victim = game.player(victim);
+    let mut techs: Vec<_> = victim
+        .unlocked_techs()
+        .filter(|tech| !thief.has_unlocked_tech(tech))
+        .cloned()
+        .collect();
+    techs.sort_by(|a, b| a.name.cmp(&b.name));
+    techs
+}
+
+/// Carries out a mission and consumes the spy.
+pub(crate) fn perform_mission(
+    game: &mut Game,
+    spy: UnitId,
+    city_id: CityId,
+    mission: EspionageMission,
+) {
+    let perpetrator = game.unit(spy).owner();
+    let victim = game.city(city_id).owner();
+
+    let points = game.player(perpetrator).espionage_points_against(victim);
+    let success = game.rng().gen_bool(mission.success_chance(points));
+    game.player_mut(perpetrator)
+        .spend_espionage_points(victim, mission.cost());
+    game.push_event(Event::PlayerChanged(perpetrator));
+
+    if success {
+        log::info!("{:?} succeeded at {:?}", perpetrator, mission);
+        match mission {
+            EspionageMission::StealTech => {
+                let tech = stealable_techs(game, perpetrator, victim)
+                    .choose(&mut *game.rng())
+                    .cloned();
+                if let Some(tech) = tech {
+                    game.player_mut(perpetrator).unlock_tech(game, tech);
+                }
+            }
+            EspionageMission::SabotageProduction => {
+                game.city_mut(city_id).sabotage_production();
+            }
+            EspionageMission::PoisonWater => {
+                let mut city = game.city_mut(city_id);
+                city.poison_water();
+                city.update_statuses(game);
+            }
+            EspionageMission::InciteRevolt => {
+                let mut city = game.city_mut(city_id);
+                city.incite_revolt();
+                city.update_statuses(game);
+            }
+        }
+        game.push_event(Event::CityChanged(city_id));
+    }
+
+    let discovered = !success || game.rng().gen_bool(DISCOVERED_ON_SUCCESS_CHANCE);
+    if discovered {
+        game.push_event(Event::SpyDiscovered(SpyDiscovered {
+            perpetrator,
+            victim,
+            city: city_id,
+            mission,
+            success,
+        }));
+    }
+
+    game.remove_unit(spy);
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::{player::EconomySlider, testing, unit::CannotPerformMission};
+
+    use super::*;
+
+    #[test]
+    fn missions_need_the_points_they_cost() {
+        let mut game = testing::new_game(8, 5);
+        let player = testing::add_player(&mut game, "rome");
+        let victim = testing::add_player(&mut game, "greece");
+        testing::add_city(&mut game, player, uvec2(1, 2));
+        testing::add_city(&mut game, victim, uvec2(6, 2));
+        let spy = testing::add_unit(&mut game, player, "spy", uvec2(6, 2));
+
+        let mut settings = game.player(player).economy_settings();
+        settings.set_beaker_percent(0);
+        settings.set_percent(EconomySlider::Espionage, 100);
+        game.player_mut(player)
+            .set_economy_settings(settings)
+            .unwrap();
+
+        let mission = EspionageMission::PoisonWater;
+        assert!(matches!(
+            game.unit(spy).can_perform_mission(&game, mission),
+            Err(CannotPerformMission::NotEnoughPoints(80))
+        ));
+
+        for _ in 0..50 {
+            if game.player(player).espionage_points_against(victim) >= mission.cost() {
+                break;
+            }
+            game.player_mut(player).on_turn_end(&game);
+        }
+        let points = game.player(player).espionage_points_against(victim);
+        assert!(points >= mission.cost());
+
+        game.unit_mut(spy).perform_mission(&game, mission).unwrap();
+        game.run_deferred_functions();
+        assert!(!game.is_unit_valid(spy));
+        assert_eq!(
+            game.player(player).espionage_points_against(victim),
+            points - mission.cost()
+        );
+    }
+
+    #[test]
+    fn stockpiled_points_improve_the_odds() {
+        let mission = EspionageMission::StealTech;
+        assert_eq!(mission.success_chance(mission.cost()), 0.5);
+        assert!(mission.success_chance(mission.cost() * 3 / 2) > 0.5);
+        assert_eq!(
+            mission.success_chance(mission.cost() * 10),
+            MAX_SUCCESS_CHANCE
+        );
+    }
+}
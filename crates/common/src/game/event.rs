@@ -1,6 +1,9 @@
 use glam::UVec2;
 
-use crate::{assets::Handle, combat::CombatEvent, registry::Tech, CityId, PlayerId, UnitId};
+use crate::{
+    assets::Handle, combat::CombatEvent, espionage::SpyDiscovered, registry::Tech,
+    tribal_village::TribalVillageEntered, CityId, PlayerId, UnitId,
+};
 
 /// Used to track changes to game state so the server
 /// can send updates to clients.
@@ -16,4 +19,12 @@ pub enum Event {
     PeaceMade(PlayerId, PlayerId),
     CombatEvent(CombatEvent),
     UnitMoved(UnitId, UVec2, UVec2),
+    /// Units moved together to a new position while following an order.
+    UnitsMovedByOrder(Vec<UnitId>, UVec2),
+    /// A sentry or alert unit woke up because an enemy came into view.
+    UnitWoken(UnitId),
+    /// A spy was caught performing a mission.
+    SpyDiscovered(SpyDiscovered),
+    /// A unit entered a tribal village.
+    TribalVillageEntered(TribalVillageEntered),
 }
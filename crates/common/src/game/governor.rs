@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{city::Specialist, Yield};
+
+/// What a city's governor emphasizes when choosing worked tiles.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GovernorFocus {
+    /// Grow steadily while working good production and commerce tiles.
+    #[default]
+    Balanced,
+    Food,
+    Production,
+    Commerce,
+    /// Grow as fast as possible, even past the health cap.
+    GreatGrowth,
+}
+
+impl GovernorFocus {
+    pub fn name(self) -> &'static str {
+        match self {
+            GovernorFocus::Balanced => "Balanced",
+            GovernorFocus::Food => "Food",
+            GovernorFocus::Production => "Production",
+            GovernorFocus::Commerce => "Commerce",
+            GovernorFocus::GreatGrowth => "Great Growth",
+        }
+    }
+
+    fn weights(self) -> FocusWeights {
+        match self {
+            GovernorFocus::Balanced => FocusWeights {
+                growth_target: 2,
+                food_below_target: 6,
+                food_above_target: 2,
+                hammers: 3,
+                commerce: 2,
+            },
+            GovernorFocus::Food => FocusWeights {
+                growth_target: 4,
+                food_below_target: 8,
+                food_above_target: 4,
+                hammers: 2,
+                commerce: 1,
+            },
+            GovernorFocus::Production => FocusWeights {
+                growth_target: 1,
+                food_below_target: 6,
+                food_above_target: 1,
+                hammers: 5,
+                commerce: 1,
+            },
+            GovernorFocus::Commerce => FocusWeights {
+                growth_target: 1,
+                food_below_target: 6,
+                food_above_target: 1,
+                hammers: 1,
+                commerce: 5,
+            },
+            GovernorFocus::GreatGrowth => FocusWeights {
+                growth_target: 6,
+                food_below_target: 8,
+                food_above_target: 6,
+                hammers: 1,
+                commerce: 1,
+            },
+        }
+    }
+}
+
+/// Per-city settings for the governor that
+/// chooses which tiles a city works.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GovernorSettings {
+    pub focus: GovernorFocus,
+    /// Stop growing once another citizen would be unhappy.
+    pub avoid_unhappiness: bool,
+}
+
+/// How much the governor values each yield.
+struct FocusWeights {
+    /// Food surplus per turn the governor tries to reach.
+    growth_target: i32,
+    /// Value of food needed to reach the growth target (or to avoid starvation).
+    food_below_target: u32,
+    /// Value of food beyond the growth target.
+    food_above_target: u32,
+    hammers: u32,
+    commerce: u32,
+}
+
+/// Scores candidate tiles for a city based on its governor settings.
+///
+/// The value of food depends on the city's current food surplus,
+/// so tiles should be chosen one at a time with [`TileScorer::add_worked`]
+/// called after each choice.
+pub struct TileScorer {
+    weights: FocusWeights,
+    food_surplus: i32,
+}
+
+impl TileScorer {
+    /// Creates a scorer for a city with the given food surplus from
+    /// already worked tiles.
+    ///
+    /// `at_happy_cap` and `at_health_cap` indicate whether growing would
+    /// add an unhappy or sick citizen, respectively.
+    pub fn new(
+        settings: GovernorSettings,
+        food_surplus: i32,
+        at_happy_cap: bool,
+        at_health_cap: bool,
+    ) -> Self {
+        let mut weights = settings.focus.weights();
+
+        let growth_capped = (settings.avoid_unhappiness && at_happy_cap)
+            || (at_health_cap && settings.focus != GovernorFocus::GreatGrowth);
+        if growth_capped {
+            // Food is only useful to avoid starvation.
+            weights.growth_target = 0;
+            weights.food_above_target = 0;
+        }
+
+        Self {
+            weights,
+            food_surplus,
+        }
+    }
+
+    pub fn score(&self, tile_yield: Yield) -> u32 {
+        let needed = (self.weights.growth_target - self.food_surplus).max(0) as u32;
+        let food_below_target = tile_yield.food.min(needed);
+        let food_above_target = tile_yield.food - food_below_target;
+
+        food_below_target * self.weights.food_below_target
+            + food_above_target * self.weights.food_above_target
+            + tile_yield.hammers * self.weights.hammers
+            + tile_yield.commerce * self.weights.commerce
+    }
+
+    pub fn score_specialist(&self, specialist: Specialist) -> u32 {
+        let output = specialist.output();
+        output.hammers * self.weights.hammers
+            + (output.beakers + output.gold + output.culture) * self.weights.commerce
+    }
+
+    /// Records that a tile with the given yield is now worked.
+    pub fn add_worked(&mut self, tile_yield: Yield) {
+        self.food_surplus += tile_yield.food as i32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FOOD: Yield = Yield {
+        hammers: 0,
+        commerce: 0,
+        food: 3,
+    };
+    const HAMMERS: Yield = Yield {
+        hammers: 3,
+        commerce: 0,
+        food: 0,
+    };
+    const COMMERCE: Yield = Yield {
+        hammers: 0,
+        commerce: 3,
+        food: 0,
+    };
+
+    fn scorer(focus: GovernorFocus, food_surplus: i32, at_health_cap: bool) -> TileScorer {
+        let settings = GovernorSettings {
+            focus,
+            avoid_unhappiness: false,
+        };
+        TileScorer::new(settings, food_surplus, false, at_health_cap)
+    }
+
+    #[test]
+    fn focus_prefers_its_yield() {
+        let production = scorer(GovernorFocus::Production, 2, false);
+        assert!(production.score(HAMMERS) > production.score(COMMERCE));
+
+        let commerce = scorer(GovernorFocus::Commerce, 2, false);
+        assert!(commerce.score(COMMERCE) > commerce.score(HAMMERS));
+
+        // A starving city needs food regardless of focus.
+        let starving = scorer(GovernorFocus::Commerce, -3, false);
+        assert!(starving.score(FOOD) > starving.score(COMMERCE));
+    }
+
+    #[test]
+    fn food_is_only_valued_up_to_the_growth_target() {
+        let mut scorer = scorer(GovernorFocus::Balanced, 0, false);
+        let before = scorer.score(FOOD);
+        scorer.add_worked(FOOD);
+        assert!(scorer.score(FOOD) < before);
+    }
+
+    #[test]
+    fn great_growth_ignores_the_health_cap() {
+        assert_eq!(scorer(GovernorFocus::Food, 2, true).score(FOOD), 0);
+        assert!(scorer(GovernorFocus::GreatGrowth, 2, true).score(FOOD) > 0);
+    }
+}
@@ -1,252 +1,186 @@
-use std::{mem, str::FromStr};
-
 use crate::{
     assets::Handle,
-    registry::{Registry, Tech},
-    Game, Player, Terrain, Tile, Yield,
+    registry::{HillsRequirement, ImprovementKind, Registry, RegistryItemNotFound, Tech},
+    saveload::legacy,
+    Game, Player, Tile, Yield,
 };
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, thiserror::Error)]
-#[error("unknown cottage level '{0}'")]
-pub struct InvalidCottageLevel(String);
-
-#[derive(Debug, thiserror::Error)]
-#[error("unknown improvement type '{0}'")]
-pub struct InvalidImprovementType(String);
-
 /// A tile improvement.
+///
+/// Improvement kinds are data-driven and loaded into the [`Registry`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum Improvement {
-    Farm,
-    Mine,
-    Road,
-    Pasture,
-    Plantation,
-    Cottage(Cottage),
+pub struct Improvement {
+    kind: Handle<ImprovementKind>,
+    /// Number of turns the tile has been worked since the
+    /// improvement was built or last upgraded.
+    worked_turns: u32,
 }
 
 impl Improvement {
-    pub fn name(&self) -> String {
-        match self {
-            Improvement::Farm => "Farm".to_owned(),
-            Improvement::Mine => "Mine".to_owned(),
-            Improvement::Road => "Road".to_owned(),
-            Improvement::Pasture => "Pasture".to_owned(),
-            Improvement::Plantation => "Plantation".to_owned(),
-            Improvement::Cottage(cottage) => format!("{:?}", cottage.level()),
+    pub fn new(kind: Handle<ImprovementKind>) -> Self {
+        Self {
+            kind,
+            worked_turns: 0,
         }
     }
 
-    pub fn is_compatible_with(&self, other: &Improvement) -> bool {
-        if mem::discriminant(self) == mem::discriminant(other) {
-            return false;
-        }
-
-        if self == &Self::Road || other == &Self::Road {
-            return true;
-        }
-
-        false
+    /// Converts one of the hardcoded improvements of an unversioned save file.
+    pub(crate) fn from_legacy(
+        legacy: legacy::Improvement,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let (id, worked_turns) = match legacy {
+            legacy::Improvement::Farm => ("farm", 0),
+            legacy::Improvement::Mine => ("mine", 0),
+            legacy::Improvement::Road => ("road", 0),
+            legacy::Improvement::Pasture => ("pasture", 0),
+            legacy::Improvement::Plantation => ("plantation", 0),
+            // Legacy cottages counted worked turns cumulatively
+            // across all levels.
+            legacy::Improvement::Cottage(legacy::Cottage { worked_turns }) => match worked_turns {
+                0..=9 => ("cottage", worked_turns),
+                10..=29 => ("hamlet", worked_turns - 10),
+                30..=69 => ("village", worked_turns - 30),
+                70.. => ("town", 0),
+            },
+        };
+        Ok(Self {
+            kind: registry.improvement(id)?,
+            worked_turns,
+        })
     }
 
-    pub fn worker_turns_to_build(&self) -> u32 {
-        match self {
-            Improvement::Farm => 5,
-            Improvement::Mine => 4,
-            Improvement::Road => 2,
-            Improvement::Pasture => 5,
-            Improvement::Plantation => 6,
-            Improvement::Cottage(_) => 4,
-        }
+    pub fn kind(&self) -> &Handle<ImprovementKind> {
+        &self.kind
     }
 
-    pub fn yield_bonus(&self) -> Yield {
-        match self {
-            Improvement::Farm => Yield {
-                food: 1,
-                hammers: 0,
-                commerce: 0,
-            },
-            Improvement::Mine => Yield {
-                food: 0,
-                hammers: 1,
-                commerce: 0,
-            },
-            Improvement::Road => Yield::default(),
-            Improvement::Pasture => Yield::default(),
-            Improvement::Plantation => Yield::default(),
-            Improvement::Cottage(c) => Yield {
-                food: 0,
-                hammers: 0,
-                commerce: c.level() as u32 + 1,
-            },
-        }
+    pub fn name(&self) -> &str {
+        &self.kind.name
     }
 
-    pub fn required_tech(&self, registry: &Registry) -> Handle<Tech> {
-        let id = match self {
-            Improvement::Farm => "Agriculture",
-            Improvement::Mine => "Mining",
-            Improvement::Road => "The Wheel",
-            Improvement::Pasture => "Animal Husbandry",
-            Improvement::Plantation => "Calendar",
-            Improvement::Cottage(_) => "Pottery",
-        };
-        registry.tech(id).unwrap()
+    pub fn is_route(&self) -> bool {
+        self.kind.is_route
     }
 
-    pub fn possible_for_tile(game: &Game, tile: &Tile, builder: &Player) -> Vec<Self> {
-        let mut possible = Vec::new();
+    /// A tile can hold at most one route and one other improvement.
+    pub fn is_compatible_with(&self, other: &Improvement) -> bool {
+        self.is_route() != other.is_route()
+    }
 
-        let owner = tile.owner(game);
-        if (owner.is_none() || owner == Some(builder.id()))
-            && !tile.has_improvement(Improvement::Road)
-        {
-            possible.push(Improvement::Road);
-        }
+    pub fn worker_turns_to_build(&self) -> u32 {
+        self.kind.worker_turns
+    }
 
-        if owner == Some(builder.id()) {
-            if (!tile.is_hilled() || tile.has_improveable_resource("Farm"))
-                && (tile.terrain() != Terrain::Desert || tile.is_flood_plains())
-            {
-                if tile.has_fresh_water() || tile.has_improveable_resource("Farm") {
-                    possible.push(Improvement::Farm);
-                }
-                if tile.terrain() != Terrain::Tundra {
-                    possible.push(Improvement::Cottage(Cottage::default()));
+    /// Gets the yield bonus of the improvement, including
+    /// bonuses from techs known to `owner`.
+    pub fn yield_bonus(&self, registry: &Registry, owner: Option<&Player>) -> Yield {
+        let mut y = self.kind.yield_bonus;
+        if let Some(owner) = owner {
+            for bonus in &self.kind.tech_yield_bonuses {
+                if let Ok(tech) = registry.tech(&bonus.tech) {
+                    if owner.has_unlocked_tech(&tech) {
+                        y = y + bonus.yield_bonus;
+                    }
                 }
             }
-
-            if tile.is_hilled()
-                || tile
-                    .resource()
-                    .map(|r| {
-                        r.improvement == "Mine"
-                            && builder
-                                .has_unlocked_tech(&game.registry().tech(&r.revealed_by).unwrap())
-                    })
-                    .unwrap_or(false)
-            {
-                possible.push(Improvement::Mine);
-            }
-
-            if tile
-                .resource()
-                .map(|r| {
-                    r.improvement == "Pasture"
-                        && builder.has_unlocked_tech(&game.registry().tech(&r.revealed_by).unwrap())
-                })
-                .unwrap_or(false)
-            {
-                possible.push(Improvement::Pasture);
-            }
-
-            if tile
-                .resource()
-                .map(|r| {
-                    r.improvement == "Plantation"
-                        && builder.has_unlocked_tech(&game.registry().tech(&r.revealed_by).unwrap())
-                })
-                .unwrap_or(false)
-            {
-                possible.push(Improvement::Plantation);
-            }
         }
-
-        possible.retain(|i| builder.has_unlocked_tech(&i.required_tech(game.registry())));
-        possible.retain(|i| {
-            !tile
-                .improvements()
-                .any(|i2| mem::discriminant(i) == mem::discriminant(i2))
-        });
-
-        possible
+        y
     }
-}
 
-impl FromStr for Improvement {
-    type Err = InvalidImprovementType;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Farm" => Ok(Improvement::Farm),
-            "Mine" => Ok(Improvement::Mine),
-            "Road" => Ok(Improvement::Road),
-            "Pasture" => Ok(Improvement::Pasture),
-            "Cottage" => Ok(Improvement::Cottage(Cottage::default())),
-            s => Err(InvalidImprovementType(s.to_owned())),
-        }
+    /// Gold gained by an enemy unit that pillages the improvement.
+    pub fn pillage_gold(&self) -> u32 {
+        self.kind.pillage_gold
     }
-}
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct Cottage {
-    worked_turns: u32,
-}
-
-impl Cottage {
-    pub fn level(&self) -> CottageLevel {
-        match self.worked_turns {
-            0..=9 => CottageLevel::Cottage,
-            10..=29 => CottageLevel::Hamlet,
-            30..=69 => CottageLevel::Village,
-            70.. => CottageLevel::Town,
-        }
+    pub fn required_tech(&self, registry: &Registry) -> Handle<Tech> {
+        required_tech(&self.kind, registry)
     }
 
     pub fn worked_turns(&self) -> u32 {
         self.worked_turns
     }
 
-    pub fn work(&mut self) {
-        self.worked_turns += 1;
+    /// Gets the improvement this one will upgrade to when worked.
+    pub fn next_upgrade(&self, registry: &Registry) -> Option<Handle<ImprovementKind>> {
+        self.kind
+            .upgrade
+            .as_ref()
+            .and_then(|upgrade| registry.improvement(&upgrade.to).ok())
     }
 
-    pub fn turns_to_next_level(&self) -> u32 {
-        match self.level() {
-            CottageLevel::Cottage => 10 - self.worked_turns,
-            CottageLevel::Hamlet => 30 - self.worked_turns,
-            CottageLevel::Village => 70 - self.worked_turns,
-            CottageLevel::Town => 0,
-        }
+    pub fn turns_to_upgrade(&self) -> Option<u32> {
+        self.kind
+            .upgrade
+            .as_ref()
+            .map(|upgrade| upgrade.worked_turns.saturating_sub(self.worked_turns))
     }
 
-    pub fn next_level(&self) -> Option<CottageLevel> {
-        match self.level() {
-            CottageLevel::Cottage => Some(CottageLevel::Hamlet),
-            CottageLevel::Hamlet => Some(CottageLevel::Village),
-            CottageLevel::Village => Some(CottageLevel::Town),
-            CottageLevel::Town => None,
+    /// Should be called at the end of a turn if a city is working the tile.
+    pub fn work(&mut self, registry: &Registry) {
+        if self.kind.upgrade.is_none() {
+            return;
+        }
+
+        self.worked_turns += 1;
+        if self.turns_to_upgrade() == Some(0) {
+            if let Some(upgrade) = self.next_upgrade(registry) {
+                self.kind = upgrade;
+                self.worked_turns = 0;
+            }
         }
     }
-}
 
-impl Default for Cottage {
-    fn default() -> Self {
-        Self { worked_turns: 0 }
+    pub fn possible_for_tile(game: &Game, tile: &Tile, builder: &Player) -> Vec<Self> {
+        let registry = game.registry();
+        let owner = tile.owner(game);
+
+        let has_revealed_resource = |kind: &ImprovementKind| {
+            tile.resource()
+                .map(|r| r.improvement == kind.name && builder.has_revealed_resource(registry, r))
+                .unwrap_or(false)
+        };
+
+        registry
+            .improvements()
+            .filter(|kind| !kind.only_by_upgrade)
+            .filter(|kind| owner == Some(builder.id()) || (owner.is_none() && kind.is_route))
+            .filter(|kind| builder.has_unlocked_tech(&required_tech(kind, registry)))
+            .filter(|kind| meets_requirements(kind, tile) || has_revealed_resource(kind))
+            .filter(|kind| {
+                // Don't allow building an improvement already present
+                // on the tile, including upgraded versions of it.
+                !tile.improvements().any(|existing| {
+                    existing.kind() == *kind
+                        || registry.is_improvement_upgrade(kind, existing.kind())
+                })
+            })
+            .map(|kind| Improvement::new(kind.clone()))
+            .collect()
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-pub enum CottageLevel {
-    Cottage,
-    Hamlet,
-    Village,
-    Town,
+fn required_tech(kind: &ImprovementKind, registry: &Registry) -> Handle<Tech> {
+    registry
+        .tech(&kind.required_tech)
+        .expect("improvement techs are checked when loading the registry")
 }
 
-impl FromStr for CottageLevel {
-    type Err = InvalidCottageLevel;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Cottage" => Ok(CottageLevel::Cottage),
-            "Hamlet" => Ok(CottageLevel::Hamlet),
-            "Village" => Ok(CottageLevel::Village),
-            "Town" => Ok(CottageLevel::Town),
-            s => Err(InvalidCottageLevel(s.to_owned())),
-        }
+fn meets_requirements(kind: &ImprovementKind, tile: &Tile) -> bool {
+    let requirements = &kind.requirements;
+    if requirements.resource_only || !tile.terrain().is_passable() {
+        return false;
     }
+
+    let terrain_allowed = requirements.terrains.is_empty()
+        || requirements.terrains.contains(&tile.terrain())
+        || (requirements.flood_plains && tile.is_flood_plains());
+    let hills_allowed = match requirements.hills {
+        HillsRequirement::Allowed => true,
+        HillsRequirement::Required => tile.is_hilled(),
+        HillsRequirement::Forbidden => !tile.is_hilled(),
+    };
+
+    terrain_allowed && hills_allowed && (!requirements.fresh_water || tile.has_fresh_water())
 }
@@ -0,0 +1,578 @@
+//! Execution of standing unit orders. (Server only.)
+
+use std::{collections::VecDeque, iter};
+
+use ahash::AHashSet;
+use glam::UVec2;
+
+use crate::{
+    event::Event,
+    path::{MovementRules, Path, Pathfinder, UnitMovementRules},
+    tile::ROUTE_TILES_PER_MOVEMENT,
+    unit::{MovementPoints, UnitOrder},
+    worker::WorkerTask,
+    Game, PlayerId, Terrain, UnitId, Visibility,
+};
+
+/// Maximum number of times an exploring unit picks a new
+/// target during one turn.
+const MAX_EXPLORE_LEGS_PER_TURN: usize = 8;
+
+/// Number of the best-rated worker tasks an automated worker
+/// tries to find a path to before giving up for the turn.
+const MAX_WORKER_TARGET_ATTEMPTS: usize = 5;
+
+/// Score of building a road tile that helps connect two cities,
+/// on the same scale as [`TileScorer`](crate::governor::TileScorer) scores.
+const CONNECT_CITIES_ROAD_SCORE: u32 = 8;
+
+/// Units on the same tile that were ordered to the same target.
+///
+/// They move together so that the stack stays intact.
+struct MoveGroup {
+    owner: PlayerId,
+    pos: UVec2,
+    target: UVec2,
+    units: Vec<UnitId>,
+}
+
+/// Carries out the orders of the given units.
+pub fn execute_unit_orders(game: &mut Game, units: &[UnitId]) {
+    let mut move_groups: Vec<MoveGroup> = Vec::new();
+    let mut explorers = Vec::new();
+    let mut automated_workers = Vec::new();
+    for &id in units {
+        if !game.is_unit_valid(id) {
+            continue;
+        }
+        let unit = game.unit(id);
+        match unit.order() {
+            Some(UnitOrder::MoveTo { target, .. }) => {
+                let target = *target;
+                match move_groups
+                    .iter_mut()
+                    .find(|g| g.owner == unit.owner() && g.pos == unit.pos() && g.target == target)
+                {
+                    Some(group) => group.units.push(id),
+                    None => move_groups.push(MoveGroup {
+                        owner: unit.owner(),
+                        pos: unit.pos(),
+                        target,
+                        units: vec![id],
+                    }),
+                }
+            }
+            Some(UnitOrder::Explore) => explorers.push(id),
+            Some(UnitOrder::AutomateWorker) => automated_workers.push(id),
+            None => {}
+        }
+    }
+
+    for group in move_groups {
+        execute_move_order(game, group);
+    }
+    for unit in explorers {
+        execute_explore_order(game, unit);
+    }
+    for unit in automated_workers {
+        execute_automate_worker_order(game, unit);
+    }
+}
+
+/// Moves a group along the shortest path to its target
+/// until it runs out of movement, arrives, or is interrupted.
+fn execute_move_order(game: &mut Game, group: MoveGroup) {
+    let visible_enemies = visible_enemy_units(game, group.owner);
+    let mut pathfinder = Pathfinder::new();
+
+    let rules = UnitMovementRules::new(group.owner, group.units.iter().map(|&u| game.unit(u)));
+    let finished = match pathfinder.compute_shortest_path(game, &rules, group.pos, group.target) {
+        Some(path) => {
+            follow_path(game, &group.units, path, Some(&visible_enemies))
+                != FollowOutcome::OutOfMovement
+        }
+        None => true,
+    };
+
+    // Update the committed path for the remaining part of the move.
+    let pos = game.unit(group.units[0]).pos();
+    let order = if finished {
+        None
+    } else {
+        let rules = UnitMovementRules::new(group.owner, group.units.iter().map(|&u| game.unit(u)));
+        pathfinder
+            .compute_shortest_path(game, &rules, pos, group.target)
+            .map(|path| UnitOrder::MoveTo {
+                target: group.target,
+                path,
+            })
+    };
+
+    for &u in &group.units {
+        game.unit_mut(u).set_order(order.clone());
+        game.push_event(Event::UnitChanged(u));
+    }
+}
+
+/// Moves an exploring unit toward the nearest tiles its owner has never seen.
+///
+/// The order ends once nothing reachable is left to explore.
+fn execute_explore_order(game: &mut Game, unit: UnitId) {
+    let owner = game.unit(unit).owner();
+    let mut pathfinder = Pathfinder::new();
+
+    let mut finished = false;
+    for _ in 0..MAX_EXPLORE_LEGS_PER_TURN {
+        if !game.unit(unit).has_movement_left() {
+            break;
+        }
+
+        let pos = game.unit(unit).pos();
+        let rules = AvoidingDanger::new(
+            owner,
+            UnitMovementRules::new(owner, iter::once(game.unit(unit))),
+        );
+        let path = match find_exploration_target(game, owner, &rules, pos)
+            .and_then(|target| pathfinder.compute_shortest_path(game, &rules, pos, target))
+        {
+            Some(path) => path,
+            None => {
+                finished = true;
+                break;
+            }
+        };
+
+        if follow_path(game, &[unit], path, None) != FollowOutcome::Arrived {
+            // Blocked or out of movement; try again next turn.
+            break;
+        }
+    }
+
+    if finished {
+        game.unit_mut(unit).set_order(None);
+    }
+    game.push_event(Event::UnitChanged(unit));
+}
+
+/// Finds the closest tile reachable under `rules` that
+/// borders a tile `player` has never seen.
+fn find_exploration_target(
+    game: &Game,
+    player: PlayerId,
+    rules: &impl MovementRules,
+    start: UVec2,
+) -> Option<UVec2> {
+    let player = game.player(player);
+    let mut queue = VecDeque::from([start]);
+    let mut visited: AHashSet<UVec2> = iter::once(start).collect();
+
+    while let Some(pos) = queue.pop_front() {
+        if pos != start
+            && game
+                .map()
+                .adjacent(pos)
+                .into_iter()
+                .any(|p| player.visibility_at(p) == Visibility::Hidden)
+        {
+            return Some(pos);
+        }
+
+        for neighbor in game.map().adjacent(pos) {
+            if rules.can_move(game, pos, neighbor, false) && visited.insert(neighbor) {
+                queue.push_back(neighbor);
+            }
+        }
+    }
+
+    None
+}
+
+/// Sends an automated worker to the most useful task near its
+/// owner's cities. Once there, the worker builds it like a manually
+/// assigned task; a new task is chosen after it completes.
+fn execute_automate_worker_order(game: &mut Game, unit: UnitId) {
+    if game.unit(unit).has_worker_task() || !game.unit(unit).has_movement_left() {
+        return;
+    }
+
+    let owner = game.unit(unit).owner();
+    let mut pathfinder = Pathfinder::new();
+    let rules = AvoidingDanger::new(
+        owner,
+        UnitMovementRules::new(owner, iter::once(game.unit(unit))),
+    );
+
+    for (target, task) in rank_worker_tasks(game, unit)
+        .into_iter()
+        .take(MAX_WORKER_TARGET_ATTEMPTS)
+    {
+        let pos = game.unit(unit).pos();
+        let path = match pathfinder.compute_shortest_path(game, &rules, pos, target) {
+            Some(path) => path,
+            None => continue,
+        };
+
+        if follow_path(game, &[unit], path, None) == FollowOutcome::Arrived {
+            game.unit_mut(unit).set_worker_task(Some(task));
+        }
+        break;
+    }
+
+    game.push_event(Event::UnitChanged(unit));
+}
+
+/// Lists the tasks an automated worker could take on,
+/// from most to least useful.
+///
+/// Improvements are rated by how much they raise the tile's value to
+/// the city that can work it. Tiles worked right now are preferred,
+/// and closer tasks are preferred over distant ones. Roads are
+/// planned between each city and its nearest unconnected peer.
+fn rank_worker_tasks(game: &Game, worker: UnitId) -> Vec<(UVec2, WorkerTask)> {
+    let worker = game.unit(worker);
+    let player = game.player(worker.owner());
+
+    // Don't send a worker where another one is already busy.
+    let busy_tiles: AHashSet<UVec2> = player
+        .units()
+        .iter()
+        .map(|&u| game.unit(u))
+        .filter(|u| u.id() != worker.id() && u.has_worker_task())
+        .map(|u| u.pos())
+        .collect();
+
+    let mut candidates: Vec<(UVec2, WorkerTask, u32)> = Vec::new();
+
+    for &city_id in player.cities() {
+        let city = game.city(city_id);
+        let scorer = city.tile_scorer();
+        for pos in game.map().big_fat_cross(city.pos()) {
+            if !city.can_work_tile(game, pos)
+                || busy_tiles.contains(&pos)
+                || is_dangerous(game, player.id(), pos)
+            {
+                continue;
+            }
+
+            let tile = game.tile(pos).unwrap();
+            let current_score = scorer.score(tile.tile_yield(game));
+            for task in WorkerTask::possible_for_tile(game, &tile, pos, &player) {
+                let improvement = match &task {
+                    WorkerTask::BuildImprovement(improvement) if !improvement.is_route() => {
+                        improvement
+                    }
+                    _ => continue,
+                };
+
+                let mut improved_tile = tile.clone();
+                improved_tile.add_improvement(improvement.clone());
+                let mut score = scorer
+                    .score(improved_tile.tile_yield(game))
+                    .saturating_sub(current_score);
+                if !tile.is_worked() {
+                    score /= 2;
+                }
+                if score > 0 {
+                    candidates.push((pos, task, score));
+                }
+            }
+        }
+    }
+
+    let mut pathfinder = Pathfinder::new();
+    let mut road_tiles = AHashSet::new();
+    for &city_id in player.cities() {
+        let city = game.city(city_id);
+        let nearest_unconnected = player
+            .cities()
+            .iter()
+            .filter(|&&peer| peer != city_id && !city.is_connected_to_city(peer))
+            .min_by_key(|&&peer| {
+                game.map()
+                    .distance_squared(game.city(peer).pos(), city.pos())
+            });
+        let peer = match nearest_unconnected {
+            Some(&peer) => game.city(peer),
+            None => continue,
+        };
+
+        let rules = RoadPlanningRules {
+            player: player.id(),
+        };
+        let path = match pathfinder.compute_shortest_path(game, &rules, city.pos(), peer.pos()) {
+            Some(path) => path,
+            None => continue,
+        };
+        for point in path.points() {
+            let pos = point.pos;
+            if busy_tiles.contains(&pos) || is_dangerous(game, player.id(), pos) {
+                continue;
+            }
+            let tile = game.tile(pos).unwrap();
+            let route = WorkerTask::possible_for_tile(game, &tile, pos, &player)
+                .into_iter()
+                .find(|task| matches!(task, WorkerTask::BuildImprovement(i) if i.is_route()));
+            if let Some(route) = route {
+                if road_tiles.insert(pos) {
+                    candidates.push((pos, route, CONNECT_CITIES_ROAD_SCORE));
+                }
+            }
+        }
+    }
+
+    let priority = |pos: UVec2, score: u32| {
+        let distance = game.map().tile_distance(pos, worker.pos());
+        score as f64 / (distance + 1) as f64
+    };
+    candidates.sort_by(|(pos_a, _, score_a), (pos_b, _, score_b)| {
+        priority(*pos_b, *score_b).total_cmp(&priority(*pos_a, *score_a))
+    });
+    candidates
+        .into_iter()
+        .map(|(pos, task, _)| (pos, task))
+        .collect()
+}
+
+/// How following a path ended.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum FollowOutcome {
+    /// The units reached the end of the path.
+    Arrived,
+    /// The units have to wait for the next turn to continue.
+    OutOfMovement,
+    /// The next tile can't be entered without fighting or at all.
+    Blocked,
+    /// A new enemy came into view.
+    Interrupted,
+}
+
+/// Moves a stack of units along a path, one tile at a time.
+///
+/// If `known_enemies` is given, stops as soon as an enemy unit
+/// not in that set becomes visible.
+fn follow_path(
+    game: &mut Game,
+    units: &[UnitId],
+    mut path: Path,
+    known_enemies: Option<&AHashSet<UnitId>>,
+) -> FollowOutcome {
+    let owner = game.unit(units[0]).owner();
+    while let Some(point) = path.next() {
+        // Orders never attack; the player decides whether to fight.
+        if units
+            .iter()
+            .any(|&u| game.unit(u).attack_target(game, point.pos).is_some())
+        {
+            return FollowOutcome::Blocked;
+        }
+
+        if units.iter().any(|&u| !game.unit(u).has_movement_left()) {
+            return FollowOutcome::OutOfMovement;
+        }
+
+        if units
+            .iter()
+            .any(|&u| !game.unit(u).can_move_to(game, point.pos))
+        {
+            return FollowOutcome::Blocked;
+        }
+
+        for &u in units {
+            game.unit_mut(u).move_to(game, point.pos);
+        }
+        game.push_event(Event::UnitsMovedByOrder(units.to_vec(), point.pos));
+
+        // Updates the position index and the owner's visibility.
+        game.run_deferred_functions();
+
+        if let Some(known_enemies) = known_enemies {
+            if visible_enemy_units(game, owner)
+                .difference(known_enemies)
+                .next()
+                .is_some()
+            {
+                return FollowOutcome::Interrupted;
+            }
+        }
+    }
+
+    FollowOutcome::Arrived
+}
+
+/// Wraps movement rules to keep units that act on their own
+/// away from tiles next to visible enemy combat units.
+struct AvoidingDanger<R> {
+    player: PlayerId,
+    rules: R,
+}
+
+impl<R> AvoidingDanger<R> {
+    fn new(player: PlayerId, rules: R) -> Self {
+        Self { player, rules }
+    }
+}
+
+impl<R: MovementRules> MovementRules for AvoidingDanger<R> {
+    fn can_move(&self, game: &Game, from: UVec2, to: UVec2, is_destination: bool) -> bool {
+        self.rules.can_move(game, from, to, is_destination) && !is_dangerous(game, self.player, to)
+    }
+
+    fn movement_cost(&self, game: &Game, from: UVec2, to: UVec2) -> MovementPoints {
+        self.rules.movement_cost(game, from, to)
+    }
+
+    fn must_stop(&self, game: &Game, from: UVec2, to: UVec2) -> bool {
+        self.rules.must_stop(game, from, to)
+    }
+
+    fn movement_left(&self) -> MovementPoints {
+        self.rules.movement_left()
+    }
+
+    fn movement_per_turn(&self) -> u32 {
+        self.rules.movement_per_turn()
+    }
+}
+
+/// Returns whether a visible enemy combat unit of `player`
+/// is on or next to `pos`.
+fn is_dangerous(game: &Game, player: PlayerId, pos: UVec2) -> bool {
+    let player = game.player(player);
+    iter::once(pos)
+        .chain(game.map().adjacent(pos))
+        .filter(|&p| player.visibility_at(p) == Visibility::Visible)
+        .any(|p| {
+            game.units_by_pos(p).any(|u| {
+                u.strength() > 0. && player.is_at_war_with(u.owner()) && player.can_see_unit(&u)
+            })
+        })
+}
+
+/// Rules for planning a road between two cities.
+///
+/// Roads can only be built on land that is unowned or owned by the
+/// player. Tiles that already have a road are cheap to cross, so
+/// planned roads reuse existing ones.
+struct RoadPlanningRules {
+    player: PlayerId,
+}
+
+impl MovementRules for RoadPlanningRules {
+    fn can_move(&self, game: &Game, _from: UVec2, to: UVec2, _is_destination: bool) -> bool {
+        if game.player(self.player).visibility_at(to) == Visibility::Hidden {
+            return false;
+        }
+
+        let tile = game.tile(to).unwrap();
+        !matches!(tile.terrain(), Terrain::Ocean | Terrain::Mountains)
+            && !matches!(tile.owner(game), Some(owner) if owner != self.player)
+    }
+
+    fn movement_cost(&self, game: &Game, _from: UVec2, to: UVec2) -> MovementPoints {
+        if game.tile(to).unwrap().has_route() || game.city_at_pos(to).is_some() {
+            MovementPoints::from_fixed_u32(
+                MovementPoints::from_u32(1).as_fixed_u32() / ROUTE_TILES_PER_MOVEMENT,
+            )
+        } else {
+            MovementPoints::from_u32(1)
+        }
+    }
+
+    fn must_stop(&self, _game: &Game, _from: UVec2, _to: UVec2) -> bool {
+        false
+    }
+
+    fn movement_left(&self) -> MovementPoints {
+        MovementPoints::from_u32(1)
+    }
+
+    fn movement_per_turn(&self) -> u32 {
+        1
+    }
+}
+
+/// Gets the enemy units currently visible to a player.
+fn visible_enemy_units(game: &Game, player: PlayerId) -> AHashSet<UnitId> {
+    let player = game.player(player);
+    game.units()
+        .filter(|u| player.is_at_war_with(u.owner()) && player.can_see_unit(u))
+        .map(|u| u.id())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use glam::uvec2;
+
+    use crate::{testing, unit::MovementPoints};
+
+    use super::*;
+
+    #[test]
+    fn move_orders_continue_over_multiple_turns() {
+        let mut game = testing::new_game(8, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let unit = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+        // Reveals the tiles around the target.
+        let target = uvec2(4, 1);
+        testing::add_unit(&mut game, player, "warrior", target);
+
+        let rules = UnitMovementRules::new(player, iter::once(game.unit(unit)));
+        let path = Pathfinder::new()
+            .compute_shortest_path(&game, &rules, uvec2(1, 1), target)
+            .unwrap();
+        game.unit_mut(unit)
+            .set_order(Some(UnitOrder::MoveTo { target, path }));
+
+        execute_unit_orders(&mut game, &[unit]);
+        assert_eq!(game.unit(unit).pos(), uvec2(2, 1));
+        assert!(matches!(
+            game.unit(unit).order(),
+            Some(UnitOrder::MoveTo { path, .. }) if path.start().pos == uvec2(2, 1)
+        ));
+
+        for _ in 0..2 {
+            game.unit_mut(unit)
+                .set_movement_left_unsafe(MovementPoints::from_u32(1));
+            execute_unit_orders(&mut game, &[unit]);
+        }
+        assert_eq!(game.unit(unit).pos(), target);
+        assert_eq!(game.unit(unit).order(), None);
+    }
+
+    #[test]
+    fn explorers_head_for_unseen_tiles() {
+        let mut game = testing::new_game(12, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let unit = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+        game.unit_mut(unit).set_order(Some(UnitOrder::Explore));
+
+        execute_unit_orders(&mut game, &[unit]);
+        assert_eq!(game.unit(unit).pos().x, 2);
+        assert_eq!(game.unit(unit).order(), Some(&UnitOrder::Explore));
+    }
+
+    #[test]
+    fn automated_workers_improve_city_tiles() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "rome");
+        testing::add_city(&mut game, player, uvec2(2, 2));
+        game.tile_mut(uvec2(3, 2))
+            .unwrap()
+            .set_has_fresh_water(true);
+
+        let worker = testing::add_unit(&mut game, player, "worker", uvec2(2, 2));
+        game.unit_mut(worker)
+            .set_order(Some(UnitOrder::AutomateWorker));
+
+        execute_unit_orders(&mut game, &[worker]);
+        let worker = game.unit(worker);
+        assert_eq!(worker.pos(), uvec2(3, 2));
+        assert!(matches!(
+            worker.worker_task(),
+            Some(WorkerTask::BuildImprovement(i)) if i.kind().id == "farm"
+        ));
+    }
+}
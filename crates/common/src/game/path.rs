@@ -0,0 +1,427 @@
+//! Unit pathing.
+
+use std::{cell::Ref, collections::BinaryHeap};
+
+use ahash::{AHashMap, AHashSet};
+use glam::UVec2;
+use serde::{Deserialize, Serialize};
+
+use crate::{unit::MovementPoints, Game, Player, PlayerId, Terrain, Unit, Visibility};
+
+/// A point on a path.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathPoint {
+    /// The position of the tile
+    pub pos: UVec2,
+    /// The number of turns from the start it takes to arrive here
+    pub turn: u32,
+    /// The movement the unit has left at this point.
+    pub movement_left: MovementPoints,
+}
+
+/// A path between two points.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Path {
+    points: Vec<PathPoint>,
+}
+
+impl Path {
+    pub fn new(points: Vec<PathPoint>) -> Self {
+        assert!(!points.is_empty(), "path cannot be empty");
+        Self { points }
+    }
+
+    pub fn start(&self) -> PathPoint {
+        *self.points.first().unwrap()
+    }
+
+    pub fn end(&self) -> PathPoint {
+        *self.points.last().unwrap()
+    }
+
+    pub fn points(&self) -> &[PathPoint] {
+        &self.points
+    }
+
+    /// Gets the next point to move to, if we begin at the starting position.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<PathPoint> {
+        if self.points.len() == 1 {
+            None
+        } else {
+            Some(self.points.remove(1))
+        }
+    }
+
+    pub fn peek(&self) -> Option<PathPoint> {
+        self.points.get(1).copied()
+    }
+}
+
+/// Determines where units can move and what moves cost.
+///
+/// Used by the [`Pathfinder`]. All positions passed to these
+/// methods are adjacent.
+pub trait MovementRules {
+    /// Returns whether the units can move from `from` to `to`.
+    ///
+    /// `is_destination` is set if `to` is the end of the path.
+    fn can_move(&self, game: &Game, from: UVec2, to: UVec2, is_destination: bool) -> bool;
+
+    /// Gets the movement cost of moving from `from` to `to`.
+    fn movement_cost(&self, game: &Game, from: UVec2, to: UVec2) -> MovementPoints;
+
+    /// Returns whether the units have to stop for the rest of
+    /// the turn after moving from `from` to `to`.
+    fn must_stop(&self, game: &Game, from: UVec2, to: UVec2) -> bool;
+
+    /// Gets the movement the units have left on the current turn.
+    fn movement_left(&self) -> MovementPoints;
+
+    /// Gets the movement the units have at the start of each turn.
+    fn movement_per_turn(&self) -> u32;
+}
+
+/// The movement rules for a stack of units, as seen by their owner.
+///
+/// Tiles the player has never seen are avoided, and only enemy units
+/// the player can currently see block the path or exert zones of control.
+#[derive(Debug, Clone)]
+pub struct UnitMovementRules {
+    player: PlayerId,
+    is_ship: bool,
+    can_fight: bool,
+    /// Whether all the units are invisible and thus
+    /// ignore zones of control.
+    is_invisible: bool,
+    movement_left: MovementPoints,
+    movement_per_turn: u32,
+}
+
+impl UnitMovementRules {
+    /// Creates the movement rules for the given units,
+    /// which must all belong to `player`.
+    pub fn new<'a>(player: PlayerId, units: impl IntoIterator<Item = Ref<'a, Unit>>) -> Self {
+        let mut rules = Self {
+            player,
+            is_ship: false,
+            can_fight: false,
+            is_invisible: true,
+            movement_left: MovementPoints::from_fixed_u32(u32::MAX),
+            movement_per_turn: u32::MAX,
+        };
+        for unit in units {
+            rules.is_ship |= unit.kind().ship;
+            if unit.movement_left().as_fixed_u32() < rules.movement_left.as_fixed_u32() {
+                rules.movement_left = unit.movement_left();
+            }
+            rules.movement_per_turn = rules.movement_per_turn.min(unit.max_movement());
+            rules.can_fight |= unit.kind().strength > 0.;
+            rules.is_invisible &= unit.is_invisible();
+        }
+        rules
+    }
+}
+
+impl MovementRules for UnitMovementRules {
+    fn can_move(&self, game: &Game, _from: UVec2, to: UVec2, is_destination: bool) -> bool {
+        let player = game.player(self.player);
+        let visibility = player.visibility_at(to);
+        if visibility == Visibility::Hidden {
+            return false;
+        }
+
+        let tile = game.tile(to).unwrap();
+        if !self.is_ship && tile.terrain() == Terrain::Ocean {
+            return false;
+        }
+        if tile.terrain() == Terrain::Mountains {
+            return false;
+        }
+        if self.is_ship && tile.terrain() != Terrain::Ocean && game.city_at_pos(to).is_none() {
+            return false;
+        }
+
+        // Enemy units block the path, unless we're attacking them at the destination.
+        if (!is_destination || !self.can_fight) && visibility == Visibility::Visible {
+            for unit in game.units_by_pos(to) {
+                if player.is_at_war_with(unit.owner()) && player.can_see_unit(&unit) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+
+    fn movement_cost(&self, game: &Game, from: UVec2, to: UVec2) -> MovementPoints {
+        movement_cost(game, &game.player(self.player), from, to)
+    }
+
+    fn must_stop(&self, game: &Game, from: UVec2, to: UVec2) -> bool {
+        !self.is_ship
+            && !self.is_invisible
+            && is_zone_of_control_move(game, &game.player(self.player), from, to, true)
+    }
+
+    fn movement_left(&self) -> MovementPoints {
+        self.movement_left
+    }
+
+    fn movement_per_turn(&self) -> u32 {
+        self.movement_per_turn
+    }
+}
+
+/// Gets the cost for a unit owned by `player` to move
+/// from `from` to the adjacent tile `to`.
+///
+/// Crossing a river costs an extra movement point
+/// unless both tiles have a road (i.e., a bridge).
+pub fn movement_cost(game: &Game, player: &Player, from: UVec2, to: UVec2) -> MovementPoints {
+    let mut cost = game.tile(to).unwrap().movement_cost(game, player);
+
+    let has_bridge = game.tile(from).unwrap().has_route() && game.tile(to).unwrap().has_route();
+    if game.rivers().is_crossing(game.map(), from, to) && !has_bridge {
+        cost += MovementPoints::from_u32(1);
+    }
+
+    cost
+}
+
+/// Returns whether a land unit owned by `player` moving from `from`
+/// to the adjacent tile `to` is stopped by a zone of control: both tiles
+/// are adjacent to a combat unit of a player we're at war with.
+///
+/// If `only_visible` is set, only enemy units the player can see are considered.
+pub fn is_zone_of_control_move(
+    game: &Game,
+    player: &Player,
+    from: UVec2,
+    to: UVec2,
+    only_visible: bool,
+) -> bool {
+    let exerts_zone_of_control = |pos: UVec2| {
+        (!only_visible || player.visibility_at(pos) == Visibility::Visible)
+            && game
+                .other_units_by_pos(pos)
+                .any(|u| u.strength() > 0. && player.is_at_war_with(u.owner()))
+    };
+
+    game.map()
+        .adjacent(from)
+        .into_iter()
+        .any(exerts_zone_of_control)
+        && game
+            .map()
+            .adjacent(to)
+            .into_iter()
+            .any(exerts_zone_of_control)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct OpenEntry {
+    score: f64,
+    pos: UVec2,
+}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.score.total_cmp(&other.score).reverse() // reverse for min-heap
+    }
+}
+
+impl Eq for OpenEntry {}
+
+/// Pathfinding engine. Uses A* to compute shortest paths.
+///
+/// Retains heap allocations for efficiency.
+#[derive(Default)]
+pub struct Pathfinder {
+    open_set: BinaryHeap<OpenEntry>,
+    in_open_set: AHashSet<UVec2>,
+    came_from: AHashMap<UVec2, UVec2>,
+    g_score: AHashMap<UVec2, f64>,
+    f_score: AHashMap<UVec2, f64>,
+}
+
+impl Pathfinder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the shortest path between two points under the given movement rules.
+    ///
+    /// Returns `None` if no possible path exists.
+    pub fn compute_shortest_path(
+        &mut self,
+        game: &Game,
+        rules: &impl MovementRules,
+        start: UVec2,
+        end: UVec2,
+    ) -> Option<Path> {
+        let total_dist = game.map().distance(start, end);
+        self.open_set.push(OpenEntry {
+            score: total_dist,
+            pos: start,
+        });
+        self.in_open_set.insert(start);
+
+        self.g_score.insert(start, 0.);
+        self.f_score.insert(start, total_dist);
+
+        let mut result = None;
+
+        let movement_per_turn = rules.movement_per_turn();
+
+        while let Some(entry) = self.open_set.pop() {
+            self.in_open_set.remove(&entry.pos);
+
+            if entry.pos == end {
+                // Found a path. Trace it back and return.
+                let mut points = vec![end];
+                let mut current = end;
+                while let Some(came_from) = self.came_from.get(&current) {
+                    current = *came_from;
+                    points.push(current);
+                }
+                points.reverse();
+                assert_eq!(points[0], start);
+
+                // Simulate movement to determine turn offsets.
+                let mut path_points = Vec::with_capacity(points.len());
+                let mut current_movement_left = rules.movement_left();
+                let mut current_turn = 0;
+                let mut prev_pos = start;
+                for (i, pos) in points.into_iter().enumerate() {
+                    let mut must_stop = false;
+                    if i != 0 {
+                        if i == 1 {
+                            current_turn += 1;
+                        }
+
+                        let movement_cost = rules
+                            .movement_cost(game, prev_pos, pos)
+                            .min(movement_per_turn);
+                        current_movement_left = current_movement_left.saturating_sub(movement_cost);
+                        must_stop = rules.must_stop(game, prev_pos, pos);
+                    }
+
+                    path_points.push(PathPoint {
+                        pos,
+                        turn: current_turn,
+                        movement_left: current_movement_left,
+                    });
+
+                    if current_movement_left.is_exhausted() || must_stop {
+                        current_turn += 1;
+                        current_movement_left = MovementPoints::from_u32(movement_per_turn);
+                    }
+                    prev_pos = pos;
+                }
+
+                result = Some(Path::new(path_points));
+                break;
+            }
+
+            for neighbor in game.map().adjacent(entry.pos) {
+                if !rules.can_move(game, entry.pos, neighbor, neighbor == end) {
+                    continue;
+                }
+
+                // Being stopped by a zone of control costs the rest of the turn.
+                let movement_cost = if rules.must_stop(game, entry.pos, neighbor) {
+                    MovementPoints::from_u32(movement_per_turn)
+                } else {
+                    rules
+                        .movement_cost(game, entry.pos, neighbor)
+                        .min(movement_per_turn)
+                };
+                let tentative_g_score = self.g_score[&entry.pos] + movement_cost.as_f64();
+                if !self.g_score.contains_key(&neighbor)
+                    || tentative_g_score < self.g_score[&neighbor]
+                {
+                    self.came_from.insert(neighbor, entry.pos);
+                    self.g_score.insert(neighbor, tentative_g_score);
+                    let f_score = tentative_g_score + game.map().distance(neighbor, end);
+                    self.f_score.insert(neighbor, f_score);
+
+                    if !self.in_open_set.contains(&neighbor) {
+                        self.in_open_set.insert(neighbor);
+                        self.open_set.push(OpenEntry {
+                            pos: neighbor,
+                            score: f_score,
+                        });
+                    }
+                }
+            }
+        }
+
+        self.reset();
+        result
+    }
+
+    fn reset(&mut self) {
+        self.open_set.clear();
+        self.in_open_set.clear();
+        self.came_from.clear();
+        self.g_score.clear();
+        self.f_score.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::{
+        river::{River, Rivers},
+        testing,
+    };
+
+    use super::*;
+
+    #[test]
+    fn zones_of_control_only_apply_at_war() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "rome");
+        let enemy = testing::add_player(&mut game, "greece");
+        testing::add_unit(&mut game, enemy, "warrior", uvec2(3, 2));
+
+        let zoc = |game: &Game, from, to| {
+            is_zone_of_control_move(game, &game.player(player), from, to, false)
+        };
+        assert!(!zoc(&game, uvec2(2, 1), uvec2(2, 2)));
+
+        game.player_mut(player).declare_war_on(&game, enemy);
+        assert!(zoc(&game, uvec2(2, 1), uvec2(2, 2)));
+        // Moving away from the enemy is always allowed.
+        assert!(!zoc(&game, uvec2(2, 1), uvec2(1, 0)));
+    }
+
+    #[test]
+    fn crossing_a_river_costs_extra_movement() {
+        let mut river = River::new();
+        for y in 0..4 {
+            river.add_position(uvec2(3, y));
+        }
+        let mut rivers = Rivers::default();
+        rivers.add(river);
+
+        let mut game = testing::new_game_with_rivers(6, 6, rivers);
+        let player = testing::add_player(&mut game, "rome");
+
+        let cost = |from, to| movement_cost(&game, &game.player(player), from, to);
+        assert_eq!(cost(uvec2(1, 1), uvec2(2, 1)), MovementPoints::from_u32(1));
+        assert_eq!(cost(uvec2(2, 1), uvec2(3, 1)), MovementPoints::from_u32(2));
+        assert_eq!(cost(uvec2(2, 1), uvec2(3, 2)), MovementPoints::from_u32(2));
+        // Walking around the end of the river avoids the crossing.
+        assert_eq!(cost(uvec2(2, 3), uvec2(3, 4)), MovementPoints::from_u32(1));
+    }
+}
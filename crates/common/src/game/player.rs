@@ -1,20 +1,42 @@
 use ahash::{AHashMap, AHashSet};
-use glam::{ivec2, uvec2, UVec2};
+use glam::{uvec2, UVec2};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{CityId, PlayerId, UnitId};
+use super::{
+    civics::{CannotSetCivics, ANARCHY_TURNS},
+    religion::{self, CannotAdoptReligion},
+    CityId, PlayerId, UnitId,
+};
 use crate::event::Event;
 use crate::lobby::SlotId;
 use crate::registry::Leader;
+use crate::saveload::legacy;
 use crate::utils::MaybeInfinityU32;
 use crate::world::Game;
 use crate::{
     assets::Handle,
-    registry::{Civilization, Tech},
-    Era, Grid, Visibility,
+    registry::{
+        Civic, CivicCategory, CivicEffectType, Civilization, Handicap, HandicapModifiers, Registry,
+        RegistryItemNotFound, Religion, Resource, Tech, TechEffect, TechEffectType,
+    },
+    Era, Grid, Unit, Visibility,
 };
 
+/// Number of units a player can support for free, regardless of population.
+const BASE_FREE_UNITS: f64 = 3.;
+/// Additional free units granted per citizen across all cities.
+const FREE_UNITS_PER_POPULATION: f64 = 0.5;
+/// Gold cost per turn of each unit beyond the free allowance.
+const UNIT_UPKEEP_COST: f64 = 1.;
+
+/// Number of military units that can be outside
+/// the player's borders without incurring supply costs.
+const FREE_SUPPLY_UNITS: usize = 1;
+/// Extra gold cost per turn of each military unit outside
+/// the player's borders beyond the free allowance.
+const UNIT_SUPPLY_COST: f64 = 0.5;
+
 /// A player in the game.
 ///
 /// All fields are private and encapsulated. Modifying player
@@ -42,7 +64,7 @@ pub struct Player {
     /// Whether the player is still alive.
     is_alive: bool,
 
-    /// Human or AI.
+    /// Human, AI or barbarian.
     kind: PlayerKind,
 
     /// The set of players we're at war with.
@@ -51,6 +73,9 @@ pub struct Player {
     civ: Handle<Civilization>,
     leader_name: String,
 
+    /// The difficulty level of the game.
+    handicap: Handle<Handicap>,
+
     /// Amount of gold in the player's treasury.
     gold: u32,
     /// Cached economy data / revenues
@@ -72,6 +97,17 @@ pub struct Player {
 
     unlocked_techs: AHashSet<Handle<Tech>>,
 
+    /// The religion the player has adopted.
+    state_religion: Option<Handle<Religion>>,
+
+    /// The civic adopted in each category.
+    civics: Vec<Handle<Civic>>,
+    /// Remaining turns of anarchy after switching civics.
+    anarchy_turns: u32,
+
+    /// Espionage points accumulated against each known player.
+    espionage_points: AHashMap<PlayerId, u32>,
+
     visibility: Grid<Visibility>,
 }
 
@@ -83,6 +119,7 @@ impl Player {
         kind: PlayerKind,
         civ: Handle<Civilization>,
         leader_name: String,
+        handicap: Handle<Handicap>,
         map_width: u32,
         map_height: u32,
     ) -> Self {
@@ -91,7 +128,7 @@ impl Player {
             .iter()
             .map(|t| game.registry().tech(t).unwrap())
             .collect();
-        Self {
+        let mut player = Self {
             on_server: true,
             id,
             lobby_id,
@@ -103,6 +140,7 @@ impl Player {
             at_war_with: AHashSet::new(),
             civ,
             leader_name,
+            handicap,
             gold: 0,
             economy: PlayerEconomy::default(),
             economy_settings: EconomySettings::default(),
@@ -111,8 +149,97 @@ impl Player {
             tech_progress: AHashMap::new(),
             research: None,
             unlocked_techs,
+            state_religion: None,
+            civics: game.registry().starting_civics().cloned().collect(),
+            anarchy_turns: 0,
+            espionage_points: AHashMap::new(),
             visibility: Grid::new(Visibility::Hidden, map_width, map_height),
-        }
+        };
+
+        player.economy.research_percent = player.research_percent(game);
+
+        // Apply slider limits from the starting civics.
+        player.apply_slider_limits();
+        player
+    }
+
+    /// Converts a player from an unversioned save file.
+    ///
+    /// `lobby_id` replaces the player's old lobby slot ID.
+    /// The player starts out with the starting civics
+    /// on the default Noble difficulty.
+    pub(crate) fn from_legacy(
+        legacy: legacy::Player,
+        lobby_id: SlotId,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let legacy::Player {
+            on_server,
+            id,
+            lobby_id: _,
+            cities,
+            units,
+            capital,
+            is_alive,
+            kind,
+            at_war_with,
+            civ,
+            leader_name,
+            gold,
+            economy,
+            economy_settings,
+            score,
+            era,
+            tech_progress,
+            research,
+            unlocked_techs,
+            visibility,
+        } = legacy;
+        Ok(Self {
+            on_server,
+            id,
+            lobby_id,
+            cities,
+            units,
+            capital,
+            is_alive,
+            kind,
+            at_war_with,
+            civ,
+            leader_name,
+            gold,
+            economy: PlayerEconomy {
+                base_revenue: economy.base_revenue,
+                gold_revenue: economy.gold_revenue,
+                beaker_revenue: economy.beaker_revenue,
+                culture_revenue: 0,
+                espionage_revenue: 0,
+                expenses: economy.expenses,
+                // Recomputed at the end of the turn
+                city_maintenance: 0,
+                unit_upkeep: 0,
+                unit_supply_cost: 0,
+                beaker_overflow: economy.beaker_overflow,
+                research_percent: 100,
+            },
+            economy_settings: EconomySettings {
+                beaker_percent: economy_settings.beaker_percent,
+                gold_percent: economy_settings.gold_percent,
+                culture_percent: 0,
+                espionage_percent: 0,
+            },
+            score,
+            era,
+            tech_progress,
+            research,
+            unlocked_techs,
+            visibility: Grid::from_legacy(visibility),
+            state_religion: None,
+            civics: registry.starting_civics().cloned().collect(),
+            anarchy_turns: 0,
+            espionage_points: AHashMap::new(),
+            handicap: registry.handicap("noble")?,
+        })
     }
 
     pub fn net_gold_per_turn(&self) -> i32 {
@@ -151,10 +278,28 @@ impl Player {
             .unwrap_or_else(|| &self.civ().leaders[0])
     }
 
+    pub fn handicap(&self) -> &Handle<Handicap> {
+        &self.handicap
+    }
+
+    /// Gets the handicap modifiers that apply to this player,
+    /// which differ between humans and the AI.
+    pub fn handicap_modifiers(&self) -> &HandicapModifiers {
+        match &self.kind {
+            PlayerKind::Human { .. } => &self.handicap.human,
+            PlayerKind::Ai | PlayerKind::Barbarian => &self.handicap.ai,
+        }
+    }
+
+    /// Gets the number of beakers the player needs to research `tech`.
+    pub fn tech_cost(&self, tech: &Tech) -> u32 {
+        tech.cost * self.economy.research_percent / 100
+    }
+
     pub fn username(&self) -> &str {
         match &self.kind {
             PlayerKind::Human { username, .. } => username,
-            PlayerKind::Ai => &self.leader().name,
+            PlayerKind::Ai | PlayerKind::Barbarian => &self.leader().name,
         }
     }
 
@@ -166,6 +311,14 @@ impl Player {
         self.economy.beaker_revenue
     }
 
+    pub fn culture_revenue(&self) -> u32 {
+        self.economy.culture_revenue
+    }
+
+    pub fn espionage_revenue(&self) -> u32 {
+        self.economy.espionage_revenue
+    }
+
     pub fn gold_revenue(&self) -> u32 {
         self.economy.gold_revenue
     }
@@ -174,18 +327,166 @@ impl Player {
         self.economy.expenses
     }
 
+    pub fn city_maintenance(&self) -> u32 {
+        self.economy.city_maintenance
+    }
+
+    pub fn unit_upkeep(&self) -> u32 {
+        self.economy.unit_upkeep
+    }
+
+    pub fn unit_supply_cost(&self) -> u32 {
+        self.economy.unit_supply_cost
+    }
+
     pub fn gold(&self) -> u32 {
         self.gold
     }
 
+    /// Adds gold to the treasury.
+    pub fn add_gold(&mut self, amount: u32) {
+        self.gold += amount;
+    }
+
+    /// Removes gold from the treasury.
+    ///
+    /// Callers should check that the player has enough gold beforehand.
+    pub fn spend_gold(&mut self, amount: u32) {
+        self.gold = self.gold.saturating_sub(amount);
+    }
+
     pub fn has_unlocked_tech(&self, tech: &Handle<Tech>) -> bool {
         self.unlocked_techs.contains(tech)
     }
 
+    /// Unlocks a tech without researching it.
+    pub fn grant_tech(&mut self, tech: Handle<Tech>) {
+        self.unlocked_techs.insert(tech);
+    }
+
+    pub fn unlocked_techs(&self) -> impl Iterator<Item = &Handle<Tech>> + '_ {
+        self.unlocked_techs.iter()
+    }
+
+    /// Returns whether the player has researched the tech
+    /// that reveals `resource`.
+    pub fn has_revealed_resource(&self, registry: &Registry, resource: &Resource) -> bool {
+        match registry.tech(&resource.revealed_by) {
+            Ok(tech) => self.has_unlocked_tech(&tech),
+            Err(_) => true,
+        }
+    }
+
+    /// Gets the effects of all techs the player has researched.
+    pub fn tech_effects(&self) -> impl Iterator<Item = &TechEffect> + '_ {
+        self.unlocked_techs
+            .iter()
+            .flat_map(|tech| tech.effects.iter())
+    }
+
+    fn tech_effect_amount(&self, typ: TechEffectType) -> u32 {
+        self.tech_effects()
+            .filter(|effect| effect.typ == typ)
+            .map(|effect| effect.amount)
+            .sum()
+    }
+
+    /// Gets the percent bonus to worker build speed from techs and civics.
+    pub fn worker_speed_bonus(&self) -> u32 {
+        (self.tech_effect_amount(TechEffectType::WorkerSpeedPercent) as i32
+            + self.civic_effect_amount(CivicEffectType::WorkerSpeedPercent))
+        .max(0) as u32
+    }
+
+    /// Gets the extra movement along routes from techs.
+    pub fn route_movement_bonus(&self) -> u32 {
+        self.tech_effect_amount(TechEffectType::RouteMovement)
+    }
+
+    pub fn civics(&self) -> &[Handle<Civic>] {
+        &self.civics
+    }
+
+    pub fn civic(&self, category: CivicCategory) -> Option<&Handle<Civic>> {
+        self.civics.iter().find(|civic| civic.category == category)
+    }
+
+    pub fn can_adopt_civic(&self, registry: &Registry, civic: &Civic) -> bool {
+        match &civic.required_tech {
+            Some(tech) => registry
+                .tech(tech)
+                .map(|tech| self.has_unlocked_tech(&tech))
+                .unwrap_or(false),
+            None => true,
+        }
+    }
+
+    pub fn is_in_anarchy(&self) -> bool {
+        self.anarchy_turns > 0
+    }
+
+    pub fn anarchy_turns(&self) -> u32 {
+        self.anarchy_turns
+    }
+
+    /// Sums the amounts of all effects of the given type from the player's civics.
+    pub fn civic_effect_amount(&self, typ: CivicEffectType) -> i32 {
+        self.civics
+            .iter()
+            .flat_map(|civic| civic.effects.iter())
+            .filter(|effect| effect.typ == typ)
+            .map(|effect| effect.amount)
+            .sum()
+    }
+
+    /// Gets the factor a percent civic effect applies, e.g. 0.75 for -25%.
+    pub fn civic_multiplier(&self, typ: CivicEffectType) -> f64 {
+        (100 + self.civic_effect_amount(typ)).max(0) as f64 / 100.
+    }
+
+    /// Gets the highest percent the player's civics allow
+    /// for economy sliders other than gold.
+    pub fn max_slider_percent(&self) -> u32 {
+        self.civics
+            .iter()
+            .flat_map(|civic| civic.effects.iter())
+            .filter(|effect| effect.typ == CivicEffectType::MaxSliderPercent)
+            .map(|effect| effect.amount.clamp(0, 100) as u32)
+            .min()
+            .unwrap_or(100)
+    }
+
+    pub fn state_religion(&self) -> Option<&Handle<Religion>> {
+        self.state_religion.as_ref()
+    }
+
+    pub fn economy_settings(&self) -> EconomySettings {
+        self.economy_settings
+    }
+
     pub fn beaker_percent(&self) -> u32 {
         self.economy_settings.beaker_percent()
     }
 
+    pub fn culture_percent(&self) -> u32 {
+        self.economy_settings.culture_percent()
+    }
+
+    pub fn espionage_percent(&self) -> u32 {
+        self.economy_settings.espionage_percent()
+    }
+
+    /// Gets the espionage points we have accumulated against `player`.
+    pub fn espionage_points_against(&self, player: PlayerId) -> u32 {
+        self.espionage_points.get(&player).copied().unwrap_or(0)
+    }
+
+    pub(crate) fn spend_espionage_points(&mut self, against: PlayerId, amount: u32) {
+        if let Some(points) = self.espionage_points.get_mut(&against) {
+            *points = points.saturating_sub(amount);
+        }
+    }
+
     pub fn lobby_id(&self) -> SlotId {
         self.lobby_id
     }
@@ -202,6 +503,10 @@ impl Player {
         &self.kind
     }
 
+    pub fn is_barbarian(&self) -> bool {
+        matches!(self.kind, PlayerKind::Barbarian)
+    }
+
     pub fn score(&self) -> u32 {
         self.score
     }
@@ -229,7 +534,7 @@ impl Player {
     /// Estimate the number of turns it takes to complete the given research.
     pub fn estimate_research_turns(&self, tech: &Tech, progress: u32) -> MaybeInfinityU32 {
         MaybeInfinityU32::new(
-            tech.cost
+            self.tech_cost(tech)
                 .saturating_sub(self.economy.beaker_overflow)
                 .saturating_sub(progress)
                 + self.beaker_revenue()
@@ -358,10 +663,112 @@ impl Player {
         self.research = Some(tech);
     }
 
-    pub fn set_economy_settings(&mut self, mut settings: EconomySettings) {
-        settings.beaker_percent = settings.beaker_percent.min(100);
-        settings.gold_percent = 100 - settings.beaker_percent;
+    /// Sets the economy sliders. They must sum to 100, and
+    /// sliders other than gold must be within the player's civic limits.
+    pub fn set_economy_settings(
+        &mut self,
+        settings: EconomySettings,
+    ) -> Result<(), InvalidEconomySettings> {
+        if settings.total_percent() != 100 {
+            return Err(InvalidEconomySettings::WrongTotal(settings.total_percent()));
+        }
+        let max = self.max_slider_percent();
+        for slider in EconomySlider::all() {
+            if settings.percent(slider) > max {
+                return Err(InvalidEconomySettings::ExceedsLimit(slider, max));
+            }
+        }
+
         self.economy_settings = settings;
+        Ok(())
+    }
+
+    /// Lowers sliders above the civic limit, moving the excess into gold.
+    fn apply_slider_limits(&mut self) {
+        let max = self.max_slider_percent();
+        for slider in EconomySlider::all() {
+            let percent = self.economy_settings.percent(slider);
+            self.economy_settings.set_percent(slider, percent.min(max));
+        }
+    }
+
+    /// Adopts a state religion, or abandons the current one if `religion` is `None`.
+    ///
+    /// The religion must be present in one of the player's cities.
+    pub fn set_state_religion(
+        &mut self,
+        game: &Game,
+        religion: Option<Handle<Religion>>,
+    ) -> Result<(), CannotAdoptReligion> {
+        if let Some(religion) = &religion {
+            if !self
+                .cities
+                .iter()
+                .any(|&city| game.city(city).has_religion(religion))
+            {
+                return Err(CannotAdoptReligion::NotPresent);
+            }
+        }
+
+        self.state_religion = religion;
+        game.push_event(Event::PlayerChanged(self.id));
+
+        let id = self.id;
+        game.defer(move |game| religion::update_cities_of(game, id));
+
+        Ok(())
+    }
+
+    /// Switches to the given civics, replacing the current civic
+    /// in each of their categories, and starts a period of anarchy.
+    pub fn set_civics(
+        &mut self,
+        game: &Game,
+        civics: Vec<Handle<Civic>>,
+    ) -> Result<(), CannotSetCivics> {
+        if self.is_in_anarchy() {
+            return Err(CannotSetCivics::InAnarchy);
+        }
+
+        for (i, civic) in civics.iter().enumerate() {
+            if !self.can_adopt_civic(game.registry(), civic) {
+                return Err(CannotSetCivics::MissingTech(civic.name.clone()));
+            }
+            if civics[..i].iter().any(|c| c.category == civic.category) {
+                return Err(CannotSetCivics::DuplicateCategory(civic.category.name()));
+            }
+        }
+
+        let mut changed = false;
+        for civic in civics {
+            if self.civics.contains(&civic) {
+                continue;
+            }
+            self.civics.retain(|c| c.category != civic.category);
+            self.civics.push(civic);
+            changed = true;
+        }
+        if !changed {
+            return Ok(());
+        }
+
+        self.anarchy_turns = ANARCHY_TURNS;
+        self.apply_slider_limits();
+        game.push_event(Event::PlayerChanged(self.id));
+
+        let id = self.id;
+        game.defer(move |game| {
+            let cities = game.player(id).cities().to_vec();
+            for city_id in cities {
+                let mut city = game.city_mut(city_id);
+                city.update_statuses(game);
+                city.update_economy(game);
+                game.push_event(Event::CityChanged(city_id));
+            }
+            game.player_mut(id).update_economy(game);
+        });
+
+        Ok(())
     }
 
     pub fn declare_war_on(&mut self, game: &Game, on_player_id: PlayerId) {
@@ -372,10 +779,32 @@ impl Player {
             game.push_event(Event::PlayerChanged(self.id));
             game.push_event(Event::PlayerChanged(on_player_id));
             game.push_event(Event::WarDeclared(self.id, on_player_id));
+
+            // Sentries that can already see the new enemy wake up
+            if self.on_server {
+                let id = self.id;
+                game.defer(move |game| {
+                    game.wake_units_watching(id);
+                    game.wake_units_watching(on_player_id);
+                });
+            }
         }
     }
 
+    /// Starts a war that can never end, without announcing it.
+    ///
+    /// Used for the barbarians, who are at war with everyone.
+    pub(crate) fn declare_permanent_war_on(&mut self, game: &Game, on_player_id: PlayerId) {
+        self.at_war_with.insert(on_player_id);
+        game.player_mut(on_player_id).at_war_with.insert(self.id);
+    }
+
     pub fn make_peace_with(&mut self, game: &Game, with_player_id: PlayerId) {
+        // There is no peace with the barbarians.
+        if self.is_barbarian() || game.player(with_player_id).is_barbarian() {
+            return;
+        }
+
         if self.at_war_with.remove(&with_player_id) {
             let mut with_player = game.player_mut(with_player_id);
             with_player.at_war_with.remove(&self.id);
@@ -391,33 +820,76 @@ impl Player {
         self.update_economy(game);
         self.do_economy_turn(game);
         self.update_research(game);
+        self.update_espionage(game);
+
+        if self.is_in_anarchy() {
+            self.anarchy_turns -= 1;
+            if !self.is_in_anarchy() {
+                self.update_economy(game);
+            }
+        }
+
         game.push_event(Event::PlayerChanged(self.id()));
     }
 
+    /// Computes the cost of techs as a percent of their base cost.
+    fn research_percent(&self, game: &Game) -> u32 {
+        game.speed()
+            .scale(self.handicap_modifiers().research_percent)
+    }
+
     pub fn update_economy(&mut self, game: &Game) {
+        self.economy.research_percent = self.research_percent(game);
+
         let mut base = 0.;
         let mut gold = 0.;
         let mut beakers = 0.;
-        let mut expenses = 0.;
+        let mut culture = 0.;
+        let mut espionage = 0.;
+        let mut maintenance = 0.;
+        let mut population = 0;
 
         for &city_id in &self.cities {
             let mut city = game.city_mut(city_id);
             base += city.economy().commerce_yield;
 
-            city.economy.gold =
-                self.economy_settings.gold_percent() as f64 / 100. * city.economy.commerce_yield;
-            city.economy.beakers =
-                self.economy_settings.beaker_percent() as f64 / 100. * city.economy.commerce_yield;
+            city.economy.gold = self.economy_settings.gold_percent() as f64 / 100.
+                * city.economy.commerce_yield
+                + city.economy.specialist_gold as f64;
+            city.economy.beakers = self.economy_settings.beaker_percent() as f64 / 100.
+                * city.economy.commerce_yield
+                + city.economy.specialist_beakers as f64;
+            city.economy.culture =
+                self.economy_settings.culture_percent() as f64 / 100. * city.economy.commerce_yield;
+            city.economy.espionage = self.economy_settings.espionage_percent() as f64 / 100.
+                * city.economy.commerce_yield;
+
+            if self.is_in_anarchy() {
+                // No commerce is produced during anarchy.
+                city.economy.gold = 0.;
+                city.economy.beakers = 0.;
+                city.economy.culture = 0.;
+                city.economy.espionage = 0.;
+            }
+            city.update_culture_per_turn();
 
             gold += city.economy().gold;
             beakers += city.economy().beakers;
-            expenses += city.economy().maintenance_cost;
+            culture += city.economy().culture;
+            espionage += city.economy().espionage;
+            maintenance += city.economy().maintenance_cost;
+            population += city.population().get();
         }
 
         self.economy.base_revenue = base.floor() as u32;
         self.economy.gold_revenue = gold.floor() as u32;
         self.economy.beaker_revenue = beakers.floor() as u32;
-        self.economy.expenses = expenses.floor() as u32;
+        self.economy.culture_revenue = culture.floor() as u32;
+        self.economy.espionage_revenue = espionage.floor() as u32;
+        self.economy.city_maintenance = maintenance.floor() as u32;
+        self.economy.unit_upkeep = self.compute_unit_upkeep(population).floor() as u32;
+        self.economy.unit_supply_cost = self.compute_unit_supply_cost(game).floor() as u32;
+        self.update_expenses();
 
         log::info!(
             "Updated economy for {} - {} beakers from {} cities",
@@ -427,11 +899,62 @@ impl Player {
         );
     }
 
+    /// Recomputes the military supply cost after
+    /// units have crossed our borders.
+    pub fn update_unit_supply_cost(&mut self, game: &Game) {
+        self.economy.unit_supply_cost = self.compute_unit_supply_cost(game).floor() as u32;
+        self.update_expenses();
+    }
+
+    fn update_expenses(&mut self) {
+        self.economy.expenses = self.economy.city_maintenance
+            + self.economy.unit_upkeep
+            + self.economy.unit_supply_cost;
+    }
+
+    /// Computes the per-turn upkeep of each unit beyond
+    /// a free allowance, which grows with total population.
+    fn compute_unit_upkeep(&self, population: u32) -> f64 {
+        let free_units = BASE_FREE_UNITS + FREE_UNITS_PER_POPULATION * population as f64;
+        (self.units.len() as f64 - free_units.floor()).max(0.)
+            * UNIT_UPKEEP_COST
+            * self.civic_multiplier(CivicEffectType::UnitUpkeepPercent)
+    }
+
+    /// Computes the per-turn supply cost of military units outside our borders.
+    fn compute_unit_supply_cost(&self, game: &Game) -> f64 {
+        let units_outside_borders = self
+            .units
+            .iter()
+            .map(|&id| game.unit(id))
+            .filter(|unit| unit.kind().strength > 0.)
+            .filter(|unit| {
+                game.tile(unit.pos())
+                    .map(|tile| tile.owner(game) != Some(self.id))
+                    .unwrap_or(false)
+            })
+            .count();
+        units_outside_borders.saturating_sub(FREE_SUPPLY_UNITS) as f64 * UNIT_SUPPLY_COST
+    }
+
     fn do_economy_turn(&mut self, game: &Game) {
-        while self.gold as i32 + self.net_gold_per_turn() < 0
-            && self.economy_settings.beaker_percent() > 0
-        {
-            self.economy_settings.decrement_beaker_percent();
+        // When broke, shift commerce into gold, giving up
+        // espionage and culture before research.
+        while self.gold as i32 + self.net_gold_per_turn() < 0 {
+            let slider = [
+                EconomySlider::Espionage,
+                EconomySlider::Culture,
+                EconomySlider::Beakers,
+            ]
+            .into_iter()
+            .find(|&slider| self.economy_settings.percent(slider) > 0);
+            let slider = match slider {
+                Some(slider) => slider,
+                None => break,
+            };
+            let percent = self.economy_settings.percent(slider);
+            self.economy_settings
+                .set_percent(slider, percent.saturating_sub(10));
             self.update_economy(game);
         }
 
@@ -440,6 +963,7 @@ impl Player {
 
     fn update_research(&mut self, game: &Game) {
         if let Some(tech) = &self.research {
+            let cost = self.tech_cost(tech);
             let progress = self.tech_progress.entry(tech.clone()).or_insert(0);
             *progress += self.economy.beaker_revenue;
             if self.economy.beaker_overflow > 0 {
@@ -448,15 +972,83 @@ impl Player {
             *progress += self.economy.beaker_overflow;
             self.economy.beaker_overflow = 0;
 
-            if *progress >= tech.cost {
-                self.economy.beaker_overflow = *progress - tech.cost;
-                game.push_event(Event::TechUnlocked(self.id, tech.clone()));
-                self.unlocked_techs.insert(tech.clone());
-                self.research = None;
+            if *progress >= cost {
+                self.economy.beaker_overflow = *progress - cost;
+                let tech = tech.clone();
+                self.unlock_tech(game, tech);
+            }
+        }
+    }
+
+    /// Gives the player a tech, whether researched, stolen or found in a village.
+    pub(crate) fn unlock_tech(&mut self, game: &Game, tech: Handle<Tech>) {
+        game.push_event(Event::TechUnlocked(self.id, tech.clone()));
+        self.unlocked_techs.insert(tech.clone());
+        if self.research.as_ref() == Some(&tech) {
+            self.research = None;
+        }
+
+        let id = self.id;
+        game.defer(move |game| religion::found_religions_for_tech(game, id, &tech));
+    }
+
+    /// Marks tiles the player has never seen as explored.
+    pub(crate) fn reveal_tiles(&mut self, tiles: impl IntoIterator<Item = UVec2>) {
+        for pos in tiles {
+            if self.visibility_at(pos) == Visibility::Hidden {
+                self.visibility.set(pos, Visibility::Fogged).unwrap();
             }
         }
     }
 
+    /// Returns whether the player can currently see `unit`.
+    ///
+    /// Units are visible on tiles in our line of sight,
+    /// except invisible units like other players' spies.
+    pub fn can_see_unit(&self, unit: &Unit) -> bool {
+        !unit.is_hidden_from(self.id) && self.visibility_at(unit.pos()) == Visibility::Visible
+    }
+
+    /// Gets the other players we have encountered: those with a city
+    /// or unit on a tile we have explored. Barbarians don't count.
+    pub fn known_players(&self, game: &Game) -> Vec<PlayerId> {
+        let mut players: Vec<PlayerId> = game
+            .cities()
+            .map(|city| (city.owner(), city.pos()))
+            .chain(
+                game.units()
+                    .filter(|unit| !unit.is_hidden_from(self.id))
+                    .map(|unit| (unit.owner(), unit.pos())),
+            )
+            .filter(|&(owner, pos)| {
+                owner != self.id
+                    && self.visibility_at(pos) != Visibility::Hidden
+                    && !game.player(owner).is_barbarian()
+            })
+            .map(|(owner, _)| owner)
+            .collect();
+        players.sort_unstable();
+        players.dedup();
+        players
+    }
+
+    /// Splits this turn's espionage revenue evenly
+    /// between the players we know.
+    fn update_espionage(&mut self, game: &Game) {
+        let known_players = self.known_players(game);
+        if known_players.is_empty() {
+            return;
+        }
+
+        let revenue = self.economy.espionage_revenue;
+        let share = revenue / known_players.len() as u32;
+        let remainder = revenue as usize % known_players.len();
+        for (i, player) in known_players.into_iter().enumerate() {
+            let points = share + u32::from(i < remainder);
+            *self.espionage_points.entry(player).or_insert(0) += points;
+        }
+    }
+
     /// Recomputes the player's visibility grid.
     pub fn update_visibility(&mut self, game: &Game) {
         // Reset Visible => Fogged
@@ -488,25 +1080,17 @@ impl Player {
 
         // Distribute Visible from visibility points
         for point in visibility_points {
-            let tile = game.tile(point).unwrap();
-            let distance = if tile.is_hilled() && !tile.is_forested() {
-                2
-            } else {
-                1
-            };
-
-            for dx in -distance..=distance {
-                for dy in -distance..=distance {
-                    let pos = point.as_i32() + ivec2(dx, dy);
-                    if self.visibility.is_in_bounds(pos) {
-                        self.visibility
-                            .set(pos.as_u32(), Visibility::Visible)
-                            .unwrap();
-                    }
-                }
+            for pos in game.tiles_visible_from(point) {
+                self.visibility.set(pos, Visibility::Visible).unwrap();
             }
         }
 
+        // Sentries that now see an enemy wake up
+        if self.on_server {
+            let id = self.id;
+            game.defer(move |game| game.wake_units_watching(id));
+        }
+
         game.push_event(Event::PlayerChanged(self.id));
     }
 }
@@ -520,11 +1104,25 @@ pub struct PlayerEconomy {
     pub gold_revenue: u32,
     /// Beaker revenue per turn.
     pub beaker_revenue: u32,
+    /// Culture per turn from commerce across all cities.
+    pub culture_revenue: u32,
+    /// Espionage points per turn, split between known players.
+    pub espionage_revenue: u32,
     /// Total expenses from inflation, city maintenance, etc.
     pub expenses: u32,
+    /// Expenses from city maintenance.
+    pub city_maintenance: u32,
+    /// Expenses from units beyond the free unit allowance.
+    pub unit_upkeep: u32,
+    /// Expenses from military units outside our borders.
+    pub unit_supply_cost: u32,
 
     /// Beakers overflowing from previous research.
     pub beaker_overflow: u32,
+
+    /// Cost of techs as a percent of their base cost,
+    /// from the difficulty level and the game speed.
+    pub research_percent: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -534,17 +1132,22 @@ pub enum PlayerKind {
         username: String,
     },
     Ai,
+    /// The barbarians, who are at war with everyone.
+    Barbarian,
 }
 
 /// A player's economy settings, determining
 /// how revenue is split into beakers, gold, culture,
 /// and espionage.
 ///
-/// All terms must sum to 100.
+/// All terms must sum to 100. Gold receives whatever
+/// the other sliders leave over.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct EconomySettings {
     beaker_percent: u32,
     gold_percent: u32,
+    culture_percent: u32,
+    espionage_percent: u32,
 }
 
 impl Default for EconomySettings {
@@ -552,28 +1155,72 @@ impl Default for EconomySettings {
         Self {
             beaker_percent: 100,
             gold_percent: 0,
+            culture_percent: 0,
+            espionage_percent: 0,
         }
     }
 }
 
+/// An economy slider that can be set directly.
+///
+/// Gold isn't included, since it takes the remainder.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EconomySlider {
+    Beakers,
+    Culture,
+    Espionage,
+}
+
+impl EconomySlider {
+    pub fn all() -> impl Iterator<Item = EconomySlider> {
+        [
+            EconomySlider::Beakers,
+            EconomySlider::Culture,
+            EconomySlider::Espionage,
+        ]
+        .into_iter()
+    }
+}
+
 impl EconomySettings {
     pub fn increment_beaker_percent(&mut self) {
-        self.beaker_percent += 10;
-        if self.beaker_percent > 100 {
-            self.beaker_percent = 100;
-        }
-
-        self.gold_percent = 100 - self.beaker_percent;
+        self.set_percent(EconomySlider::Beakers, self.beaker_percent + 10);
     }
 
     pub fn decrement_beaker_percent(&mut self) {
-        self.beaker_percent = self.beaker_percent.saturating_sub(10);
-        self.gold_percent = 100 - self.beaker_percent;
+        self.set_percent(
+            EconomySlider::Beakers,
+            self.beaker_percent.saturating_sub(10),
+        );
     }
 
     pub fn set_beaker_percent(&mut self, percent: u32) {
-        self.beaker_percent = percent.min(100);
-        self.gold_percent = 100 - self.beaker_percent;
+        self.set_percent(EconomySlider::Beakers, percent);
+    }
+
+    /// Sets the percent of a slider. It is clamped so that
+    /// it fits alongside the other non-gold sliders, and
+    /// gold takes the remainder.
+    pub fn set_percent(&mut self, slider: EconomySlider, percent: u32) {
+        let others: u32 = EconomySlider::all()
+            .filter(|&s| s != slider)
+            .map(|s| self.percent(s))
+            .sum();
+        let percent = percent.min(100u32.saturating_sub(others));
+        match slider {
+            EconomySlider::Beakers => self.beaker_percent = percent,
+            EconomySlider::Culture => self.culture_percent = percent,
+            EconomySlider::Espionage => self.espionage_percent = percent,
+        }
+        self.gold_percent = 100u32.saturating_sub(others + percent);
+    }
+
+    pub fn percent(&self, slider: EconomySlider) -> u32 {
+        match slider {
+            EconomySlider::Beakers => self.beaker_percent,
+            EconomySlider::Culture => self.culture_percent,
+            EconomySlider::Espionage => self.espionage_percent,
+        }
     }
 
     pub fn beaker_percent(&self) -> u32 {
@@ -583,4 +1230,248 @@ impl EconomySettings {
     pub fn gold_percent(&self) -> u32 {
         self.gold_percent
     }
+
+    pub fn culture_percent(&self) -> u32 {
+        self.culture_percent
+    }
+
+    pub fn espionage_percent(&self) -> u32 {
+        self.espionage_percent
+    }
+
+    /// Sum of all slider percents, which is 100 for valid settings.
+    pub fn total_percent(&self) -> u32 {
+        [
+            self.beaker_percent,
+            self.gold_percent,
+            self.culture_percent,
+            self.espionage_percent,
+        ]
+        .into_iter()
+        .fold(0, u32::saturating_add)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidEconomySettings {
+    #[error("economy sliders sum to {0}%, not 100%")]
+    WrongTotal(u32),
+    #[error("the {0:?} slider exceeds the {1}% allowed by the player's civics")]
+    ExceedsLimit(EconomySlider, u32),
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::{city::BuildTask, testing, GameSpeed};
+
+    use super::*;
+
+    #[test]
+    fn units_beyond_allowance_cost_upkeep_and_supply() {
+        let mut game = testing::new_game(12, 12);
+        let player = testing::add_player(&mut game, "rome");
+        testing::add_city(&mut game, player, uvec2(2, 2));
+
+        // Three military units inside our borders...
+        for pos in [uvec2(2, 2), uvec2(1, 2), uvec2(3, 3)] {
+            testing::add_unit(&mut game, player, "warrior", pos);
+        }
+        // ...three outside...
+        for pos in [uvec2(9, 9), uvec2(10, 9), uvec2(9, 10)] {
+            testing::add_unit(&mut game, player, "warrior", pos);
+        }
+        // ...and a non-military unit outside, which costs no supply.
+        testing::add_unit(&mut game, player, "worker", uvec2(10, 10));
+
+        game.player_mut(player).update_economy(&game);
+        let player = game.player(player);
+
+        // A size 1 city supports 3.5 units for free, rounded down.
+        assert_eq!(player.unit_upkeep(), 4);
+        // One unit outside our borders is free.
+        assert_eq!(player.unit_supply_cost(), 1);
+        assert_eq!(
+            player.expenses(),
+            player.city_maintenance() + player.unit_upkeep() + player.unit_supply_cost()
+        );
+    }
+
+    #[test]
+    fn expenses_add_up_the_rounded_costs() {
+        let mut game = testing::new_game(12, 12);
+        let player = testing::add_player(&mut game, "rome");
+        testing::add_city(&mut game, player, uvec2(2, 2));
+        for pos in [uvec2(9, 9), uvec2(10, 9)] {
+            testing::add_unit(&mut game, player, "warrior", pos);
+        }
+
+        game.player_mut(player).update_economy(&game);
+        let player = game.player(player);
+
+        // Maintenance and supply are both a fraction of a gold,
+        // which must not add up to a whole gold of expenses.
+        assert_eq!(player.city_maintenance(), 0);
+        assert_eq!(player.unit_supply_cost(), 0);
+        assert_eq!(player.expenses(), 0);
+    }
+
+    #[test]
+    fn switching_civics_starts_anarchy_and_limits_sliders() {
+        let mut game = testing::new_game(12, 12);
+        let player = testing::add_player(&mut game, "rome");
+        let registry = game.registry();
+        let despotism = registry.civic("despotism").unwrap();
+        let hereditary_rule = registry.civic("hereditary_rule").unwrap();
+        let representation = registry.civic("representation").unwrap();
+
+        assert!(game.player(player).civics().contains(&despotism));
+        let mut settings = game.player(player).economy_settings();
+        settings.set_beaker_percent(100);
+        game.player_mut(player)
+            .set_economy_settings(settings)
+            .unwrap();
+
+        assert!(matches!(
+            game.player_mut(player)
+                .set_civics(&game, vec![hereditary_rule.clone()]),
+            Err(CannotSetCivics::MissingTech(_))
+        ));
+        game.player_mut(player)
+            .grant_tech(registry.tech("Monarchy").unwrap());
+        game.player_mut(player)
+            .grant_tech(registry.tech("Constitution").unwrap());
+        assert!(matches!(
+            game.player_mut(player)
+                .set_civics(&game, vec![hereditary_rule.clone(), representation]),
+            Err(CannotSetCivics::DuplicateCategory(_))
+        ));
+
+        game.player_mut(player)
+            .set_civics(&game, vec![hereditary_rule.clone()])
+            .unwrap();
+        game.run_deferred_functions();
+        {
+            let player = game.player(player);
+            assert!(player.civics().contains(&hereditary_rule));
+            assert!(!player.civics().contains(&despotism));
+            assert_eq!(player.anarchy_turns(), ANARCHY_TURNS);
+            assert_eq!(player.max_slider_percent(), 80);
+            assert_eq!(player.economy_settings().beaker_percent(), 80);
+            assert_eq!(player.economy_settings().gold_percent(), 20);
+        }
+
+        settings.set_beaker_percent(90);
+        assert!(matches!(
+            game.player_mut(player).set_economy_settings(settings),
+            Err(InvalidEconomySettings::ExceedsLimit(
+                EconomySlider::Beakers,
+                80
+            ))
+        ));
+        assert!(matches!(
+            game.player_mut(player)
+                .set_civics(&game, vec![despotism.clone()]),
+            Err(CannotSetCivics::InAnarchy)
+        ));
+
+        for _ in 0..ANARCHY_TURNS {
+            game.player_mut(player).on_turn_end(&game);
+        }
+        assert!(!game.player(player).is_in_anarchy());
+        game.player_mut(player)
+            .set_civics(&game, vec![despotism])
+            .unwrap();
+    }
+
+    #[test]
+    fn sliders_are_clamped_and_gold_takes_the_rest() {
+        let mut settings = EconomySettings::default();
+        settings.set_beaker_percent(70);
+        settings.set_percent(EconomySlider::Culture, 50);
+        assert_eq!(settings.culture_percent(), 30);
+        assert_eq!(settings.gold_percent(), 0);
+
+        settings.set_percent(EconomySlider::Culture, 10);
+        settings.set_percent(EconomySlider::Espionage, 10);
+        settings.decrement_beaker_percent();
+        assert_eq!(settings.beaker_percent(), 60);
+        assert_eq!(settings.gold_percent(), 20);
+        assert_eq!(settings.total_percent(), 100);
+    }
+
+    #[test]
+    fn sliders_split_city_commerce() {
+        let mut game = testing::new_game(12, 12);
+        let player = testing::add_player(&mut game, "rome");
+        let city = testing::add_city(&mut game, player, uvec2(2, 2));
+
+        let mut settings = EconomySettings::default();
+        settings.set_beaker_percent(50);
+        settings.set_percent(EconomySlider::Culture, 20);
+        settings.set_percent(EconomySlider::Espionage, 10);
+        game.player_mut(player)
+            .set_economy_settings(settings)
+            .unwrap();
+        game.city_mut(city).update_economy(&game);
+        game.player_mut(player).update_economy(&game);
+
+        let city = game.city(city);
+        let economy = city.economy();
+        let commerce = economy.commerce_yield;
+        assert!((economy.beakers - commerce * 0.5).abs() < 1e-9);
+        assert!((economy.gold - commerce * 0.2).abs() < 1e-9);
+        assert!((economy.culture - commerce * 0.2).abs() < 1e-9);
+        assert!((economy.espionage - commerce * 0.1).abs() < 1e-9);
+        assert_eq!(city.gold_per_turn(), economy.gold.floor() as u32);
+        assert_eq!(city.beakers_per_turn(), economy.beakers.floor() as u32);
+
+        let player = game.player(player);
+        assert_eq!(player.gold_revenue(), city.gold_per_turn());
+        assert_eq!(player.beaker_revenue(), city.beakers_per_turn());
+    }
+
+    #[test]
+    fn handicaps_scale_costs_separately_for_humans_and_ai() {
+        let mut game = testing::new_game(12, 12);
+        game.set_speed(GameSpeed::Epic);
+        let human = testing::add_player_with_kind(
+            &mut game,
+            "rome",
+            PlayerKind::Human {
+                account_uuid: Uuid::nil(),
+                username: "tester".to_owned(),
+            },
+            "deity",
+        );
+        let ai = testing::add_player_with_kind(&mut game, "greece", PlayerKind::Ai, "deity");
+
+        let registry = testing::registry();
+        let deity = registry.handicap("deity").unwrap();
+        assert_eq!(
+            game.player(human).handicap_modifiers().research_percent,
+            deity.human.research_percent
+        );
+        assert_eq!(
+            game.player(ai).handicap_modifiers().extra_starting_units,
+            deity.ai.extra_starting_units
+        );
+
+        // Deity makes research 130% as expensive for humans and 60% for the AI,
+        // and Epic speed scales both by 150%.
+        let alphabet = registry.tech("Alphabet").unwrap();
+        assert_eq!(game.player(human).tech_cost(&alphabet), 330 * 195 / 100);
+        assert_eq!(game.player(ai).tech_cost(&alphabet), 330 * 90 / 100);
+
+        // Production is 110% for humans and 65% for the AI.
+        let warrior = BuildTask::Unit(registry.unit_kind("warrior").unwrap());
+        let human_city = testing::add_city(&mut game, human, uvec2(2, 2));
+        let ai_city = testing::add_city(&mut game, ai, uvec2(8, 8));
+        assert_eq!(
+            game.city(human_city).build_task_cost(&warrior),
+            15 * 165 / 100
+        );
+        assert_eq!(game.city(ai_city).build_task_cost(&warrior), 15 * 97 / 100);
+    }
 }
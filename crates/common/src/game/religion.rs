@@ -0,0 +1,182 @@
+//! Religions: founding, spread between cities, and state religions.
+//!
+//! A religion is founded in a city of the first player to research
+//! its founding tech. It then spreads along trade connections
+//! and through missionaries.
+
+use rand::Rng;
+
+use crate::{
+    assets::Handle,
+    event::Event,
+    registry::{Religion, Tech},
+    CityId, Game, PlayerId,
+};
+
+/// Chance per turn that a religion spreads to a city
+/// connected to a city with the religion.
+const SPREAD_CHANCE: f64 = 0.05;
+/// Chance per turn that a religion spreads to a city
+/// connected to its holy city.
+const HOLY_CITY_SPREAD_CHANCE: f64 = 0.1;
+
+/// Returns whether any player has founded the religion.
+pub fn is_founded(game: &Game, religion: &Handle<Religion>) -> bool {
+    game.cities().any(|city| city.is_holy_city_of(religion))
+}
+
+/// Founds the religions whose founding tech `player` just researched,
+/// if no one has founded them yet.
+///
+/// The religion is founded in the player's most populous city
+/// without a religion, or in their most populous city if
+/// all of them have one already.
+pub fn found_religions_for_tech(game: &Game, player: PlayerId, tech: &Tech) {
+    for religion in game.registry().religions() {
+        if religion.founded_by != tech.name || is_founded(game, religion) {
+            continue;
+        }
+
+        let holy_city = game
+            .player(player)
+            .cities()
+            .iter()
+            .copied()
+            .max_by_key(|&id| {
+                let city = game.city(id);
+                let has_religion = city.religions().next().is_some();
+                (!has_religion, city.population())
+            });
+        let holy_city = match holy_city {
+            Some(city) => city,
+            None => continue,
+        };
+
+        {
+            let mut city = game.city_mut(holy_city);
+            city.make_holy_city_of(religion.clone());
+            city.update_statuses(game);
+            log::info!("{} was founded in {}", religion.name, city.name());
+        }
+        game.push_event(Event::CityChanged(holy_city));
+
+        // Founders without a state religion adopt the new one.
+        let adopts_religion = game.player(player).state_religion().is_none();
+        if adopts_religion {
+            let _ = game
+                .player_mut(player)
+                .set_state_religion(game, Some(religion.clone()));
+        }
+    }
+}
+
+/// Spreads religions along trade connections.
+///
+/// Should be called at the end of each turn, after cities have
+/// updated their trade networks.
+pub fn spread_religions(game: &Game) {
+    let mut spreads: Vec<(CityId, Handle<Religion>)> = Vec::new();
+
+    for city in game.cities() {
+        for religion in game.registry().religions() {
+            if city.has_religion(religion) {
+                continue;
+            }
+
+            let mut chance: f64 = 0.;
+            for source in game.cities() {
+                if source.id() == city.id()
+                    || !source.has_religion(religion)
+                    || !city.is_connected_to_city(source.id())
+                {
+                    continue;
+                }
+                let source_chance = if source.is_holy_city_of(religion) {
+                    HOLY_CITY_SPREAD_CHANCE
+                } else {
+                    SPREAD_CHANCE
+                };
+                chance = chance.max(source_chance);
+            }
+
+            if chance > 0. && game.rng().gen_bool(chance) {
+                spreads.push((city.id(), religion.clone()));
+            }
+        }
+    }
+
+    for (city_id, religion) in spreads {
+        let mut city = game.city_mut(city_id);
+        log::info!("{} spread to {}", religion.name, city.name());
+        city.add_religion(religion);
+        city.update_statuses(game);
+        game.push_event(Event::CityChanged(city_id));
+    }
+}
+
+/// Recomputes happiness in each of the player's cities
+/// after their religions or state religion changed.
+pub(crate) fn update_cities_of(game: &Game, player: PlayerId) {
+    for &city_id in game.player(player).cities() {
+        game.city_mut(city_id).update_statuses(game);
+        game.push_event(Event::CityChanged(city_id));
+    }
+}
+
+/// Gets the religions the player can adopt as a state religion:
+/// those present in at least one of their cities.
+pub fn adoptable_religions(game: &Game, player: PlayerId) -> Vec<Handle<Religion>> {
+    let mut religions: Vec<Handle<Religion>> = Vec::new();
+    for &city_id in game.player(player).cities() {
+        for religion in game.city(city_id).religions() {
+            if !religions.contains(religion) {
+                religions.push(religion.clone());
+            }
+        }
+    }
+    religions.sort_by(|a, b| a.name.cmp(&b.name));
+    religions
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotAdoptReligion {
+    #[error("the religion is not present in any of the player's cities")]
+    NotPresent,
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn religions_are_founded_once_and_spread_by_missionaries() {
+        let mut game = testing::new_game(10, 5);
+        let founder = testing::add_player(&mut game, "rome");
+        let other = testing::add_player(&mut game, "greece");
+        let holy_city = testing::add_city(&mut game, founder, uvec2(2, 2));
+        let other_city = testing::add_city(&mut game, other, uvec2(7, 2));
+
+        let registry = testing::registry();
+        let buddhism = registry.religion("buddhism").unwrap();
+        let meditation = registry.tech(&buddhism.founded_by).unwrap();
+
+        found_religions_for_tech(&game, founder, &meditation);
+        found_religions_for_tech(&game, other, &meditation);
+        game.run_deferred_functions();
+        assert!(game.city(holy_city).is_holy_city_of(&buddhism));
+        assert!(!game.city(other_city).has_religion(&buddhism));
+        assert_eq!(game.player(founder).state_religion(), Some(&buddhism));
+        assert_eq!(adoptable_religions(&game, other), Vec::new());
+
+        let missionary = testing::add_unit(&mut game, founder, "missionary", uvec2(7, 2));
+        game.unit_mut(missionary).spread_religion(&game).unwrap();
+        game.run_deferred_functions();
+        assert!(game.city(other_city).has_religion(&buddhism));
+        assert!(!game.is_unit_valid(missionary));
+        assert_eq!(adoptable_religions(&game, other), vec![buddhism]);
+    }
+}
@@ -1,5 +1,5 @@
 use ahash::AHashMap;
-use glam::{uvec2, UVec2};
+use glam::{ivec2, uvec2, UVec2};
 use serde::{Deserialize, Serialize};
 use slotmap::SlotMap;
 
@@ -58,6 +58,44 @@ impl Rivers {
         }
     }
 
+    /// Returns whether moving between the adjacent tiles
+    /// `from` and `to` crosses a river.
+    ///
+    /// A diagonal move crosses a river if rivers block
+    /// both orthogonal routes around the shared corner.
+    pub fn is_crossing<T>(&self, grid: &Grid<T>, from: UVec2, to: UVec2) -> bool {
+        let offset = grid.offset(from, to);
+        if offset.x == 0 || offset.y == 0 {
+            self.separates(grid, from, to)
+        } else {
+            let (Some(a), Some(b)) = (
+                grid.wrap(from.as_i32() + ivec2(offset.x, 0)),
+                grid.wrap(from.as_i32() + ivec2(0, offset.y)),
+            ) else {
+                return false;
+            };
+            (self.separates(grid, from, a) || self.separates(grid, a, to))
+                && (self.separates(grid, from, b) || self.separates(grid, b, to))
+        }
+    }
+
+    /// Returns whether a river runs between two orthogonally adjacent tiles.
+    ///
+    /// Uses the wrapped offset between the tiles so that
+    /// moves across the seam of a wrapping map are handled.
+    fn separates<T>(&self, grid: &Grid<T>, a: UVec2, b: UVec2) -> bool {
+        let offset = grid.offset(a, b);
+        if offset.y == 0 {
+            // Along the left side of the right tile
+            let right = if offset.x > 0 { b } else { a };
+            self.river_id_at(right, Axis::Vertical).is_some()
+        } else {
+            // Along the top side of the lower tile
+            let lower = if offset.y > 0 { b } else { a };
+            self.river_id_at(lower, Axis::Horizontal).is_some()
+        }
+    }
+
     /// Distributes fresh water from rivers into tiles.
     pub fn distribute_fresh_water(&self, tiles: &mut Grid<Tile>) {
         for river in self.rivers.values() {
@@ -154,3 +192,25 @@ impl River {
         segments.into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rivers_along_the_seam_are_crossed_when_wrapping() {
+        let mut river = River::new();
+        for y in 0..4 {
+            river.add_position(uvec2(0, y));
+        }
+        let mut rivers = Rivers::default();
+        rivers.add(river);
+
+        let mut grid = Grid::new((), 10, 5);
+        grid.set_wrap_x(true);
+        assert!(rivers.is_crossing(&grid, uvec2(9, 1), uvec2(0, 1)));
+        assert!(rivers.is_crossing(&grid, uvec2(0, 1), uvec2(9, 1)));
+        assert!(rivers.is_crossing(&grid, uvec2(9, 1), uvec2(0, 2)));
+        assert!(!rivers.is_crossing(&grid, uvec2(0, 1), uvec2(1, 1)));
+    }
+}
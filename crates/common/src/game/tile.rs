@@ -1,16 +1,16 @@
 use std::collections::VecDeque;
-use std::mem;
 
 use ahash::AHashSet;
 use arrayvec::ArrayVec;
-use glam::{ivec2, uvec2, DVec2, IVec2, UVec2};
+use glam::{ivec2, DVec2, IVec2, UVec2};
+use once_cell::unsync::OnceCell;
 use serde::{Deserialize, Serialize};
 
 use crate::assets::Handle;
 use crate::player::Player;
-use crate::registry::Resource;
+use crate::registry::{Registry, RegistryItemNotFound, Resource, TechEffectType, TerrainFeature};
+use crate::saveload::legacy;
 use crate::unit::MovementPoints;
-use crate::utils::UVecExt;
 use crate::world::Game;
 use crate::Yield;
 
@@ -18,6 +18,17 @@ use super::culture::Culture;
 use super::improvement::Improvement;
 use super::{CityId, PlayerId};
 
+/// IDs of the built-in terrain features, which are derived
+/// from tile state rather than stored on the tile.
+pub const FOREST_FEATURE: &str = "forest";
+pub const HILLS_FEATURE: &str = "hills";
+pub const FLOOD_PLAINS_FEATURE: &str = "floodPlains";
+pub const FRESH_WATER_FEATURE: &str = "freshWater";
+
+/// Number of route tiles a unit can move along per movement
+/// point, before bonuses from techs.
+pub const ROUTE_TILES_PER_MOVEMENT: u32 = 3;
+
 /// A map tile.
 ///
 /// All fields are private and encapsulated. Modifying tile
@@ -40,8 +51,20 @@ pub struct Tile {
 
     resource: Option<Handle<Resource>>,
 
+    /// Data-driven features placed on the tile, besides the
+    /// built-in ones.
+    features: Vec<Handle<TerrainFeature>>,
+    /// Cached result of [`Tile::active_features`], cleared
+    /// whenever the tile's features change.
+    #[serde(skip)]
+    active_features: OnceCell<Vec<Handle<TerrainFeature>>>,
+
     improvements: Vec<Improvement>,
 
+    /// Whether a tribal village stands on the tile, waiting
+    /// for the first unit to enter it.
+    has_tribal_village: bool,
+
     owner: Option<PlayerId>,
 }
 
@@ -56,11 +79,51 @@ impl Tile {
             influencers: Vec::new(),
             worked_by_city: None,
             resource: None,
+            features: Vec::new(),
+            active_features: OnceCell::new(),
             improvements: Vec::new(),
+            has_tribal_village: false,
             owner: None,
         }
     }
 
+    /// Converts a tile from an unversioned save file.
+    pub(crate) fn from_legacy(
+        legacy: legacy::Tile,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let legacy::Tile {
+            terrain,
+            is_forested,
+            is_hilled,
+            has_fresh_water,
+            culture,
+            influencers,
+            worked_by_city,
+            resource,
+            improvements,
+            owner,
+        } = legacy;
+        Ok(Self {
+            terrain,
+            is_forested,
+            is_hilled,
+            has_fresh_water,
+            culture,
+            influencers,
+            worked_by_city,
+            resource,
+            features: Vec::new(),
+            active_features: OnceCell::new(),
+            improvements: improvements
+                .into_iter()
+                .map(|i| Improvement::from_legacy(i, registry))
+                .collect::<Result<_, _>>()?,
+            owner,
+            has_tribal_village: false,
+        })
+    }
+
     pub fn update_owner(&mut self, game: &Game) {
         let old_owner = self.owner;
         self.owner = self
@@ -100,8 +163,9 @@ impl Tile {
         }
     }
 
-    pub fn has_improvement(&self, improvement: Improvement) -> bool {
-        self.improvements.contains(&improvement)
+    /// Returns whether the tile has a road or other route.
+    pub fn has_route(&self) -> bool {
+        self.improvements.iter().any(Improvement::is_route)
     }
 
     pub fn is_resource_improved(&self) -> bool {
@@ -113,59 +177,110 @@ impl Tile {
         }
     }
 
-    pub fn tile_yield(&self) -> Yield {
-        let mut y = Yield::default();
+    /// Gets the features on the tile, including the built-in
+    /// forest, hills, flood plains and fresh water features.
+    pub fn features(&self, registry: &Registry) -> Vec<Handle<TerrainFeature>> {
+        let builtin = [
+            (self.is_forested, FOREST_FEATURE),
+            (self.is_hilled, HILLS_FEATURE),
+            (self.is_flood_plains(), FLOOD_PLAINS_FEATURE),
+            (self.has_fresh_water, FRESH_WATER_FEATURE),
+        ];
+        let mut features: Vec<_> = builtin
+            .into_iter()
+            .filter(|(present, _)| *present)
+            .filter_map(|(_, id)| registry.terrain_feature(id).ok())
+            .collect();
+        features.extend(self.features.iter().cloned());
+        features
+    }
+
+    /// Gets the features whose effects apply to the tile, i.e.
+    /// those not suppressed by another feature.
+    ///
+    /// The result is cached, since yields are computed
+    /// for every visible tile each frame.
+    pub fn active_features(&self, registry: &Registry) -> &[Handle<TerrainFeature>] {
+        self.active_features.get_or_init(|| {
+            let features = self.features(registry);
+            features
+                .iter()
+                .filter(|feature| {
+                    !feature
+                        .suppressed_by
+                        .iter()
+                        .any(|id| features.iter().any(|other| &other.id == id))
+                })
+                .cloned()
+                .collect()
+        })
+    }
 
-        match self.terrain {
-            Terrain::Ocean => {
-                y.commerce += 2;
-                y.food += 1;
-            }
+    fn invalidate_active_features(&mut self) {
+        self.active_features = OnceCell::new();
+    }
 
-            Terrain::Plains => {
-                y.food += 1;
-                y.hammers += 1;
-            }
-            Terrain::Grassland => {
-                y.food += 2;
-                y.commerce += 1;
-            }
-            Terrain::Tundra => {
-                y.food += 1;
-            }
-            Terrain::Desert | Terrain::Mountains => {}
-        }
+    /// Gets the data-driven features placed on the tile,
+    /// excluding built-in ones.
+    pub fn placed_features(&self) -> impl Iterator<Item = &Handle<TerrainFeature>> + '_ {
+        self.features.iter()
+    }
 
-        if self.is_forested {
-            y.hammers += 1;
-        }
-        if self.is_hilled {
-            y.hammers += 1;
-            y.food = y.food.saturating_sub(1);
-        }
+    pub fn tile_yield(&self, game: &Game) -> Yield {
+        let registry = game.registry();
+        let mut y = registry
+            .terrain(self.terrain)
+            .map(|terrain| terrain.base_yield)
+            .unwrap_or_default();
 
-        if self.has_fresh_water && !self.is_forested {
-            y.commerce += 1;
+        let features = self.active_features(registry);
+        for feature in features {
+            y = y + feature.yield_bonus;
         }
-
-        if self.is_flood_plains() {
-            y.food += 3;
+        for feature in features {
+            y = y.saturating_sub(feature.yield_penalty);
         }
 
+        let owner = self.owner.map(|owner| game.player(owner));
+
         if let Some(resource) = &self.resource {
-            y = y + resource.yield_bonus;
+            // Resources give nothing until the owner can see them.
+            let is_revealed = match &owner {
+                Some(owner) => owner.has_revealed_resource(registry, resource),
+                None => true,
+            };
+            if is_revealed {
+                y = y + resource.yield_bonus;
 
-            if self
-                .improvements
-                .iter()
-                .any(|i| i.name() == resource.improvement)
-            {
-                y = y + resource.improved_bonus;
+                if self
+                    .improvements
+                    .iter()
+                    .any(|i| i.name() == resource.improvement)
+                {
+                    y = y + resource.improved_bonus;
+                }
             }
         }
 
         for improvement in &self.improvements {
-            y = y + improvement.yield_bonus();
+            y = y + improvement.yield_bonus(registry, owner.as_deref());
+        }
+
+        if let Some(owner) = &owner {
+            for effect in owner.tech_effects() {
+                let applies = match (effect.typ, &effect.target) {
+                    (TechEffectType::FeatureYield, Some(target)) => {
+                        features.iter().any(|f| &f.id == target)
+                    }
+                    (TechEffectType::ImprovementYield, Some(target)) => {
+                        self.improvements.iter().any(|i| &i.kind().id == target)
+                    }
+                    _ => false,
+                };
+                if applies {
+                    y = y + effect.yield_bonus;
+                }
+            }
         }
 
         y
@@ -199,6 +314,16 @@ impl Tile {
         self.resource.as_ref()
     }
 
+    /// Removes the resource from the tile if `player` hasn't
+    /// revealed it. Used to hide resources from clients.
+    pub fn hide_unrevealed_resource(&mut self, registry: &Registry, player: &Player) {
+        if let Some(resource) = &self.resource {
+            if !player.has_revealed_resource(registry, resource) {
+                self.resource = None;
+            }
+        }
+    }
+
     pub fn culture(&self) -> &Culture {
         &self.culture
     }
@@ -216,19 +341,27 @@ impl Tile {
     }
 
     pub fn movement_cost(&self, game: &Game, player: &Player) -> MovementPoints {
-        let mut cost = MovementPoints::from_u32(1);
-        if self.is_forested() || self.is_hilled() {
-            cost += MovementPoints::from_u32(1);
-        }
+        let registry = game.registry();
+        let terrain_cost = registry
+            .terrain(self.terrain)
+            .map(|terrain| terrain.movement_cost)
+            .unwrap_or(1);
+        let cost = self
+            .active_features(registry)
+            .iter()
+            .map(|feature| feature.movement_cost)
+            .fold(terrain_cost, u32::max);
+        let mut cost = MovementPoints::from_u32(cost);
 
-        if self.improvements().any(|i| matches!(i, Improvement::Road)) {
+        if self.has_route() {
             let can_use_road = match self.owner(game) {
                 Some(owner) => !player.is_at_war_with(owner),
                 None => true,
             };
 
             if can_use_road {
-                cost = MovementPoints::from_fixed_u32(cost.as_fixed_u32() / 3);
+                let divisor = ROUTE_TILES_PER_MOVEMENT + player.route_movement_bonus();
+                cost = MovementPoints::from_fixed_u32(cost.as_fixed_u32() / divisor);
             }
         }
 
@@ -241,15 +374,34 @@ impl Tile {
             .unwrap_or(false)
     }
 
-    pub fn defense_bonus(&self) -> u32 {
-        let mut bonus = 0;
-        if self.is_forested() {
-            bonus += 50;
-        }
-        if self.is_hilled() {
-            bonus += 25;
+    /// Gets the percent defense bonus for units on the tile.
+    pub fn defense_bonus(&self, game: &Game) -> u32 {
+        let registry = game.registry();
+        let terrain_bonus = registry
+            .terrain(self.terrain)
+            .map(|terrain| terrain.defense_bonus)
+            .unwrap_or_default();
+        terrain_bonus
+            + self
+                .active_features(registry)
+                .iter()
+                .map(|feature| feature.defense_bonus)
+                .sum::<u32>()
+    }
+
+    /// Gets the health and sickness the tile contributes to
+    /// a city that owns it.
+    pub fn health_and_sickness(&self, game: &Game) -> (f32, f32) {
+        let registry = game.registry();
+        let (mut health, mut sickness) = registry
+            .terrain(self.terrain)
+            .map(|terrain| (terrain.health, terrain.sickness))
+            .unwrap_or_default();
+        for feature in self.active_features(registry) {
+            health += feature.health;
+            sickness += feature.sickness;
         }
-        bonus
+        (health, sickness)
     }
 
     pub fn worked_by_city(&self) -> Option<CityId> {
@@ -258,18 +410,22 @@ impl Tile {
 
     pub fn set_forested(&mut self, f: bool) {
         self.is_forested = f;
+        self.invalidate_active_features();
     }
 
     pub fn set_hilled(&mut self, h: bool) {
         self.is_hilled = h;
+        self.invalidate_active_features();
     }
 
     pub fn set_terrain(&mut self, t: Terrain) {
         self.terrain = t;
+        self.invalidate_active_features();
     }
 
     pub fn set_has_fresh_water(&mut self, fresh_water: bool) {
         self.has_fresh_water = fresh_water;
+        self.invalidate_active_features();
     }
 
     pub fn set_worked_by_city(&mut self, by_city: Option<CityId>) {
@@ -280,14 +436,33 @@ impl Tile {
         self.resource = Some(resource);
     }
 
+    pub fn has_tribal_village(&self) -> bool {
+        self.has_tribal_village
+    }
+
+    pub fn add_tribal_village(&mut self) {
+        self.has_tribal_village = true;
+    }
+
+    pub(crate) fn remove_tribal_village(&mut self) {
+        self.has_tribal_village = false;
+    }
+
+    pub fn add_feature(&mut self, feature: Handle<TerrainFeature>) {
+        if !self.features.contains(&feature) {
+            self.features.push(feature);
+            self.invalidate_active_features();
+        }
+    }
+
     pub fn add_improvement(&mut self, improvement: Improvement) {
         if !self
             .improvements
             .iter()
-            .any(|i| mem::discriminant(i) == mem::discriminant(&improvement))
+            .any(|i| i.kind() == improvement.kind())
         {
-            if improvement != Improvement::Road {
-                self.is_forested = false;
+            if !improvement.is_route() {
+                self.set_forested(false);
             }
 
             self.improvements
@@ -297,17 +472,25 @@ impl Tile {
         }
     }
 
+    /// Removes an improvement of the same kind as `improvement`, if present.
+    pub fn remove_improvement(&mut self, improvement: &Improvement) {
+        self.improvements.retain(|i| i.kind() != improvement.kind());
+    }
+
     /// Should be called at the end of a turn if a city is working this tile.
-    pub fn work(&mut self) {
+    pub fn work(&mut self, game: &Game) {
         for improvement in &mut self.improvements {
-            if let Improvement::Cottage(cottage) = improvement {
-                cottage.work();
-            }
+            improvement.work(game.registry());
         }
     }
 }
 
 /// A terrain type.
+///
+/// The set of terrain types is fixed, since map generation and
+/// rendering rely on it. Mods can change the stats of each terrain
+/// type through [`TerrainKind`](crate::registry::TerrainKind) assets,
+/// and add new kinds of land through [`TerrainFeature`]s instead.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Terrain {
     Ocean,
@@ -332,11 +515,16 @@ pub struct OutOfBounds {
 }
 
 /// A 2D array that can be used to store tiles.
+///
+/// A grid can optionally wrap around along the X axis, making
+/// the map a cylinder. Neighborhood and distance helpers
+/// then treat the leftmost and rightmost columns as adjacent.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Grid<T> {
     tiles: Box<[T]>,
     width: u32,
     height: u32,
+    wrap_x: bool,
 }
 
 impl<T> Grid<T> {
@@ -348,9 +536,19 @@ impl<T> Grid<T> {
             tiles: vec![initial_value; width as usize * height as usize].into_boxed_slice(),
             width,
             height,
+            wrap_x: false,
         }
     }
 
+    /// Returns whether the grid wraps around along the X axis.
+    pub fn wraps_x(&self) -> bool {
+        self.wrap_x
+    }
+
+    pub fn set_wrap_x(&mut self, wrap_x: bool) {
+        self.wrap_x = wrap_x;
+    }
+
     pub fn get(&self, pos: UVec2) -> Result<&T, OutOfBounds> {
         let index = self.index(pos)?;
         Ok(&self.tiles[index])
@@ -386,9 +584,10 @@ impl<T> Grid<T> {
                 if dx == 0 && dy == 0 {
                     continue;
                 }
-                let p = pos.as_i32() + ivec2(dx, dy);
-                if self.is_in_bounds(p) {
-                    result.push(p.as_u32());
+                if let Some(p) = self.wrap(pos.as_i32() + ivec2(dx, dy)) {
+                    if p != pos && !result.contains(&p) {
+                        result.push(p);
+                    }
                 }
             }
         }
@@ -403,10 +602,10 @@ impl<T> Grid<T> {
         let mut adjacent = ArrayVec::new();
 
         for [dx, dy] in [[1, 0], [-1, 0], [0, 1], [0, -1]] {
-            let x = pos.x as i32 + dx;
-            let y = pos.y as i32 + dy;
-            if self.is_in_bounds(ivec2(x, y)) {
-                adjacent.push(uvec2(x as u32, y as u32));
+            if let Some(p) = self.wrap(pos.as_i32() + ivec2(dx, dy)) {
+                if p != pos && !adjacent.contains(&p) {
+                    adjacent.push(p);
+                }
             }
         }
 
@@ -427,9 +626,10 @@ impl<T> Grid<T> {
                     continue;
                 }
 
-                let bfc_pos = pos.as_i32() + ivec2(dx, dy);
-                if self.is_in_bounds(bfc_pos) {
-                    bfc.push(bfc_pos.as_u32());
+                if let Some(bfc_pos) = self.wrap(pos.as_i32() + ivec2(dx, dy)) {
+                    if bfc_pos != pos && !bfc.contains(&bfc_pos) {
+                        bfc.push(bfc_pos);
+                    }
                 }
             }
         }
@@ -450,7 +650,9 @@ impl<T> Grid<T> {
             tiles.push(next);
 
             for neighbor in self.straight_adjacent(next) {
-                if visited.insert(neighbor) && neighbor.distance_squared(pos) <= radius_squared {
+                if visited.insert(neighbor)
+                    && self.distance_squared(neighbor, pos) <= radius_squared
+                {
                     stack.push(neighbor);
                 }
             }
@@ -467,14 +669,92 @@ impl<T> Grid<T> {
             tiles: self.tiles.into_iter().cloned().map(mapper).collect(),
             width: self.width,
             height: self.height,
+            wrap_x: self.wrap_x,
         }
     }
 
+    /// Converts a grid from an unversioned save file.
+    /// Such grids never wrap.
+    pub(crate) fn from_legacy(legacy: legacy::Grid<T>) -> Self {
+        Self {
+            tiles: legacy.tiles,
+            width: legacy.width,
+            height: legacy.height,
+            wrap_x: false,
+        }
+    }
+
+    pub fn try_map<G, E>(self, mapper: impl FnMut(T) -> Result<G, E>) -> Result<Grid<G>, E> {
+        Ok(Grid {
+            tiles: self
+                .tiles
+                .into_vec()
+                .into_iter()
+                .map(mapper)
+                .collect::<Result<_, _>>()?,
+            width: self.width,
+            height: self.height,
+            wrap_x: self.wrap_x,
+        })
+    }
+
+    /// Returns whether `pos` lies on the grid without wrapping.
+    ///
+    /// Use [`Grid::wrap`] for positions computed by offsetting another position.
     pub fn is_in_bounds(&self, pos: IVec2) -> bool {
         let (x, y) = (pos.x, pos.y);
         x >= 0 && y >= 0 && x < self.width as i32 && y < self.height as i32
     }
 
+    /// Converts a position that may lie off the grid
+    /// to the position of the tile it refers to, wrapping
+    /// the X coordinate if the grid wraps.
+    ///
+    /// Returns `None` if the position is out of bounds.
+    pub fn wrap(&self, pos: IVec2) -> Option<UVec2> {
+        let mut pos = pos;
+        if self.wrap_x {
+            pos.x = pos.x.rem_euclid(self.width as i32);
+        }
+        if self.is_in_bounds(pos) {
+            Some(pos.as_u32())
+        } else {
+            None
+        }
+    }
+
+    /// Gets the shortest offset from `from` to `to`,
+    /// which may cross the wrapping edge of the grid.
+    pub fn offset(&self, from: UVec2, to: UVec2) -> IVec2 {
+        let mut offset = to.as_i32() - from.as_i32();
+        if self.wrap_x {
+            let width = self.width as i32;
+            if offset.x > width / 2 {
+                offset.x -= width;
+            } else if offset.x < -width / 2 {
+                offset.x += width;
+            }
+        }
+        offset
+    }
+
+    /// Gets the squared Euclidean distance between two positions.
+    pub fn distance_squared(&self, a: UVec2, b: UVec2) -> u32 {
+        let offset = self.offset(a, b);
+        (offset.x * offset.x + offset.y * offset.y) as u32
+    }
+
+    /// Gets the Euclidean distance between two positions.
+    pub fn distance(&self, a: UVec2, b: UVec2) -> f64 {
+        (self.distance_squared(a, b) as f64).sqrt()
+    }
+
+    /// Gets the number of king moves between two positions,
+    /// i.e. the largest of the distances along each axis.
+    pub fn tile_distance(&self, a: UVec2, b: UVec2) -> u32 {
+        self.offset(a, b).abs().max_element() as u32
+    }
+
     pub fn rings(&self, pos: UVec2) -> Rings<T> {
         Rings::new(self, pos)
     }
@@ -503,22 +783,25 @@ impl Grid<f64> {
     /// Samples the grid at the given point with linear interpolation.
     ///
     /// Unlike other grid functions, this interprets the grid as a continuous
-    /// field instead of a discrete list of tiles.
+    /// field instead of a discrete list of tiles. Points past the right edge
+    /// of a wrapping grid blend into its left edge.
     pub fn sample(&self, pos: DVec2) -> f64 {
-        let x1 = pos.x.floor() as u32;
-        let y1 = pos.y.floor() as u32;
+        let x1 = pos.x.floor() as i32;
+        let y1 = pos.y.floor() as i32;
         let x2 = x1 + 1;
         let y2 = y1 + 1;
 
-        let pos_a = uvec2(x1, y1);
-        let pos_b = uvec2(x2, y1);
-        let pos_c = uvec2(x2, y2);
-        let pos_d = uvec2(x1, y2);
+        let value_at = |pos: IVec2| {
+            self.wrap(pos)
+                .and_then(|pos| self.get(pos).ok())
+                .copied()
+                .unwrap_or_default()
+        };
 
-        let a = self.get(pos_a).map(|x| *x).unwrap_or_default();
-        let b = self.get(pos_b).map(|x| *x).unwrap_or_default();
-        let c = self.get(pos_c).map(|x| *x).unwrap_or_default();
-        let d = self.get(pos_d).map(|x| *x).unwrap_or_default();
+        let a = value_at(ivec2(x1, y1));
+        let b = value_at(ivec2(x2, y1));
+        let c = value_at(ivec2(x2, y2));
+        let d = value_at(ivec2(x1, y2));
 
         let x_coeff = pos.x.fract();
         let y_coeff = pos.y.fract();
@@ -564,3 +847,86 @@ impl<'a, T> Iterator for Rings<'a, T> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec2, uvec2};
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn tech_effects_add_feature_yields_on_owned_tiles() {
+        let mut game = testing::new_game(4, 3);
+        let player = testing::add_player(&mut game, "rome");
+        testing::add_city(&mut game, player, uvec2(1, 1));
+        let pos = uvec2(2, 1);
+        game.tile_mut(pos).unwrap().set_has_fresh_water(true);
+
+        let commerce = |game: &Game| game.tile(pos).unwrap().tile_yield(game).commerce;
+        assert_eq!(commerce(&game), 2);
+
+        let sailing = game.registry().tech("Sailing").unwrap();
+        game.player_mut(player).grant_tech(sailing);
+        assert_eq!(commerce(&game), 3);
+    }
+
+    #[test]
+    fn feature_yields_follow_tile_changes() {
+        let game = testing::new_game(3, 3);
+        let pos = uvec2(1, 1);
+        let tile_yield = |game: &Game| game.tile(pos).unwrap().tile_yield(game);
+
+        game.tile_mut(pos).unwrap().set_has_fresh_water(true);
+        let y = tile_yield(&game);
+        assert_eq!((y.food, y.hammers, y.commerce), (2, 0, 2));
+
+        // Forests suppress the fresh water commerce bonus.
+        game.tile_mut(pos).unwrap().set_forested(true);
+        let y = tile_yield(&game);
+        assert_eq!((y.food, y.hammers, y.commerce), (2, 1, 1));
+
+        let mut tile = game.tile_mut(pos).unwrap();
+        tile.set_forested(false);
+        tile.set_has_fresh_water(false);
+        tile.add_feature(game.registry().terrain_feature("jungle").unwrap());
+        drop(tile);
+        let y = tile_yield(&game);
+        assert_eq!((y.food, y.hammers, y.commerce), (1, 0, 1));
+
+        let mut tile = game.tile_mut(uvec2(0, 0)).unwrap();
+        tile.set_terrain(Terrain::Desert);
+        tile.add_feature(game.registry().terrain_feature("oasis").unwrap());
+        drop(tile);
+        let y = game.tile(uvec2(0, 0)).unwrap().tile_yield(&game);
+        assert_eq!((y.food, y.hammers, y.commerce), (3, 0, 2));
+    }
+
+    #[test]
+    fn wrapping_grid_neighbors_and_distances() {
+        let mut grid = Grid::new(0, 10, 5);
+        assert!(!grid.adjacent(uvec2(0, 2)).contains(&uvec2(9, 2)));
+        assert_eq!(grid.tile_distance(uvec2(0, 2), uvec2(9, 2)), 9);
+
+        grid.set_wrap_x(true);
+        assert!(grid.adjacent(uvec2(0, 2)).contains(&uvec2(9, 2)));
+        assert_eq!(grid.adjacent(uvec2(0, 2)).len(), 8);
+        assert_eq!(grid.tile_distance(uvec2(0, 2), uvec2(9, 2)), 1);
+        assert_eq!(grid.offset(uvec2(9, 2), uvec2(0, 3)), ivec2(1, 1));
+        assert_eq!(grid.wrap(ivec2(-1, 0)), Some(uvec2(9, 0)));
+        assert_eq!(grid.wrap(ivec2(0, -1)), None);
+    }
+
+    #[test]
+    fn sampling_a_wrapping_grid_blends_across_the_edge() {
+        let mut grid = Grid::new(0., 4, 2);
+        grid.set(uvec2(0, 0), 1.).unwrap();
+        grid.set(uvec2(0, 1), 1.).unwrap();
+        assert_eq!(grid.sample(dvec2(3.5, 0.)), 0.);
+
+        grid.set_wrap_x(true);
+        assert_eq!(grid.sample(dvec2(3.5, 0.)), 0.5);
+        assert_eq!(grid.sample(dvec2(0.5, 0.)), 0.5);
+    }
+}
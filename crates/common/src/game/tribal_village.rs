@@ -0,0 +1,241 @@
+//! Tribal villages placed by the map generator.
+//!
+//! The first unit to enter a village receives a random reward
+//! for its owner. Some villages are hostile and release barbarians.
+
+use glam::{ivec2, UVec2};
+use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::Handle,
+    barbarians,
+    event::Event,
+    registry::{Tech, UnitKind},
+    Game, PlayerId, Unit,
+};
+
+/// Smallest amount of gold found in a village.
+const MIN_GOLD: u32 = 25;
+/// Largest amount of gold found in a village.
+const MAX_GOLD: u32 = 75;
+/// Radius of the area revealed by a village's map, in tiles.
+const MAP_REVEAL_RADIUS: i32 = 6;
+/// Maximum number of barbarians released by a hostile village.
+const MAX_BARBARIANS: usize = 2;
+/// Units a village may join the player as.
+const FREE_UNIT_KINDS: [&str; 2] = ["scout", "worker"];
+
+/// What a unit found in a tribal village.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum VillageOutcome {
+    Gold(u32),
+    Tech(Handle<Tech>),
+    /// The villagers shared a map of the surrounding area.
+    MapRevealed,
+    /// Villagers joined the player as a unit.
+    FreeUnit(Handle<UnitKind>),
+    /// The village was hostile and released barbarians.
+    Barbarians,
+}
+
+impl VillageOutcome {
+    pub fn description(&self) -> String {
+        match self {
+            VillageOutcome::Gold(amount) => format!("We found {} gold in the village!", amount),
+            VillageOutcome::Tech(tech) => {
+                format!("The villagers taught us the secrets of {}!", tech.name)
+            }
+            VillageOutcome::MapRevealed => {
+                "The villagers gave us a map of the surrounding lands.".to_owned()
+            }
+            VillageOutcome::FreeUnit(kind) => {
+                format!("Some of the villagers joined us as a {}!", kind.name)
+            }
+            VillageOutcome::Barbarians => "The village was full of hostile barbarians!".to_owned(),
+        }
+    }
+}
+
+/// A player's unit entered a tribal village.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TribalVillageEntered {
+    pub player: PlayerId,
+    pub pos: UVec2,
+    pub outcome: VillageOutcome,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum OutcomeKind {
+    Gold,
+    Tech,
+    MapRevealed,
+    FreeUnit,
+    Barbarians,
+}
+
+impl OutcomeKind {
+    /// Relative chance of the outcome.
+    fn weight(self) -> u32 {
+        match self {
+            OutcomeKind::Gold => 30,
+            OutcomeKind::Tech => 15,
+            OutcomeKind::MapRevealed => 20,
+            OutcomeKind::FreeUnit => 20,
+            OutcomeKind::Barbarians => 15,
+        }
+    }
+}
+
+/// Gives `player` the reward of the village at `pos`
+/// and removes the village.
+pub(crate) fn enter(game: &mut Game, player: PlayerId, pos: UVec2) {
+    if !game.tile(pos).unwrap().has_tribal_village() {
+        return;
+    }
+    game.tile_mut(pos).unwrap().remove_tribal_village();
+    game.push_event(Event::TileChanged(pos));
+
+    let techs = game.player(player).researchable_techs(game);
+    let unit_kinds: Vec<Handle<UnitKind>> = FREE_UNIT_KINDS
+        .iter()
+        .filter_map(|id| game.registry().unit_kind(id).ok())
+        .collect();
+    let can_release_barbarians = barbarians::barbarian_player(game).is_some()
+        && !barbarians::free_tiles_near(game, pos).is_empty();
+
+    let mut kinds = vec![OutcomeKind::Gold, OutcomeKind::MapRevealed];
+    if !techs.is_empty() {
+        kinds.push(OutcomeKind::Tech);
+    }
+    if !unit_kinds.is_empty() {
+        kinds.push(OutcomeKind::FreeUnit);
+    }
+    if can_release_barbarians {
+        kinds.push(OutcomeKind::Barbarians);
+    }
+
+    let kind = *kinds
+        .choose_weighted(&mut *game.rng(), |kind| kind.weight())
+        .unwrap();
+    let outcome = match kind {
+        OutcomeKind::Gold => {
+            let amount = game.rng().gen_range(MIN_GOLD..=MAX_GOLD);
+            game.player_mut(player).add_gold(amount);
+            game.push_event(Event::PlayerChanged(player));
+            VillageOutcome::Gold(amount)
+        }
+        OutcomeKind::Tech => {
+            let tech = techs.choose(&mut *game.rng()).unwrap().clone();
+            game.player_mut(player).unlock_tech(game, tech.clone());
+            game.push_event(Event::PlayerChanged(player));
+            VillageOutcome::Tech(tech)
+        }
+        OutcomeKind::MapRevealed => {
+            let tiles: Vec<UVec2> = (-MAP_REVEAL_RADIUS..=MAP_REVEAL_RADIUS)
+                .flat_map(|dx| {
+                    (-MAP_REVEAL_RADIUS..=MAP_REVEAL_RADIUS).map(move |dy| ivec2(dx, dy))
+                })
+                .filter_map(|offset| game.map().wrap(pos.as_i32() + offset))
+                .collect();
+            game.player_mut(player).reveal_tiles(tiles);
+            game.push_event(Event::PlayerChanged(player));
+            VillageOutcome::MapRevealed
+        }
+        OutcomeKind::FreeUnit => {
+            let kind = unit_kinds.choose(&mut *game.rng()).unwrap().clone();
+            let id = game.new_unit_id();
+            game.add_unit(Unit::new(id, player, kind.clone(), pos));
+            game.defer(move |game| game.player_mut(player).update_visibility(game));
+            VillageOutcome::FreeUnit(kind)
+        }
+        OutcomeKind::Barbarians => {
+            let count = game.rng().gen_range(1..=MAX_BARBARIANS);
+            barbarians::spawn_units_near(game, pos, count);
+            VillageOutcome::Barbarians
+        }
+    };
+
+    log::info!("Tribal village at {:?}: {:?}", pos, outcome);
+    game.push_event(Event::TribalVillageEntered(TribalVillageEntered {
+        player,
+        pos,
+        outcome,
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::{testing, Visibility};
+
+    use super::*;
+
+    #[test]
+    fn villages_reward_the_first_unit_to_enter() {
+        let mut game = testing::new_game(40, 8);
+        let player = testing::add_player(&mut game, "rome");
+        let handicap = game.registry().handicap("noble").unwrap();
+        let barbarian = barbarians::add_barbarian_player(&mut game, handicap).unwrap();
+
+        // Barbarians leave villages alone.
+        let pos = uvec2(2, 4);
+        game.tile_mut(pos).unwrap().add_tribal_village();
+        let raider = testing::add_unit(&mut game, barbarian, "warrior", uvec2(1, 4));
+        game.unit_mut(raider).move_to(&game, pos);
+        game.run_deferred_functions();
+        assert!(game.tile(pos).unwrap().has_tribal_village());
+        game.remove_unit(raider);
+
+        for x in (2..38).step_by(3) {
+            let pos = uvec2(x, 4);
+            game.tile_mut(pos).unwrap().add_tribal_village();
+            let scout = testing::add_unit(&mut game, player, "scout", uvec2(x, 3));
+            let gold = game.player(player).gold();
+            game.drain_events(|_| {});
+
+            game.unit_mut(scout).move_to(&game, pos);
+            game.run_deferred_functions();
+            assert!(!game.tile(pos).unwrap().has_tribal_village());
+
+            let mut outcome = None;
+            game.drain_events(|event| {
+                if let Event::TribalVillageEntered(entered) = event {
+                    assert_eq!(entered.player, player);
+                    assert_eq!(entered.pos, pos);
+                    outcome = Some(entered.outcome);
+                }
+            });
+            match outcome.expect("no village entered") {
+                VillageOutcome::Gold(amount) => {
+                    assert!((MIN_GOLD..=MAX_GOLD).contains(&amount));
+                    assert_eq!(game.player(player).gold(), gold + amount);
+                }
+                VillageOutcome::Tech(tech) => {
+                    assert!(game.player(player).has_unlocked_tech(&tech));
+                }
+                VillageOutcome::MapRevealed => {
+                    let far = uvec2(x, 4 + MAP_REVEAL_RADIUS as u32 - 3);
+                    assert_ne!(game.player(player).visibility_at(far), Visibility::Hidden);
+                }
+                VillageOutcome::FreeUnit(kind) => {
+                    assert!(game
+                        .units_by_pos(pos)
+                        .any(|u| u.id() != scout && u.kind() == &kind));
+                }
+                VillageOutcome::Barbarians => {
+                    let released = game
+                        .map()
+                        .adjacent(pos)
+                        .into_iter()
+                        .flat_map(|p| game.units_by_pos(p).map(|u| u.owner()).collect::<Vec<_>>())
+                        .filter(|&owner| owner == barbarian)
+                        .count();
+                    assert!(released <= MAX_BARBARIANS);
+                }
+            }
+            game.remove_unit(scout);
+        }
+    }
+}
@@ -12,16 +12,28 @@ use serde::{Deserialize, Serialize};
 use crate::{
     assets::Handle,
     combat::CombatSimulator,
+    espionage::{self, EspionageMission},
     event::Event,
-    registry::{CapabilityType, CombatBonusType, UnitKind},
+    path::{self, Path},
+    registry::{
+        CapabilityType, CombatBonusType, Promotion, PromotionEffectType, Registry,
+        RegistryItemNotFound, Religion, UnitCategory, UnitKind,
+    },
+    saveload::legacy,
+    tribal_village,
     world::Game,
-    City,
+    City, Improvement,
 };
 
 use super::{PlayerId, UnitId};
 
 pub use crate::worker::WorkerTask;
 
+/// Base gold cost of any unit upgrade.
+const UPGRADE_BASE_COST: u32 = 20;
+/// Gold cost per hammer of difference between the old and new unit kinds.
+const UPGRADE_COST_PER_HAMMER: u32 = 3;
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum UnitMoveOutcome {
     Success,
@@ -29,6 +41,20 @@ pub enum UnitMoveOutcome {
     Combat,
 }
 
+/// A standing order that the server carries out for a unit
+/// at the start of each turn until it completes or is interrupted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitOrder {
+    /// Move toward `target` over as many turns as needed.
+    ///
+    /// `path` is the path the unit will follow from its current position.
+    MoveTo { target: UVec2, path: Path },
+    /// Move toward the nearest unexplored tiles, avoiding enemies.
+    Explore,
+    /// Build improvements and roads for the owner's cities.
+    AutomateWorker,
+}
+
 /// Represents a unit in the game.
 ///
 /// All fields are private and encapsulated. Modifying unit
@@ -51,12 +77,28 @@ pub struct Unit {
     is_fortified_forever: bool,
     is_skipping_turn: bool,
     is_fortified_until_heal: bool,
+    /// Whether the unit is waiting until an enemy comes into view.
+    is_sentry: bool,
+    /// Whether the unit is fortified until an enemy comes into view.
+    is_on_alert: bool,
 
     /// Whether the unit has used its one attack for this
     /// turn and therefore cannot attack again until the next turn.
     has_used_attack: bool,
 
+    /// Whether the unit moved between two tiles in an enemy
+    /// zone of control and cannot move again until the next turn.
+    stopped_by_zone_of_control: bool,
+
     capabilities: Vec<Capability>,
+
+    /// Experience points gained from combat
+    /// and from being trained in a city with a barracks.
+    experience: u32,
+    /// Promotions the unit has received.
+    promotions: Vec<Handle<Promotion>>,
+
+    order: Option<UnitOrder>,
 }
 
 impl Unit {
@@ -72,26 +114,72 @@ impl Unit {
             is_fortified_forever: false,
             is_skipping_turn: false,
             is_fortified_until_heal: false,
+            is_sentry: false,
+            is_on_alert: false,
             has_used_attack: false,
-            capabilities: kind
-                .capabilities
-                .iter()
-                .map(|ty| match ty {
-                    CapabilityType::FoundCity => Capability::FoundCity,
-                    CapabilityType::DoWork => {
-                        Capability::Worker(WorkerCapability { current_task: None })
-                    }
-                    CapabilityType::CarryUnits => todo!(),
-                    CapabilityType::BombardCityDefenses => Capability::BombardCity {
-                        max_per_turn: kind.max_bombard_per_turn,
-                    },
-                })
-                .collect(),
+            stopped_by_zone_of_control: false,
+            capabilities: capabilities_for_kind(&kind),
+            experience: 0,
+            promotions: Vec::new(),
+            order: None,
         }
     }
 
+    /// Converts a unit from an unversioned save file.
+    pub(crate) fn from_legacy(
+        legacy: legacy::Unit,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let legacy::Unit {
+            on_server,
+            id,
+            owner,
+            kind,
+            pos,
+            health,
+            movement_left,
+            is_fortified_forever,
+            is_skipping_turn,
+            is_fortified_until_heal,
+            has_used_attack,
+            capabilities,
+        } = legacy;
+        Ok(Self {
+            on_server,
+            id,
+            owner,
+            kind,
+            pos,
+            health,
+            movement_left,
+            is_fortified_forever,
+            is_skipping_turn,
+            is_fortified_until_heal,
+            is_sentry: false,
+            is_on_alert: false,
+            has_used_attack,
+            stopped_by_zone_of_control: false,
+            capabilities: capabilities
+                .into_iter()
+                .map(|c| Capability::from_legacy(c, registry))
+                .collect::<Result<_, _>>()?,
+            experience: 0,
+            promotions: Vec::new(),
+            order: None,
+        })
+    }
+
     pub fn is_fortified(&self) -> bool {
-        self.is_fortified_forever || self.is_skipping_turn || self.is_fortified_until_heal
+        self.is_fortified_forever
+            || self.is_skipping_turn
+            || self.is_fortified_until_heal
+            || self.is_sentry
+            || self.is_on_alert
+    }
+
+    /// Returns whether the unit will wake up when an enemy comes into view.
+    pub fn is_watching_for_enemies(&self) -> bool {
+        self.is_sentry || self.is_on_alert
     }
 
     pub fn health(&self) -> f64 {
@@ -119,9 +207,22 @@ impl Unit {
             Capability::FoundCity => typ == CapabilityType::FoundCity,
             Capability::BombardCity { .. } => typ == CapabilityType::BombardCityDefenses,
             Capability::Worker(_) => typ == CapabilityType::DoWork,
+            Capability::SpreadReligion => typ == CapabilityType::SpreadReligion,
+            Capability::Espionage => typ == CapabilityType::Espionage,
         })
     }
 
+    /// Returns whether the unit is invisible to other players,
+    /// as spies are.
+    pub fn is_invisible(&self) -> bool {
+        self.has_capability(CapabilityType::Espionage)
+    }
+
+    /// Returns whether the unit is never shown to `player`.
+    pub fn is_hidden_from(&self, player: PlayerId) -> bool {
+        self.is_invisible() && self.owner != player
+    }
+
     pub fn has_worker_task(&self) -> bool {
         self.worker_task().is_some()
     }
@@ -145,12 +246,22 @@ impl Unit {
         }
     }
 
+    pub fn order(&self) -> Option<&UnitOrder> {
+        self.order.as_ref()
+    }
+
+    pub fn set_order(&mut self, order: Option<UnitOrder>) {
+        self.order = order;
+    }
+
     pub fn can_attack(&self) -> bool {
         self.strength() > 0. && !self.has_used_attack
     }
 
     pub fn will_attack(&self, game: &Game, unit: &Unit) -> bool {
-        game.player(self.owner).is_at_war_with(unit.owner()) && self.can_attack()
+        game.player(self.owner).is_at_war_with(unit.owner())
+            && self.can_attack()
+            && !unit.is_invisible()
     }
 
     pub fn id(&self) -> UnitId {
@@ -161,6 +272,119 @@ impl Unit {
         self.health() * self.kind().strength
     }
 
+    pub fn experience(&self) -> u32 {
+        self.experience
+    }
+
+    pub fn promotions(&self) -> impl Iterator<Item = &Handle<Promotion>> + '_ {
+        self.promotions.iter()
+    }
+
+    pub fn has_promotion(&self, promotion: &Handle<Promotion>) -> bool {
+        self.promotions.contains(promotion)
+    }
+
+    /// The unit's level, which increases with each promotion.
+    pub fn level(&self) -> u32 {
+        self.promotions.len() as u32 + 1
+    }
+
+    /// The total experience needed before the next promotion can be taken.
+    pub fn experience_needed_for_promotion(&self) -> u32 {
+        self.level() * self.level() + 1
+    }
+
+    pub fn add_experience(&mut self, amount: u32) {
+        self.experience += amount;
+    }
+
+    /// Sums the amounts of all promotion effects of the given type.
+    ///
+    /// For `AgainstUnitCategory`, only effects matching `category` are counted.
+    fn promotion_effect(&self, typ: PromotionEffectType, category: Option<UnitCategory>) -> u32 {
+        self.promotions
+            .iter()
+            .flat_map(|p| p.effects.iter())
+            .filter(|e| e.typ == typ)
+            .filter(|e| {
+                typ != PromotionEffectType::AgainstUnitCategory || e.unit_category == category
+            })
+            .map(|e| e.amount)
+            .sum()
+    }
+
+    /// Returns whether the unit can receive the given promotion now.
+    pub fn can_promote(&self, promotion: &Handle<Promotion>) -> Result<(), CannotPromote> {
+        if self.has_promotion(promotion) {
+            return Err(CannotPromote::AlreadyHasPromotion);
+        }
+
+        if !promotion.unit_categories.contains(&self.kind.category) {
+            return Err(CannotPromote::WrongUnitCategory);
+        }
+
+        if !promotion.prerequisites.is_empty()
+            && !self
+                .promotions()
+                .any(|p| promotion.prerequisites.contains(&p.id))
+        {
+            return Err(CannotPromote::MissingPrerequisites);
+        }
+
+        if self.experience < self.experience_needed_for_promotion() {
+            return Err(CannotPromote::NotEnoughExperience);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the promotions the unit can currently receive.
+    pub fn available_promotions(&self, game: &Game) -> Vec<Handle<Promotion>> {
+        game.registry()
+            .promotions()
+            .filter(|p| self.can_promote(p).is_ok())
+            .cloned()
+            .collect()
+    }
+
+    /// Gives the unit a promotion.
+    pub fn promote(
+        &mut self,
+        game: &Game,
+        promotion: Handle<Promotion>,
+    ) -> Result<(), CannotPromote> {
+        assert!(self.on_server);
+        self.can_promote(&promotion)?;
+
+        log::info!("Promoting {} with {}", self.kind.name, promotion.name);
+
+        // Movement bonuses apply immediately.
+        let bonus_movement = promotion
+            .effects
+            .iter()
+            .filter(|e| e.typ == PromotionEffectType::Movement)
+            .map(|e| e.amount)
+            .sum();
+        self.movement_left += MovementPoints::from_u32(bonus_movement);
+
+        self.promotions.push(promotion);
+        game.push_event(Event::UnitChanged(self.id));
+
+        Ok(())
+    }
+
+    /// The number of movement points the unit gets each turn.
+    pub fn max_movement(&self) -> u32 {
+        self.kind.movement + self.promotion_effect(PromotionEffectType::Movement, None)
+    }
+
+    /// Computes this unit's modified attack strength against
+    /// a defender.
+    pub fn modified_attacking_strength(&self) -> f64 {
+        let percent_bonus = self.promotion_effect(PromotionEffectType::StrengthPercent, None);
+        self.strength() * (1. + percent_bonus as f64 / 100.)
+    }
+
     pub fn has_used_attack(&self) -> bool {
         self.has_used_attack
     }
@@ -180,7 +404,7 @@ impl Unit {
 
         // Tile defense bonus
         let tile = game.tile(self.pos).unwrap();
-        percent_bonus += tile.defense_bonus() as i32;
+        percent_bonus += tile.defense_bonus(game) as i32;
 
         // City + building defense bonus - TODO
 
@@ -208,6 +432,19 @@ impl Unit {
             }
         }
 
+        // Opponent promotions against our category
+        percent_bonus -= attacker.promotion_effect(
+            PromotionEffectType::AgainstUnitCategory,
+            Some(self.kind.category),
+        ) as i32;
+
+        // Our promotions
+        percent_bonus += self.promotion_effect(PromotionEffectType::StrengthPercent, None) as i32;
+        percent_bonus += self.promotion_effect(
+            PromotionEffectType::AgainstUnitCategory,
+            Some(attacker.kind.category),
+        ) as i32;
+
         // Add our bonuses
         for bonus in &self.kind.combat_bonuses {
             if bonus.only_on_attack {
@@ -268,13 +505,13 @@ impl Unit {
     }
 
     pub fn movement_text(&self) -> String {
-        if self.movement_left().as_f64().ceil() as u32 == self.kind().movement {
+        if self.movement_left().as_f64().ceil() as u32 == self.max_movement() {
             lexical::to_string(self.movement_left().as_f64().ceil() as u32)
         } else {
             format!(
                 "{} / {}",
                 lexical::to_string(self.movement_left().as_f64().ceil() as u32),
-                self.kind().movement
+                self.max_movement()
             )
         }
     }
@@ -283,6 +520,10 @@ impl Unit {
         self.on_server = false;
     }
 
+    pub(crate) fn is_on_server(&self) -> bool {
+        self.on_server
+    }
+
     /// Returns whether the unit can found a city on the tile it's currently on.
     pub fn can_found_city(&self, game: &Game) -> Result<(), CannotFoundCity> {
         // This unit must be able to found a city.
@@ -343,10 +584,290 @@ impl Unit {
         Ok(())
     }
 
+    /// Returns the religion the unit would spread to the city on its tile:
+    /// its owner's state religion.
+    pub fn can_spread_religion(
+        &self,
+        game: &Game,
+    ) -> Result<Handle<Religion>, CannotSpreadReligion> {
+        if !self.has_capability(CapabilityType::SpreadReligion) {
+            return Err(CannotSpreadReligion::MissingCapability);
+        }
+
+        if !self.has_movement_left() {
+            return Err(CannotSpreadReligion::NoMovementLeft);
+        }
+
+        let city = game
+            .city_at_pos(self.pos)
+            .ok_or(CannotSpreadReligion::NotInCity)?;
+        if game.player(self.owner).is_at_war_with(city.owner()) {
+            return Err(CannotSpreadReligion::EnemyCity);
+        }
+
+        let religion = game
+            .player(self.owner)
+            .state_religion()
+            .cloned()
+            .ok_or(CannotSpreadReligion::NoStateReligion)?;
+        if city.has_religion(&religion) {
+            return Err(CannotSpreadReligion::AlreadyPresent);
+        }
+
+        Ok(religion)
+    }
+
+    /// Spreads the owner's state religion to the city on the
+    /// unit's tile. The unit is consumed.
+    pub fn spread_religion(&mut self, game: &Game) -> Result<(), CannotSpreadReligion> {
+        assert!(self.on_server);
+        let religion = self.can_spread_religion(game)?;
+
+        let this = self.id();
+        let pos = self.pos;
+        game.defer(move |game| {
+            let city_id = game.city_id_at_pos(pos).unwrap();
+            {
+                let mut city = game.city_mut(city_id);
+                log::info!("{} was spread to {}", religion.name, city.name());
+                city.add_religion(religion);
+                city.update_statuses(game);
+            }
+            game.push_event(Event::CityChanged(city_id));
+            game.remove_unit(this);
+        });
+
+        Ok(())
+    }
+
+    /// Checks whether the unit can perform an espionage mission
+    /// in the foreign city on its tile.
+    pub fn can_perform_mission(
+        &self,
+        game: &Game,
+        mission: EspionageMission,
+    ) -> Result<(), CannotPerformMission> {
+        if !self.has_capability(CapabilityType::Espionage) {
+            return Err(CannotPerformMission::MissingCapability);
+        }
+
+        if !self.has_movement_left() {
+            return Err(CannotPerformMission::NoMovementLeft);
+        }
+
+        let city = game
+            .city_at_pos(self.pos)
+            .ok_or(CannotPerformMission::NotInCity)?;
+        if city.owner() == self.owner {
+            return Err(CannotPerformMission::OwnCity);
+        }
+
+        if game
+            .player(self.owner)
+            .espionage_points_against(city.owner())
+            < mission.cost()
+        {
+            return Err(CannotPerformMission::NotEnoughPoints(mission.cost()));
+        }
+
+        match mission {
+            EspionageMission::StealTech => {
+                if espionage::stealable_techs(game, self.owner, city.owner()).is_empty() {
+                    return Err(CannotPerformMission::NothingToSteal);
+                }
+            }
+            EspionageMission::SabotageProduction => {
+                let progress = city
+                    .build_task()
+                    .map(|task| city.build_task_progress(task))
+                    .unwrap_or(0);
+                if progress == 0 {
+                    return Err(CannotPerformMission::NoProduction);
+                }
+            }
+            EspionageMission::PoisonWater => {
+                if city.is_poisoned() {
+                    return Err(CannotPerformMission::AlreadyAffected);
+                }
+            }
+            EspionageMission::InciteRevolt => {
+                if city.is_in_revolt() {
+                    return Err(CannotPerformMission::AlreadyAffected);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Performs an espionage mission in the city on the unit's tile.
+    /// The unit is consumed whether or not the mission succeeds.
+    pub fn perform_mission(
+        &mut self,
+        game: &Game,
+        mission: EspionageMission,
+    ) -> Result<(), CannotPerformMission> {
+        assert!(self.on_server);
+        self.can_perform_mission(game, mission)?;
+
+        let this = self.id();
+        let city = game.city_id_at_pos(self.pos).unwrap();
+        game.defer(move |game| espionage::perform_mission(game, this, city, mission));
+
+        Ok(())
+    }
+
+    /// Computes the gold cost to upgrade this unit to the given kind.
+    ///
+    /// Based on the difference in hammer cost between the two kinds.
+    pub fn upgrade_cost(&self, kind: &UnitKind) -> u32 {
+        UPGRADE_BASE_COST + UPGRADE_COST_PER_HAMMER * kind.cost.saturating_sub(self.kind.cost)
+    }
+
+    /// Returns whether the unit can be upgraded to the given kind.
+    pub fn can_upgrade_to(&self, game: &Game, kind: &UnitKind) -> Result<(), CannotUpgrade> {
+        if !game.registry().is_unit_upgrade(&self.kind, kind) {
+            return Err(CannotUpgrade::NotAnUpgrade);
+        }
+
+        let owner = game.player(self.owner);
+
+        for tech in &kind.techs {
+            if !owner.has_unlocked_tech(&game.registry().tech(tech).unwrap()) {
+                return Err(CannotUpgrade::MissingTechs);
+            }
+        }
+
+        if game.registry().is_unit_replaced_for_civ(kind, owner.civ())
+            || (!kind.only_for_civs.is_empty() && !kind.only_for_civs.contains(&owner.civ().id))
+        {
+            return Err(CannotUpgrade::NotAnUpgrade);
+        }
+
+        // Resources have to be available in one of our trade networks.
+        for resource in &kind.resources {
+            if !owner
+                .cities()
+                .iter()
+                .any(|&c| game.city(c).resources().any(|r| &r.id == resource))
+            {
+                return Err(CannotUpgrade::MissingResources);
+            }
+        }
+
+        if game.tile(self.pos).unwrap().owner(game) != Some(self.owner) {
+            return Err(CannotUpgrade::NotInOwnTerritory);
+        }
+
+        if !self.has_movement_left() {
+            return Err(CannotUpgrade::NoMovementLeft);
+        }
+
+        if owner.gold() < self.upgrade_cost(kind) {
+            return Err(CannotUpgrade::NotEnoughGold);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the unit kinds this unit can currently be upgraded to.
+    pub fn possible_upgrades(&self, game: &Game) -> Vec<Handle<UnitKind>> {
+        game.registry()
+            .unit_kinds()
+            .filter(|k| self.can_upgrade_to(game, k).is_ok())
+            .cloned()
+            .collect()
+    }
+
+    /// Upgrades the unit to a newer kind in its line, spending
+    /// gold from the owner's treasury.
+    ///
+    /// The unit keeps its position and health ratio, but uses
+    /// up its movement for the turn.
+    pub fn upgrade_to(&mut self, game: &Game, kind: Handle<UnitKind>) -> Result<(), CannotUpgrade> {
+        assert!(self.on_server);
+        self.can_upgrade_to(game, &kind)?;
+
+        let cost = self.upgrade_cost(&kind);
+        game.player_mut(self.owner).spend_gold(cost);
+        game.push_event(Event::PlayerChanged(self.owner));
+
+        log::info!(
+            "Upgrading {} to {} for {} gold",
+            self.kind.name,
+            kind.name,
+            cost
+        );
+
+        self.capabilities = capabilities_for_kind(&kind);
+        self.kind = kind;
+        self.movement_left = MovementPoints::from_u32(0);
+
+        game.push_event(Event::UnitChanged(self.id));
+
+        Ok(())
+    }
+
+    /// Returns the improvement the unit would destroy by pillaging its tile.
+    ///
+    /// Roads are only pillaged once nothing else is left.
+    pub fn can_pillage(&self, game: &Game) -> Result<Improvement, CannotPillage> {
+        if self.kind.strength == 0. {
+            return Err(CannotPillage::NotMilitary);
+        }
+
+        if !self.has_movement_left() {
+            return Err(CannotPillage::NoMovementLeft);
+        }
+
+        let tile = game.tile(self.pos).unwrap();
+        match tile.owner(game) {
+            Some(owner) if game.player(self.owner).is_at_war_with(owner) => {}
+            _ => return Err(CannotPillage::NotEnemyTerritory),
+        }
+
+        tile.improvements()
+            .min_by_key(|i| i.is_route())
+            .cloned()
+            .ok_or(CannotPillage::NoImprovement)
+    }
+
+    /// Destroys an enemy improvement on the unit's tile,
+    /// giving gold to the unit's owner.
+    ///
+    /// Uses one movement point.
+    pub fn pillage(&mut self, game: &Game) -> Result<(), CannotPillage> {
+        assert!(self.on_server);
+        let improvement = self.can_pillage(game)?;
+
+        game.tile_mut(self.pos)
+            .unwrap()
+            .remove_improvement(&improvement);
+        game.push_event(Event::TileChanged(self.pos));
+
+        let gold = improvement.pillage_gold();
+        game.player_mut(self.owner).add_gold(gold);
+        game.push_event(Event::PlayerChanged(self.owner));
+
+        log::info!(
+            "{} pillaged a {} for {} gold",
+            self.kind.name,
+            improvement.name(),
+            gold
+        );
+
+        self.movement_left = self
+            .movement_left
+            .saturating_sub(MovementPoints::from_u32(1));
+        game.push_event(Event::UnitChanged(self.id));
+
+        Ok(())
+    }
+
     /// Returns whether the unit has moved on the current turn.
     pub fn has_moved(&self) -> bool {
         self.movement_left.as_fixed_u32()
-            < MovementPoints::from_u32(self.kind.movement).as_fixed_u32()
+            < MovementPoints::from_u32(self.max_movement()).as_fixed_u32()
     }
 
     /// Returns whether the unit can move to the given adjacent
@@ -354,7 +875,7 @@ impl Unit {
     ///
     /// `target` must be adjacent to this unit's current position.
     pub fn can_move_to(&self, game: &Game, target: UVec2) -> bool {
-        if target.as_f32().distance_squared(self.pos().as_f32()) > 2. {
+        if game.map().tile_distance(target, self.pos()) > 1 {
             return false;
         }
 
@@ -367,6 +888,10 @@ impl Unit {
             return false;
         }
 
+        if self.stopped_by_zone_of_control {
+            return false;
+        }
+
         if !self.can_attack() && self.attack_target(game, target).is_some() {
             return false;
         }
@@ -396,13 +921,26 @@ impl Unit {
         }
 
         let old_pos = self.pos;
+
+        // Land units have to stop when moving within an enemy zone of control.
+        // Spies slip through unnoticed.
+        if !self.kind.ship
+            && !self.is_invisible()
+            && path::is_zone_of_control_move(game, &game.player(self.owner), old_pos, target, false)
+        {
+            self.stopped_by_zone_of_control = true;
+        }
+
         self.pos = target;
 
         // Spend the movement points
         let target_tile = game.tile(target).unwrap();
-        self.movement_left = self
-            .movement_left
-            .saturating_sub(target_tile.movement_cost(game, &*game.player(self.owner())));
+        self.movement_left = self.movement_left.saturating_sub(path::movement_cost(
+            game,
+            &game.player(self.owner),
+            old_pos,
+            target,
+        ));
 
         // Worker task is canceled
         if self.has_worker_task() {
@@ -414,16 +952,41 @@ impl Unit {
         let owner = self.owner;
         game.defer(move |game| game.player_mut(owner).update_visibility(game));
 
+        // Crossing our borders changes military supply costs
+        if self.on_server
+            && game.tile(old_pos).unwrap().owner(game) != target_tile.owner(game)
+            && self.kind.strength > 0.
+        {
+            game.defer(move |game| {
+                game.player_mut(owner).update_unit_supply_cost(game);
+                game.push_event(Event::PlayerChanged(owner));
+            });
+        }
+
         // If we moved into an enemy city, then the city is captured
         if let Some(city) = game.city_id_at_pos(target) {
             let mut city = game.city_mut(city);
-            if game.player(self.owner).is_at_war_with(city.owner()) {
+            if game.player(self.owner).is_at_war_with(city.owner()) && !self.is_invisible() {
                 city.transfer_control(game, self.owner);
             }
         }
 
         game.push_event(Event::UnitMoved(self.id, old_pos, target));
 
+        // The first unit to enter a tribal village claims its reward.
+        // Barbarians leave villages alone.
+        if self.on_server
+            && target_tile.has_tribal_village()
+            && !game.player(self.owner).is_barbarian()
+        {
+            game.defer(move |game| tribal_village::enter(game, owner, target));
+        }
+
+        // Enemy sentries that can see the new position wake up
+        if self.on_server {
+            game.defer(move |game| game.wake_enemies_of(owner));
+        }
+
         UnitMoveOutcome::Success
     }
 
@@ -468,10 +1031,11 @@ impl Unit {
         }
 
         if let Some(task) = self.worker_task() {
+            let speed_bonus = game.player(self.owner).worker_speed_bonus();
             let mut progress_grid = game.worker_progress_grid_mut();
-            progress_grid.add_progress_to(self.pos, task);
-            if progress_grid.is_task_completed(self.pos, task) {
-                task.complete(game, self.pos);
+            progress_grid.add_progress_to(self.pos, task, speed_bonus);
+            if progress_grid.is_task_completed(self.pos, task, game.speed()) {
+                task.complete(game, self.pos, self.owner);
                 self.set_worker_task(None);
             }
         }
@@ -485,8 +1049,9 @@ impl Unit {
     }
 
     fn reset_movement(&mut self) {
-        self.movement_left = MovementPoints::from_u32(self.kind.movement);
+        self.movement_left = MovementPoints::from_u32(self.max_movement());
         self.has_used_attack = false;
+        self.stopped_by_zone_of_control = false;
     }
 
     fn heal(&mut self, game: &Game) {
@@ -503,7 +1068,7 @@ impl Unit {
 
         let tile = game.tile(self.pos()).unwrap();
         let tile_owner = tile.owner(game).map(|t| game.player(t));
-        let rate = if game.city_at_pos(self.pos()).is_some() {
+        let base_rate = if game.city_at_pos(self.pos()).is_some() {
             0.2
         } else {
             match tile_owner {
@@ -519,6 +1084,8 @@ impl Unit {
                 None => 0.1,
             }
         };
+        let rate = base_rate
+            + self.promotion_effect(PromotionEffectType::HealingPercent, None) as f64 / 100.;
 
         self.set_health(self.health + rate);
         assert!(self.health <= 1.);
@@ -548,6 +1115,20 @@ impl Unit {
     pub fn skip_turn(&mut self) {
         self.is_skipping_turn = true;
     }
+
+    pub fn sentry(&mut self) {
+        self.is_sentry = true;
+    }
+
+    pub fn alert(&mut self) {
+        self.is_on_alert = true;
+    }
+
+    /// Wakes a sentry or alert unit so its owner can give it new orders.
+    pub fn wake(&mut self) {
+        self.is_sentry = false;
+        self.is_on_alert = false;
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -560,6 +1141,98 @@ pub enum CannotFoundCity {
     InOpponentLand,
 }
 
+#[derive(Debug, thiserror::Error)]
+pub enum CannotSpreadReligion {
+    #[error("this unit is not a missionary")]
+    MissingCapability,
+    #[error("the unit has no movement left")]
+    NoMovementLeft,
+    #[error("the unit is not in a city")]
+    NotInCity,
+    #[error("religions cannot be spread to enemy cities")]
+    EnemyCity,
+    #[error("the unit's owner has no state religion")]
+    NoStateReligion,
+    #[error("the city already has the religion")]
+    AlreadyPresent,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotPerformMission {
+    #[error("this unit is not a spy")]
+    MissingCapability,
+    #[error("the unit has no movement left")]
+    NoMovementLeft,
+    #[error("the unit is not in a city")]
+    NotInCity,
+    #[error("missions cannot be performed in our own cities")]
+    OwnCity,
+    #[error("not enough espionage points (need {0})")]
+    NotEnoughPoints(u32),
+    #[error("the city's owner knows no techs we lack")]
+    NothingToSteal,
+    #[error("the city has no production to sabotage")]
+    NoProduction,
+    #[error("the city is already suffering from this mission")]
+    AlreadyAffected,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotPromote {
+    #[error("the unit already has this promotion")]
+    AlreadyHasPromotion,
+    #[error("this promotion is not available for the unit's category")]
+    WrongUnitCategory,
+    #[error("the unit is missing a prerequisite promotion")]
+    MissingPrerequisites,
+    #[error("the unit does not have enough experience")]
+    NotEnoughExperience,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotPillage {
+    #[error("only military units can pillage")]
+    NotMilitary,
+    #[error("the unit has no movement left")]
+    NoMovementLeft,
+    #[error("the tile does not belong to an enemy")]
+    NotEnemyTerritory,
+    #[error("there is no improvement to pillage")]
+    NoImprovement,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CannotUpgrade {
+    #[error("this unit cannot be upgraded to that unit kind")]
+    NotAnUpgrade,
+    #[error("missing the techs required for the upgrade")]
+    MissingTechs,
+    #[error("missing the resources required for the upgrade")]
+    MissingResources,
+    #[error("units can only be upgraded in their owner's territory")]
+    NotInOwnTerritory,
+    #[error("the unit has no movement left")]
+    NoMovementLeft,
+    #[error("not enough gold")]
+    NotEnoughGold,
+}
+
+fn capabilities_for_kind(kind: &UnitKind) -> Vec<Capability> {
+    kind.capabilities
+        .iter()
+        .map(|ty| match ty {
+            CapabilityType::FoundCity => Capability::FoundCity,
+            CapabilityType::DoWork => Capability::Worker(WorkerCapability { current_task: None }),
+            CapabilityType::CarryUnits => todo!(),
+            CapabilityType::BombardCityDefenses => Capability::BombardCity {
+                max_per_turn: kind.max_bombard_per_turn,
+            },
+            CapabilityType::SpreadReligion => Capability::SpreadReligion,
+            CapabilityType::Espionage => Capability::Espionage,
+        })
+        .collect()
+}
+
 fn float_options() -> WriteFloatOptions {
     WriteFloatOptions::builder()
         .trim_floats(true)
@@ -648,6 +1321,28 @@ pub enum Capability {
     FoundCity,
     BombardCity { max_per_turn: u32 },
     Worker(WorkerCapability),
+    SpreadReligion,
+    Espionage,
+}
+
+impl Capability {
+    fn from_legacy(
+        legacy: legacy::Capability,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        Ok(match legacy {
+            legacy::Capability::FoundCity => Capability::FoundCity,
+            legacy::Capability::BombardCity { max_per_turn } => {
+                Capability::BombardCity { max_per_turn }
+            }
+            legacy::Capability::Worker(worker) => Capability::Worker(WorkerCapability {
+                current_task: worker
+                    .current_task
+                    .map(|task| WorkerTask::from_legacy(task, registry))
+                    .transpose()?,
+            }),
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -660,3 +1355,199 @@ impl WorkerCapability {
         self.current_task.as_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn upgrades_follow_replaces_chain() {
+        let registry = testing::registry();
+        let warrior = registry.unit_kind("warrior").unwrap();
+        let axeman = registry.unit_kind("axeman").unwrap();
+        let maceman = registry.unit_kind("maceman").unwrap();
+
+        assert!(registry.is_unit_upgrade(&warrior, &axeman));
+        assert!(registry.is_unit_upgrade(&warrior, &maceman));
+        assert!(!registry.is_unit_upgrade(&axeman, &warrior));
+        assert!(!registry.is_unit_upgrade(&warrior, &warrior));
+    }
+
+    #[test]
+    fn promotions_require_experience_and_prerequisites() {
+        let mut game = testing::new_game(4, 4);
+        let player = testing::add_player(&mut game, "rome");
+        let warrior = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+
+        let registry = testing::registry();
+        let combat1 = registry.promotion("combat1").unwrap();
+        let combat2 = registry.promotion("combat2").unwrap();
+        let mobility = registry.promotion("mobility").unwrap();
+
+        let mut unit = game.unit_mut(warrior);
+        assert!(matches!(
+            unit.can_promote(&combat1),
+            Err(CannotPromote::NotEnoughExperience)
+        ));
+        assert!(matches!(
+            unit.can_promote(&combat2),
+            Err(CannotPromote::MissingPrerequisites)
+        ));
+        assert!(matches!(
+            unit.can_promote(&mobility),
+            Err(CannotPromote::WrongUnitCategory)
+        ));
+
+        let needed = unit.experience_needed_for_promotion();
+        unit.add_experience(needed);
+        let base_strength = unit.modified_attacking_strength();
+        unit.promote(&game, combat1.clone()).unwrap();
+
+        assert_eq!(unit.level(), 2);
+        assert_eq!(unit.experience_needed_for_promotion(), 5);
+        assert!(matches!(
+            unit.can_promote(&combat1),
+            Err(CannotPromote::AlreadyHasPromotion)
+        ));
+        assert!((unit.modified_attacking_strength() - base_strength * 1.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn upgrade_requires_territory_and_gold() {
+        let mut game = testing::new_game(10, 10);
+        let player = testing::add_player(&mut game, "rome");
+        testing::add_city(&mut game, player, uvec2(2, 2));
+        let archer = testing::add_unit(&mut game, player, "archer", uvec2(2, 3));
+        let far_archer = testing::add_unit(&mut game, player, "archer", uvec2(8, 8));
+
+        let registry = testing::registry();
+        for tech in ["Archery", "Feudalism"] {
+            game.player_mut(player)
+                .grant_tech(registry.tech(tech).unwrap());
+        }
+        let longbowman = registry.unit_kind("longbowman").unwrap();
+
+        let cost = game.unit(archer).upgrade_cost(&longbowman);
+        assert_eq!(cost, UPGRADE_BASE_COST + UPGRADE_COST_PER_HAMMER * 25);
+        assert!(matches!(
+            game.unit(archer).can_upgrade_to(&game, &longbowman),
+            Err(CannotUpgrade::NotEnoughGold)
+        ));
+
+        game.player_mut(player).add_gold(cost);
+        assert!(matches!(
+            game.unit(far_archer).can_upgrade_to(&game, &longbowman),
+            Err(CannotUpgrade::NotInOwnTerritory)
+        ));
+
+        game.unit_mut(archer)
+            .upgrade_to(&game, longbowman.clone())
+            .unwrap();
+        let unit = game.unit(archer);
+        assert_eq!(unit.kind(), &longbowman);
+        assert!(!unit.has_movement_left());
+        assert_eq!(game.player(player).gold(), 0);
+    }
+
+    #[test]
+    fn sentries_wake_when_enemies_come_into_view() {
+        let mut game = testing::new_game(8, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let other = testing::add_player(&mut game, "greece");
+        let sentry = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+        let stranger = testing::add_unit(&mut game, other, "warrior", uvec2(4, 1));
+        game.unit_mut(sentry).sentry();
+
+        let approach = |game: &mut Game, pos| {
+            game.unit_mut(stranger).move_to(game, pos);
+            game.run_deferred_functions();
+        };
+
+        // Units we're at peace with are ignored.
+        approach(&mut game, uvec2(3, 1));
+        game.unit_mut(stranger)
+            .set_movement_left_unsafe(MovementPoints::from_u32(1));
+        approach(&mut game, uvec2(2, 1));
+        assert!(game.unit(sentry).is_watching_for_enemies());
+
+        game.player_mut(player).declare_war_on(&game, other);
+        game.unit_mut(stranger)
+            .set_movement_left_unsafe(MovementPoints::from_u32(1));
+        approach(&mut game, uvec2(2, 2));
+        assert!(!game.unit(sentry).is_watching_for_enemies());
+    }
+
+    #[test]
+    fn sentries_wake_on_war_and_new_enemy_units() {
+        let mut game = testing::new_game(8, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let other = testing::add_player(&mut game, "greece");
+        let sentry = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+        let stranger = testing::add_unit(&mut game, other, "warrior", uvec2(2, 1));
+        game.unit_mut(sentry).sentry();
+        game.run_deferred_functions();
+        assert!(game.unit(sentry).is_watching_for_enemies());
+
+        // Declaring war wakes sentries that already see the enemy.
+        game.player_mut(other).declare_war_on(&game, player);
+        game.run_deferred_functions();
+        assert!(!game.unit(sentry).is_watching_for_enemies());
+
+        // So do enemy units that appear in view.
+        game.remove_unit(stranger);
+        game.unit_mut(sentry).sentry();
+        game.run_deferred_functions();
+        assert!(game.unit(sentry).is_watching_for_enemies());
+        testing::add_unit(&mut game, other, "warrior", uvec2(0, 0));
+        game.run_deferred_functions();
+        assert!(!game.unit(sentry).is_watching_for_enemies());
+    }
+
+    #[test]
+    fn spies_do_not_wake_sentries() {
+        let mut game = testing::new_game(8, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let other = testing::add_player(&mut game, "greece");
+        let sentry = testing::add_unit(&mut game, player, "warrior", uvec2(1, 1));
+        let spy = testing::add_unit(&mut game, other, "spy", uvec2(3, 1));
+        game.unit_mut(sentry).sentry();
+        game.player_mut(player).declare_war_on(&game, other);
+
+        game.unit_mut(spy).move_to(&game, uvec2(2, 1));
+        game.run_deferred_functions();
+        assert_eq!(game.unit(spy).pos(), uvec2(2, 1));
+        assert!(game.unit(sentry).is_watching_for_enemies());
+    }
+
+    #[test]
+    fn pillaging_destroys_enemy_improvements_before_roads() {
+        let mut game = testing::new_game(6, 6);
+        let player = testing::add_player(&mut game, "rome");
+        let enemy = testing::add_player(&mut game, "greece");
+        testing::add_city(&mut game, enemy, uvec2(3, 3));
+
+        let pos = uvec2(3, 2);
+        let registry = testing::registry();
+        for kind in ["farm", "road"] {
+            let improvement = Improvement::new(registry.improvement(kind).unwrap());
+            game.tile_mut(pos).unwrap().add_improvement(improvement);
+        }
+
+        let unit = testing::add_unit(&mut game, player, "warrior", pos);
+        assert!(matches!(
+            game.unit(unit).can_pillage(&game),
+            Err(CannotPillage::NotEnemyTerritory)
+        ));
+
+        game.player_mut(player).declare_war_on(&game, enemy);
+        game.unit_mut(unit).pillage(&game).unwrap();
+        let tile = game.tile(pos).unwrap();
+        assert!(tile.improvements().all(|i| i.is_route()));
+        assert_eq!(game.player(player).gold(), 10);
+        assert!(!game.unit(unit).has_movement_left());
+    }
+}
@@ -7,14 +7,30 @@
 //! Each turn spent by a worker contributes to the building of that improvement,
 //! so using 2 workers on one tile will result in half the build time.
 //!
-//! For each tile and each possible worker task, we store the number of worker turns
-//! spent. When that number reaches the required number of worker turns, the task is completed.
+//! For each tile and each possible worker task, we store the worker turns spent,
+//! in hundredths of a turn. A worker normally contributes a full turn of progress,
+//! but techs can speed workers up. When the progress reaches the required number
+//! of worker turns, the task is completed.
 
 use ahash::AHashMap;
 use glam::UVec2;
 use serde::{Deserialize, Serialize};
 
-use crate::{event::Event, Game, Grid, Improvement, Player, Tile};
+use crate::{
+    event::Event,
+    registry::{Registry, RegistryItemNotFound},
+    saveload::legacy,
+    Game, GameSpeed, Grid, Improvement, Player, PlayerId, Tile,
+};
+
+/// Hammers given to the nearest city when a forest is chopped.
+pub const CHOP_FOREST_HAMMERS: u32 = 20;
+/// Tech needed to chop forests.
+const CHOP_FOREST_TECH: &str = "Bronze Working";
+const CHOP_FOREST_WORKER_TURNS: u32 = 3;
+const REMOVE_IMPROVEMENT_WORKER_TURNS: u32 = 1;
+/// Progress contributed by one worker in one turn, without speed bonuses.
+const PROGRESS_PER_TURN: u32 = 100;
 
 /// Stores worker turn progress for each pair of (tile, worker task).
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,14 +45,34 @@ impl WorkerProgressGrid {
         }
     }
 
-    pub fn add_progress_to(&mut self, pos: UVec2, task: &WorkerTask) {
+    /// Converts worker progress from an unversioned save file,
+    /// which stored whole turns.
+    pub(crate) fn from_legacy(
+        legacy: legacy::WorkerProgressGrid,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let progress = Grid::from_legacy(legacy.progress).try_map(|tasks| {
+            tasks
+                .into_iter()
+                .map(|(task, turns)| {
+                    let task = WorkerTask::from_legacy(task, registry)?;
+                    Ok((task, turns * PROGRESS_PER_TURN))
+                })
+                .collect()
+        })?;
+        Ok(Self { progress })
+    }
+
+    /// Adds a turn of progress from a worker with the given
+    /// percent speed bonus.
+    pub fn add_progress_to(&mut self, pos: UVec2, task: &WorkerTask, speed_bonus: u32) {
         let map = self.progress.get_mut(pos).unwrap();
         let progress = map.entry(task.clone()).or_insert(0);
-        *progress += 1;
+        *progress += PROGRESS_PER_TURN * (100 + speed_bonus) / 100;
     }
 
-    pub fn is_task_completed(&self, pos: UVec2, task: &WorkerTask) -> bool {
-        self.progress_for(pos, task) >= task.worker_turns_to_build()
+    pub fn is_task_completed(&self, pos: UVec2, task: &WorkerTask, speed: GameSpeed) -> bool {
+        self.progress_for(pos, task) >= task.worker_turns_to_build(speed) * PROGRESS_PER_TURN
     }
 
     pub fn progress_for(&self, pos: UVec2, task: &WorkerTask) -> u32 {
@@ -51,17 +87,19 @@ impl WorkerProgressGrid {
     /// Predicts the number of remaining turns to complete the given task.
     pub fn predict_remaining_turns_for(&self, game: &Game, pos: UVec2, task: &WorkerTask) -> u32 {
         // TODO (perf): don't do a linear search on every unit to find workers on this tile.
-        let num_workers = game
+        let progress_per_turn: u32 = game
             .units()
             .filter(|u| u.pos() == pos && u.worker_task() == Some(task))
-            .count() as u32;
-        if num_workers == 0 {
+            .map(|u| PROGRESS_PER_TURN * (100 + game.player(u.owner()).worker_speed_bonus()) / 100)
+            .sum();
+        if progress_per_turn == 0 {
             return u32::MAX;
         }
         let progress = self.progress_for(pos, task);
-        let remaining_turns_needed = task.worker_turns_to_build().saturating_sub(progress);
+        let remaining_progress_needed =
+            (task.worker_turns_to_build(game.speed()) * PROGRESS_PER_TURN).saturating_sub(progress);
 
-        (remaining_turns_needed + num_workers - 1) / num_workers
+        (remaining_progress_needed + progress_per_turn - 1) / progress_per_turn
     }
 }
 
@@ -69,46 +107,144 @@ impl WorkerProgressGrid {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum WorkerTask {
     BuildImprovement(Improvement),
+    /// Cuts down the forest, giving hammers to the builder's nearest city.
+    ChopForest,
+    RemoveImprovement(Improvement),
 }
 
 impl WorkerTask {
+    /// Converts a worker task from an unversioned save file.
+    pub(crate) fn from_legacy(
+        legacy: legacy::WorkerTask,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        match legacy {
+            legacy::WorkerTask::BuildImprovement(improvement) => Ok(WorkerTask::BuildImprovement(
+                Improvement::from_legacy(improvement, registry)?,
+            )),
+        }
+    }
+
     pub fn name(&self) -> String {
         match self {
-            WorkerTask::BuildImprovement(i) => i.name(),
+            WorkerTask::BuildImprovement(i) => i.name().to_owned(),
+            WorkerTask::ChopForest => "Chop Forest".to_owned(),
+            WorkerTask::RemoveImprovement(i) => format!("Remove {}", i.name()),
         }
     }
 
     pub fn present_participle(&self) -> String {
         match self {
             WorkerTask::BuildImprovement(i) => format!("Building {}", i.name()),
+            WorkerTask::ChopForest => "Chopping Forest".to_owned(),
+            WorkerTask::RemoveImprovement(i) => format!("Removing {}", i.name()),
         }
     }
 
-    pub fn worker_turns_to_build(&self) -> u32 {
-        match self {
+    /// Gets the number of worker turns needed to complete the task
+    /// in a game played at the given speed.
+    pub fn worker_turns_to_build(&self, speed: GameSpeed) -> u32 {
+        let turns = match self {
             WorkerTask::BuildImprovement(i) => i.worker_turns_to_build(),
-        }
+            WorkerTask::ChopForest => CHOP_FOREST_WORKER_TURNS,
+            WorkerTask::RemoveImprovement(_) => REMOVE_IMPROVEMENT_WORKER_TURNS,
+        };
+        speed.scale(turns).max(1)
     }
 
-    /// Completes the worker task.
-    pub fn complete(&self, game: &Game, pos: UVec2) {
+    /// Completes the worker task for a worker owned by `builder`.
+    pub fn complete(&self, game: &Game, pos: UVec2, builder: PlayerId) {
         match self {
             WorkerTask::BuildImprovement(improvement) => {
                 game.tile_mut(pos)
                     .unwrap()
                     .add_improvement(improvement.clone());
-                game.push_event(Event::TileChanged(pos));
+            }
+            WorkerTask::ChopForest => {
+                game.tile_mut(pos).unwrap().set_forested(false);
+
+                let nearest_city = game
+                    .player(builder)
+                    .cities()
+                    .iter()
+                    .copied()
+                    .min_by_key(|&c| game.map().distance_squared(game.city(c).pos(), pos));
+                if let Some(city) = nearest_city {
+                    game.city_mut(city).add_bonus_hammers(CHOP_FOREST_HAMMERS);
+                    game.push_event(Event::CityChanged(city));
+                }
+            }
+            WorkerTask::RemoveImprovement(improvement) => {
+                game.tile_mut(pos).unwrap().remove_improvement(improvement);
             }
         }
+        game.push_event(Event::TileChanged(pos));
     }
 
     pub fn possible_for_tile(game: &Game, tile: &Tile, pos: UVec2, builder: &Player) -> Vec<Self> {
         if game.city_at_pos(pos).is_some() {
             return Vec::new(); // can't build over a city
         }
-        Improvement::possible_for_tile(game, tile, builder)
+        let mut tasks: Vec<Self> = Improvement::possible_for_tile(game, tile, builder)
             .into_iter()
             .map(WorkerTask::BuildImprovement)
-            .collect()
+            .collect();
+
+        // Only our own or unclaimed land can be cleared.
+        let owner = tile.owner(game);
+        if owner.is_some() && owner != Some(builder.id()) {
+            return tasks;
+        }
+
+        if tile.is_forested()
+            && builder.has_unlocked_tech(&game.registry().tech(CHOP_FOREST_TECH).unwrap())
+        {
+            tasks.push(WorkerTask::ChopForest);
+        }
+
+        tasks.extend(
+            tile.improvements()
+                .cloned()
+                .map(WorkerTask::RemoveImprovement),
+        );
+
+        tasks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::testing;
+
+    use super::*;
+
+    #[test]
+    fn chopping_forest_gives_hammers_to_the_nearest_city() {
+        let mut game = testing::new_game(8, 3);
+        let player = testing::add_player(&mut game, "rome");
+        let near = testing::add_city(&mut game, player, uvec2(1, 1));
+        let far = testing::add_city(&mut game, player, uvec2(6, 1));
+        let pos = uvec2(3, 1);
+        game.tile_mut(pos).unwrap().set_forested(true);
+
+        let can_chop = |game: &Game| {
+            let tile = game.tile(pos).unwrap();
+            WorkerTask::possible_for_tile(game, &tile, pos, &game.player(player))
+                .contains(&WorkerTask::ChopForest)
+        };
+        assert!(!can_chop(&game));
+        let bronze_working = game.registry().tech(CHOP_FOREST_TECH).unwrap();
+        game.player_mut(player).grant_tech(bronze_working);
+        assert!(can_chop(&game));
+
+        WorkerTask::ChopForest.complete(&game, pos, player);
+        assert!(!game.tile(pos).unwrap().is_forested());
+        assert_eq!(
+            game.city(near).economy().overflow_hammers,
+            CHOP_FOREST_HAMMERS
+        );
+        assert_eq!(game.city(far).economy().overflow_hammers, 0);
     }
 }
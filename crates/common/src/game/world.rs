@@ -5,15 +5,16 @@ use std::{
 };
 
 use ahash::AHashMap;
-use glam::{uvec2, UVec2};
+use glam::{ivec2, uvec2, UVec2};
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64Mcg;
 use slotmap::{SecondaryMap, SlotMap};
 
 use super::{CityId, PlayerId, UnitId};
 use crate::{
-    event::Event, lobby::GameLobby, registry::Registry, river::Rivers, saveload::SaveFile,
-    tile::OutOfBounds, worker::WorkerProgressGrid, City, Grid, Player, Tile, Turn, Unit,
+    event::Event, lobby::GameLobby, order, registry::Registry, religion, river::Rivers,
+    saveload::SaveFile, tile::OutOfBounds, worker::WorkerProgressGrid, City, GameSpeed, Grid,
+    Player, Tile, Turn, Unit,
 };
 
 /// Stores the entire game state.
@@ -44,6 +45,7 @@ pub struct Game {
     rng: RefCell<Pcg64Mcg>,
 
     turn: Turn,
+    speed: GameSpeed,
 
     events: RefCell<Vec<Event>>,
 
@@ -80,6 +82,7 @@ impl Game {
             rng: RefCell::new(Pcg64Mcg::from_entropy()),
 
             turn: Turn::new(0),
+            speed: GameSpeed::Normal,
 
             events: RefCell::new(Vec::new()),
 
@@ -105,6 +108,7 @@ impl Game {
             worker_progress: RefCell::new(file.worker_progress),
             rng: RefCell::new(Pcg64Mcg::from_entropy()), // TODO: should RNG state persist?
             turn: file.turn,
+            speed: file.speed,
             events: RefCell::new(Vec::new()),
             deferred: RefCell::new(Vec::new()),
             lobby: file.lobby,
@@ -149,6 +153,7 @@ impl Game {
                 .collect(),
             worker_progress: self.worker_progress.borrow().clone(),
             turn: self.turn,
+            speed: self.speed,
             lobby: self.lobby.clone(),
         }
     }
@@ -293,15 +298,114 @@ impl Game {
             .map(|id| self.unit(id))
     }
 
+    /// Gets the units at the given position, skipping units that
+    /// are currently mutably borrowed (such as a unit that is moving).
+    pub fn other_units_by_pos(&self, pos: UVec2) -> impl Iterator<Item = Ref<Unit>> + '_ {
+        self.units_by_pos
+            .get(&pos)
+            .map(|v| v.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|&id| self.units[id].try_borrow().ok())
+    }
+
     /// Adds a new unit with an existing ID.
     pub fn add_unit(&mut self, unit: Unit) {
         let id = unit.id();
         let owner = unit.owner();
+        let on_server = unit.is_on_server();
         self.units_by_pos.entry(unit.pos()).or_default().push(id);
         self.units.insert(id, RefCell::new(unit));
 
         self.player_mut(owner).register_unit(id);
         self.push_event(Event::UnitChanged(id));
+
+        // Enemy sentries that can see the new unit wake up
+        if on_server {
+            self.defer(move |game| game.wake_enemies_of(owner));
+        }
+    }
+
+    /// Updates the index of units by position after
+    /// a unit moved from `old_pos` to `new_pos`.
+    ///
+    /// Needed on the client, where units are moved with [`Unit::set_pos_unsafe`].
+    pub fn update_unit_pos_index(&mut self, unit: UnitId, old_pos: UVec2, new_pos: UVec2) {
+        if let Some(v) = self.units_by_pos.get_mut(&old_pos) {
+            v.retain(|id| *id != unit);
+        }
+        self.units_by_pos.entry(new_pos).or_default().push(unit);
+    }
+
+    /// Wakes the sentry and alert units of `player` that
+    /// can see a unit of a player they're at war with.
+    ///
+    /// Invisible units such as spies wake no one.
+    pub fn wake_units_watching(&mut self, player: PlayerId) {
+        if !self.is_player_valid(player) {
+            return;
+        }
+
+        let watchers: Vec<UnitId> = {
+            let player = self.player(player);
+            player
+                .units()
+                .iter()
+                .map(|&id| self.unit(id))
+                .filter(|w| {
+                    w.is_watching_for_enemies()
+                        && self.tiles_visible_from(w.pos()).into_iter().any(|pos| {
+                            self.units_by_pos(pos).any(|u| {
+                                player.is_at_war_with(u.owner()) && !u.is_hidden_from(player.id())
+                            })
+                        })
+                })
+                .map(|w| w.id())
+                .collect()
+        };
+
+        for watcher in watchers {
+            self.unit_mut(watcher).wake();
+            self.push_event(Event::UnitChanged(watcher));
+            self.push_event(Event::UnitWoken(watcher));
+        }
+    }
+
+    /// Wakes the watching units of every player at war with `player`.
+    pub fn wake_enemies_of(&mut self, player: PlayerId) {
+        let enemies: Vec<PlayerId> = self
+            .players()
+            .filter(|p| p.is_at_war_with(player))
+            .map(|p| p.id())
+            .collect();
+        for enemy in enemies {
+            self.wake_units_watching(enemy);
+        }
+    }
+
+    /// Gets the tiles revealed to the owner of a unit
+    /// or a tile at `pos`.
+    ///
+    /// Hills without forest extend the sight range to two tiles.
+    pub fn tiles_visible_from(&self, pos: UVec2) -> Vec<UVec2> {
+        let tile = self.tile(pos).unwrap();
+        let distance = if tile.is_hilled() && !tile.is_forested() {
+            2
+        } else {
+            1
+        };
+
+        let mut tiles = Vec::new();
+        for dx in -distance..=distance {
+            for dy in -distance..=distance {
+                if let Some(p) = self.map.wrap(pos.as_i32() + ivec2(dx, dy)) {
+                    if !tiles.contains(&p) {
+                        tiles.push(p);
+                    }
+                }
+            }
+        }
+        tiles
     }
 
     /// Gets the tile map.
@@ -349,6 +453,14 @@ impl Game {
         self.turn = turn;
     }
 
+    pub fn speed(&self) -> GameSpeed {
+        self.speed
+    }
+
+    pub fn set_speed(&mut self, speed: GameSpeed) {
+        self.speed = speed;
+    }
+
     pub fn tile_worker(&self, pos: UVec2) -> Option<CityId> {
         self.tile(pos).unwrap().worked_by_city()
     }
@@ -371,6 +483,8 @@ impl Game {
             city.borrow_mut().on_turn_end(self);
         }
 
+        religion::spread_religions(self);
+
         for player in self.players.values() {
             player.borrow_mut().on_turn_end(self);
         }
@@ -386,18 +500,20 @@ impl Game {
         self.turn.increment();
     }
 
+    /// Carries out the standing orders of all units. (Server only.)
+    ///
+    /// Should be called at the start of each turn.
+    pub fn execute_unit_orders(&mut self) {
+        let units: Vec<UnitId> = self.units.keys().collect();
+        order::execute_unit_orders(self, &units);
+    }
+
     pub fn push_event(&self, event: Event) {
         if let Event::UnitMoved(unit, old_pos, new_pos) = &event {
             let unit = *unit;
             let old_pos = *old_pos;
             let new_pos = *new_pos;
-            self.defer(move |game| {
-                game.units_by_pos
-                    .get_mut(&old_pos)
-                    .unwrap()
-                    .retain(|id| *id != unit);
-                game.units_by_pos.entry(new_pos).or_default().push(unit);
-            });
+            self.defer(move |game| game.update_unit_pos_index(unit, old_pos, new_pos));
         }
 
         self.events.borrow_mut().push(event);
@@ -18,14 +18,16 @@ pub mod poisson;
 pub mod protocol;
 pub mod registry;
 pub mod saveload;
+#[cfg(test)]
+mod testing;
 pub mod types;
 pub mod utils;
 
 pub use game::{
     culture::CultureLevel,
-    improvement::{Cottage, CottageLevel, Improvement},
+    improvement::Improvement,
     tile::{Grid, Terrain},
     world::Game,
     *,
 };
-pub use types::{Era, Turn, Visibility, Year, Yield};
+pub use types::{Era, GameSpeed, Turn, Visibility, Year, Yield};
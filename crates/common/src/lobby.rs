@@ -1,3 +1,4 @@
+use ahash::AHashMap;
 use serde::{Deserialize, Serialize};
 use slotmap::SlotMap;
 use uuid::Uuid;
@@ -5,6 +6,7 @@ use uuid::Uuid;
 use crate::{
     assets::Handle,
     registry::{Civilization, Leader},
+    saveload::legacy,
 };
 
 slotmap::new_key_type! {
@@ -23,6 +25,39 @@ impl GameLobby {
         Self::default()
     }
 
+    /// Converts a lobby from an unversioned save file.
+    ///
+    /// Slots are given new IDs. Returns the lobby and a map
+    /// from the old slot IDs to the new ones.
+    pub(crate) fn from_legacy(legacy: legacy::GameLobby) -> (Self, AHashMap<SlotId, SlotId>) {
+        let mut lobby = Self::new();
+        let mut slot_ids = AHashMap::new();
+        for (old_id, slot) in legacy.slots {
+            let player = match slot.player {
+                legacy::SlotPlayer::Empty { player_uuid } => SlotPlayer::Empty { player_uuid },
+                legacy::SlotPlayer::Human {
+                    player_uuid,
+                    username,
+                    civ,
+                    leader,
+                    is_admin,
+                } => SlotPlayer::Human {
+                    player_uuid,
+                    username,
+                    leader: Leader::from_legacy(&civ, leader),
+                    civ,
+                    is_admin,
+                },
+                legacy::SlotPlayer::Ai { civ, leader } => SlotPlayer::Ai {
+                    leader: Leader::from_legacy(&civ, leader),
+                    civ,
+                },
+            };
+            slot_ids.insert(old_id, lobby.add_slot(LobbySlot { player }));
+        }
+        (lobby, slot_ids)
+    }
+
     pub fn add_slot(&mut self, slot: LobbySlot) -> SlotId {
         self.slots.insert(slot)
     }
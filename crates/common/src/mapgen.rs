@@ -4,6 +4,8 @@ use glam::{uvec2, UVec2};
 use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
+use crate::GameSpeed;
+
 /// Settings provided to the map generator.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapgenSettings {
@@ -12,6 +14,18 @@ pub struct MapgenSettings {
 
     /// Number of tiles along each axis.
     pub size: MapSize,
+
+    /// Whether the game has barbarians.
+    pub barbarians: bool,
+
+    /// ID of the [`Handicap`](crate::registry::Handicap) all players start with.
+    pub difficulty: String,
+
+    /// How fast the game progresses.
+    pub speed: GameSpeed,
+
+    /// Whether the map wraps around from east to west.
+    pub wrap_x: bool,
 }
 
 impl Default for MapgenSettings {
@@ -21,6 +35,10 @@ impl Default for MapgenSettings {
                 num_continents: NumContinents::Two,
             }),
             size: MapSize::Normal,
+            barbarians: true,
+            difficulty: "noble".to_owned(),
+            speed: GameSpeed::Normal,
+            wrap_x: false,
         }
     }
 }
@@ -31,6 +49,13 @@ pub enum LandGeneratorSettings {
     Flat(FlatSettings),
     /// A map consisting of one or more continents separated by ocean.
     Continents(ContinentsSettings),
+    /// A map consisting of many small islands.
+    Archipelago(ArchipelagoSettings),
+    /// A map consisting of a single large continent, optionally
+    /// containing inland seas.
+    Pangaea(PangaeaSettings),
+    /// A map with coastlines shaped by fractal noise.
+    Fractal(FractalSettings),
 }
 
 impl Display for LandGeneratorSettings {
@@ -44,6 +69,21 @@ impl Display for LandGeneratorSettings {
             LandGeneratorSettings::Continents(_) => {
                 write!(f, "Continents")
             }
+            LandGeneratorSettings::Archipelago(_) => {
+                write!(f, "Archipelago")
+            }
+            LandGeneratorSettings::Pangaea(settings) => write!(
+                f,
+                "Pangaea - {}",
+                if settings.inland_seas {
+                    "Inland Seas"
+                } else {
+                    "No Inland Seas"
+                }
+            ),
+            LandGeneratorSettings::Fractal(settings) => {
+                write!(f, "Fractal - {}% Land", settings.land_percent as u32)
+            }
         }
     }
 }
@@ -60,6 +100,31 @@ pub struct ContinentsSettings {
     pub num_continents: NumContinents,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchipelagoSettings {}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PangaeaSettings {
+    /// Whether to carve inland seas out of the continent.
+    pub inland_seas: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FractalSettings {
+    /// Approximate fraction of the map covered by land.
+    pub land_percent: LandPercent,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum LandPercent {
+    Twenty = 20,
+    Thirty = 30,
+    Forty = 40,
+    Fifty = 50,
+    Sixty = 60,
+    Seventy = 70,
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
 pub enum NumContinents {
     One = 1,
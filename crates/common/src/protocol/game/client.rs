@@ -4,7 +4,13 @@ use glam::UVec2;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    assets::Handle, city::BuildTask, player::EconomySettings, registry::Tech, worker::WorkerTask,
+    assets::Handle,
+    city::{BuildTask, Specialist},
+    espionage::EspionageMission,
+    governor::GovernorSettings,
+    player::EconomySettings,
+    registry::{Civic, Promotion, Religion, Tech, UnitKind},
+    worker::WorkerTask,
     CityId, PlayerId, UnitId,
 };
 
@@ -19,11 +25,24 @@ pub struct ClientGamePacket {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClientPacket {
     MoveUnits(MoveUnits),
+    OrderMoveUnits(OrderMoveUnits),
     SetCityBuildTask(SetCityBuildTask),
+    BuyBuildTask(BuyBuildTask),
+    AppendBuildQueue(AppendBuildQueue),
+    InsertBuildQueue(InsertBuildQueue),
+    RemoveBuildQueue(RemoveBuildQueue),
+    ReorderBuildQueue(ReorderBuildQueue),
+    SetBuildTaskRepeat(SetBuildTaskRepeat),
+    SetCityGovernor(SetCityGovernor),
+    SetCitySpecialists(SetCitySpecialists),
     SetWorkerTask(SetWorkerTask),
     SetEconomySettings(SetEconomySettings),
     SetResearch(SetResearch),
+    SetStateReligion(SetStateReligion),
+    SetCivics(SetCivics),
     DoUnitAction(DoUnitAction),
+    UpgradeUnit(UpgradeUnit),
+    PromoteUnit(PromoteUnit),
     DeclareWar(DeclareWar),
     MakePeace(MakePeace),
     ConfigureWorkedTiles(ConfigureWorkedTiles),
@@ -49,6 +68,22 @@ pub struct MoveUnits {
     pub target_pos: UVec2,
 }
 
+/// Orders units on the same tile to move to a (possibly distant)
+/// target over as many turns as needed.
+///
+/// The server stores the order on each unit and moves them along the
+/// shortest path immediately and at the start of each following turn.
+/// The order is canceled when the units arrive, when the path is blocked,
+/// or when an enemy comes into view.
+///
+/// The server broadcasts `UnitsMoved` for each step and `UpdateUnit`
+/// with the units' remaining order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderMoveUnits {
+    pub unit_ids: Vec<UnitId>,
+    pub target_pos: UVec2,
+}
+
 /// Sets a city's current build task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetCityBuildTask {
@@ -56,6 +91,76 @@ pub struct SetCityBuildTask {
     pub build_task: BuildTask,
 }
 
+/// Completes a city's current build task immediately
+/// in exchange for gold.
+///
+/// On success, the server sends `UpdateCity` and `UpdatePlayer`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuyBuildTask {
+    pub city_id: CityId,
+}
+
+/// Adds a task to the end of a city's build queue.
+///
+/// If `repeat` is set, the task (which must be a unit)
+/// is built continuously until removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppendBuildQueue {
+    pub city_id: CityId,
+    pub build_task: BuildTask,
+    pub repeat: bool,
+}
+
+/// Inserts a task into a city's build queue at `index`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InsertBuildQueue {
+    pub city_id: CityId,
+    pub index: usize,
+    pub build_task: BuildTask,
+    pub repeat: bool,
+}
+
+/// Removes the entry at `index` from a city's build queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoveBuildQueue {
+    pub city_id: CityId,
+    pub index: usize,
+}
+
+/// Moves an entry in a city's build queue from index `from` to index `to`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderBuildQueue {
+    pub city_id: CityId,
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Sets whether the entry at `index` in a city's build queue
+/// is built continuously. Only units can be repeated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetBuildTaskRepeat {
+    pub city_id: CityId,
+    pub index: usize,
+    pub repeat: bool,
+}
+
+/// Configures the governor that chooses a city's worked tiles.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCityGovernor {
+    pub city_id: CityId,
+    pub settings: GovernorSettings,
+}
+
+/// Sets the number of specialists of a type that a city should always keep.
+///
+/// The count is clamped to the city's specialist slots and population.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCitySpecialists {
+    pub city_id: CityId,
+    pub specialist: Specialist,
+    pub count: u32,
+}
+
 /// Sets a worker's current task.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetWorkerTask {
@@ -75,6 +180,24 @@ pub struct SetResearch {
     pub tech: Handle<Tech>,
 }
 
+/// Adopts a state religion, or abandons the current
+/// one if `religion` is `None`.
+///
+/// The religion must be present in one of the player's cities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetStateReligion {
+    pub religion: Option<Handle<Religion>>,
+}
+
+/// Switches to the given civics, each replacing the current
+/// civic in its category.
+///
+/// Changing any civic starts a period of anarchy.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetCivics {
+    pub civics: Vec<Handle<Civic>>,
+}
+
 /// An action performed on a unit.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UnitAction {
@@ -83,6 +206,20 @@ pub enum UnitAction {
     SkipTurn,
     FortifyUntilHealed,
     FoundCity,
+    /// Wait until an enemy comes into view.
+    Sentry,
+    /// Fortify until an enemy comes into view.
+    Alert,
+    /// Destroy an enemy improvement on the unit's tile for gold.
+    Pillage,
+    /// Explore the map each turn until cancelled or nothing is left to explore.
+    Explore,
+    /// Let the server choose the worker's tasks each turn until cancelled.
+    AutomateWorker,
+    /// Spread the owner's state religion to the city on the unit's tile.
+    SpreadReligion,
+    /// Perform an espionage mission in the foreign city on the unit's tile.
+    EspionageMission(EspionageMission),
 }
 
 /// Performs a [`UnitAction`].
@@ -92,6 +229,25 @@ pub struct DoUnitAction {
     pub action: UnitAction,
 }
 
+/// Upgrades a unit to a newer unit kind in the same line.
+///
+/// The unit must be in its owner's territory, and the owner
+/// must have enough gold to pay for the upgrade.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeUnit {
+    pub unit_id: UnitId,
+    pub unit_kind: Handle<UnitKind>,
+}
+
+/// Gives a unit a promotion.
+///
+/// The unit must have enough experience for its next level.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromoteUnit {
+    pub unit_id: UnitId,
+    pub promotion: Handle<Promotion>,
+}
+
 /// Declares war on a player.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DeclareWar {
@@ -6,8 +6,9 @@ use glam::UVec2;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    assets::Handle, combat::CombatEvent, registry::Tech, river::Rivers, unit::MovementPoints,
-    worker::WorkerProgressGrid, City, Grid, Player, PlayerId, Tile, Turn, Unit, UnitId,
+    assets::Handle, combat::CombatEvent, espionage::SpyDiscovered, registry::Tech, river::Rivers,
+    tribal_village::TribalVillageEntered, unit::MovementPoints, worker::WorkerProgressGrid, City,
+    GameSpeed, Grid, Player, PlayerId, Tile, Turn, Unit, UnitId,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +39,11 @@ pub enum ServerPacket {
     WarDeclared(WarDeclared),
     PeaceMade(PeaceMade),
     CombatEvent(CombatEvent),
+    UnitWoken(UnitWoken),
+    /// Sent to the victim when a spy is caught in one of their cities.
+    SpyDiscovered(SpyDiscovered),
+    /// Sent to the player whose unit entered a tribal village.
+    TribalVillageEntered(TribalVillageEntered),
 }
 
 /// Sent in the `GameStarted` lobby packet.
@@ -51,6 +57,8 @@ pub struct InitialGameData {
     pub map: Grid<RefCell<Tile>>,
     /// The current turn.
     pub turn: Turn,
+    /// The speed of the game.
+    pub speed: GameSpeed,
     /// Every player in the game.
     pub players: Vec<Player>,
     /// Every unit in the game.
@@ -131,6 +139,13 @@ pub struct TechUnlocked {
     pub tech: Handle<Tech>,
 }
 
+/// Informs the client that one of its sentry or alert
+/// units woke up because an enemy came into view.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitWoken {
+    pub unit: UnitId,
+}
+
 /// Response to a `SaveGame` request.
 ///
 /// The packet contains the serialized game data,
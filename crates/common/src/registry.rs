@@ -1,20 +1,34 @@
 mod building;
 mod civ;
+mod civic;
+mod handicap;
+mod improvement;
+mod promotion;
+mod religion;
 mod resource;
 mod tech;
+mod terrain;
 mod unit_kind;
 
 pub use building::*;
 pub use civ::*;
+pub use civic::*;
+pub use handicap::*;
+pub use improvement::*;
+pub use promotion::*;
+pub use religion::*;
 pub use resource::*;
 pub use tech::*;
+pub use terrain::*;
 pub use unit_kind::*;
 
 use crate::{
     assets::{Assets, Handle},
     utils::delimit_string,
+    Terrain,
 };
 
+use ahash::AHashMap;
 use indexmap::IndexMap;
 
 /// A registry of data-driven game files - unit kinds, civilizations,
@@ -26,6 +40,15 @@ pub struct Registry {
     buildings: IndexMap<String, Handle<Building>, ahash::RandomState>,
     techs: IndexMap<String, Handle<Tech>, ahash::RandomState>,
     resources: IndexMap<String, Handle<Resource>, ahash::RandomState>,
+    promotions: IndexMap<String, Handle<Promotion>, ahash::RandomState>,
+    improvements: IndexMap<String, Handle<ImprovementKind>, ahash::RandomState>,
+    terrains: IndexMap<String, Handle<TerrainKind>, ahash::RandomState>,
+    /// Terrain stats indexed by the terrain type they apply to.
+    terrains_by_type: AHashMap<Terrain, Handle<TerrainKind>>,
+    terrain_features: IndexMap<String, Handle<TerrainFeature>, ahash::RandomState>,
+    religions: IndexMap<String, Handle<Religion>, ahash::RandomState>,
+    civics: IndexMap<String, Handle<Civic>, ahash::RandomState>,
+    handicaps: IndexMap<String, Handle<Handicap>, ahash::RandomState>,
 }
 
 fn load_into_map<T: Send + Sync + 'static>(
@@ -42,6 +65,11 @@ fn load_into_map<T: Send + Sync + 'static>(
 #[error("no {0} exists with ID '{1}'")]
 pub struct RegistryItemNotFound(&'static str, String);
 
+/// An item that refers to another item that does not exist.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid {0} '{1}'")]
+pub struct InvalidRegistryItem(&'static str, String, #[source] RegistryItemNotFound);
+
 fn get<T>(
     map: &IndexMap<String, Handle<T>, ahash::RandomState>,
     id: &str,
@@ -57,12 +85,19 @@ impl Registry {
         Self::default()
     }
 
-    pub fn load_from_assets(&mut self, assets: &Assets) {
+    pub fn load_from_assets(&mut self, assets: &Assets) -> Result<(), InvalidRegistryItem> {
         load_into_map(assets, &mut self.unit_kinds, |u| &u.id);
         load_into_map(assets, &mut self.civs, |c| &c.id);
         load_into_map(assets, &mut self.buildings, |b| &b.name);
         load_into_map(assets, &mut self.techs, |t| &t.name);
         load_into_map(assets, &mut self.resources, |r| &r.id);
+        load_into_map(assets, &mut self.promotions, |p| &p.id);
+        load_into_map(assets, &mut self.improvements, |i| &i.id);
+        load_into_map(assets, &mut self.terrains, |t| &t.id);
+        load_into_map(assets, &mut self.terrain_features, |f| &f.id);
+        load_into_map(assets, &mut self.religions, |r| &r.id);
+        load_into_map(assets, &mut self.civics, |c| &c.id);
+        load_into_map(assets, &mut self.handicaps, |h| &h.id);
 
         // Sort all items alphabetically.
         self.unit_kinds.sort_by(|_, a, _, b| a.name.cmp(&b.name));
@@ -70,8 +105,69 @@ impl Registry {
         self.buildings.sort_by(|_, a, _, b| a.name.cmp(&b.name));
         self.techs.sort_by(|_, a, _, b| a.name.cmp(&b.name));
         self.resources.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.promotions.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.improvements.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.terrains.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.terrain_features
+            .sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.religions.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        self.civics.sort_by(|_, a, _, b| a.name.cmp(&b.name));
+        // ...except difficulty levels, which are sorted from easiest to hardest.
+        self.handicaps.sort_by(|_, a, _, b| a.level.cmp(&b.level));
+
+        self.terrains_by_type = self
+            .terrains
+            .values()
+            .map(|kind| (kind.terrain, kind.clone()))
+            .collect();
+
+        self.validate()?;
 
         log::info!("Initialized the registry");
+        Ok(())
+    }
+
+    /// Checks that items only refer to other items that exist,
+    /// so that lookups by ID can't fail during the game.
+    fn validate(&self) -> Result<(), InvalidRegistryItem> {
+        for kind in self.improvements.values() {
+            let invalid = |e| InvalidRegistryItem("improvement", kind.id.clone(), e);
+            self.tech(&kind.required_tech).map_err(invalid)?;
+            for bonus in &kind.tech_yield_bonuses {
+                self.tech(&bonus.tech).map_err(invalid)?;
+            }
+            if let Some(upgrade) = &kind.upgrade {
+                self.improvement(&upgrade.to).map_err(invalid)?;
+            }
+        }
+
+        for tech in self.techs.values() {
+            let invalid = |e| InvalidRegistryItem("tech", tech.name.clone(), e);
+            for effect in &tech.effects {
+                match (effect.typ, &effect.target) {
+                    (TechEffectType::FeatureYield, Some(target)) => {
+                        self.terrain_feature(target).map_err(invalid)?;
+                    }
+                    (TechEffectType::ImprovementYield, Some(target)) => {
+                        self.improvement(target).map_err(invalid)?;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for handicap in self.handicaps.values() {
+            let invalid = |e| InvalidRegistryItem("handicap", handicap.id.clone(), e);
+            for unit_kind in handicap
+                .human
+                .extra_starting_units
+                .iter()
+                .chain(&handicap.ai.extra_starting_units)
+            {
+                self.unit_kind(unit_kind).map_err(invalid)?;
+            }
+        }
+        Ok(())
     }
 
     pub fn unit_kind(&self, id: &str) -> Result<Handle<UnitKind>, RegistryItemNotFound> {
@@ -94,12 +190,53 @@ impl Registry {
         get(&self.resources, id, "resource")
     }
 
+    pub fn promotion(&self, id: &str) -> Result<Handle<Promotion>, RegistryItemNotFound> {
+        get(&self.promotions, id, "promotion")
+    }
+
+    pub fn improvement(&self, id: &str) -> Result<Handle<ImprovementKind>, RegistryItemNotFound> {
+        get(&self.improvements, id, "improvement")
+    }
+
+    /// Gets the stats of a base terrain type.
+    pub fn terrain(&self, terrain: Terrain) -> Result<Handle<TerrainKind>, RegistryItemNotFound> {
+        self.terrains_by_type
+            .get(&terrain)
+            .cloned()
+            .ok_or_else(|| RegistryItemNotFound("terrain", format!("{:?}", terrain)))
+    }
+
+    pub fn terrain_feature(
+        &self,
+        id: &str,
+    ) -> Result<Handle<TerrainFeature>, RegistryItemNotFound> {
+        get(&self.terrain_features, id, "terrain feature")
+    }
+
+    pub fn religion(&self, id: &str) -> Result<Handle<Religion>, RegistryItemNotFound> {
+        get(&self.religions, id, "religion")
+    }
+
+    pub fn civic(&self, id: &str) -> Result<Handle<Civic>, RegistryItemNotFound> {
+        get(&self.civics, id, "civic")
+    }
+
+    pub fn handicap(&self, id: &str) -> Result<Handle<Handicap>, RegistryItemNotFound> {
+        get(&self.handicaps, id, "handicap")
+    }
+
     pub fn num_civs(&self) -> usize {
-        self.civs.len()
+        self.civs().count()
     }
 
+    /// Gets the civilizations players can pick, excluding the barbarians.
     pub fn civs(&self) -> impl Iterator<Item = &Handle<Civilization>> + '_ {
-        self.civs.values()
+        self.civs.values().filter(|civ| !civ.barbarian)
+    }
+
+    /// Gets the civilization of the barbarian player.
+    pub fn barbarian_civ(&self) -> Option<&Handle<Civilization>> {
+        self.civs.values().find(|civ| civ.barbarian)
     }
 
     pub fn unit_kinds(&self) -> impl Iterator<Item = &Handle<UnitKind>> + '_ {
@@ -118,6 +255,37 @@ impl Registry {
         self.resources.values()
     }
 
+    pub fn promotions(&self) -> impl Iterator<Item = &Handle<Promotion>> + '_ {
+        self.promotions.values()
+    }
+
+    pub fn improvements(&self) -> impl Iterator<Item = &Handle<ImprovementKind>> + '_ {
+        self.improvements.values()
+    }
+
+    pub fn terrain_features(&self) -> impl Iterator<Item = &Handle<TerrainFeature>> + '_ {
+        self.terrain_features.values()
+    }
+
+    pub fn religions(&self) -> impl Iterator<Item = &Handle<Religion>> + '_ {
+        self.religions.values()
+    }
+
+    pub fn civics(&self) -> impl Iterator<Item = &Handle<Civic>> + '_ {
+        self.civics.values()
+    }
+
+    /// Gets the difficulty levels, easiest first.
+    pub fn handicaps(&self) -> impl Iterator<Item = &Handle<Handicap>> + '_ {
+        self.handicaps.values()
+    }
+
+    /// Gets the civics every player starts with: those
+    /// without a required tech.
+    pub fn starting_civics(&self) -> impl Iterator<Item = &Handle<Civic>> + '_ {
+        self.civics().filter(|civic| civic.required_tech.is_none())
+    }
+
     pub fn is_unit_replaced_for_civ(&self, unit: &UnitKind, civ: &Civilization) -> bool {
         for u in self.unit_kinds() {
             if u.only_for_civs.contains(&civ.id) {
@@ -129,6 +297,71 @@ impl Registry {
         false
     }
 
+    /// Returns whether `upgrade` is a newer unit kind in the same
+    /// line as `unit`, determined by following the `replaces` chain of `upgrade`.
+    ///
+    /// Unique units are treated as the unit they replace, so e.g.
+    /// a Phalanx can upgrade to anything an Axeman can.
+    pub fn is_unit_upgrade(&self, unit: &UnitKind, upgrade: &UnitKind) -> bool {
+        let base = if unit.only_for_civs.is_empty() {
+            &unit.id
+        } else {
+            unit.replaces.as_ref().unwrap_or(&unit.id)
+        };
+
+        // A unique unit is a variant of its base unit, not an upgrade.
+        if !upgrade.only_for_civs.is_empty() && upgrade.replaces.as_ref() == Some(base) {
+            return false;
+        }
+
+        let mut current = upgrade.replaces.clone();
+        let mut steps = 0;
+        while let Some(id) = current {
+            if &id == base {
+                return true;
+            }
+
+            // Guard against cycles in malformed data.
+            steps += 1;
+            if steps > self.unit_kinds.len() {
+                break;
+            }
+
+            current = self.unit_kind(&id).ok().and_then(|u| u.replaces.clone());
+        }
+
+        false
+    }
+
+    /// Returns whether `upgrade` is reached by following
+    /// the upgrade chain of `improvement`, e.g. a Town from a Cottage.
+    pub fn is_improvement_upgrade(
+        &self,
+        improvement: &ImprovementKind,
+        upgrade: &ImprovementKind,
+    ) -> bool {
+        let mut current = improvement.upgrade.as_ref().map(|u| u.to.clone());
+        let mut steps = 0;
+        while let Some(id) = current {
+            if id == upgrade.id {
+                return true;
+            }
+
+            // Guard against cycles in malformed data.
+            steps += 1;
+            if steps > self.improvements.len() {
+                break;
+            }
+
+            current = self
+                .improvement(&id)
+                .ok()
+                .and_then(|i| i.upgrade.as_ref().map(|u| u.to.clone()));
+        }
+
+        false
+    }
+
     pub fn is_building_replaced_for_civ(&self, building: &Building, civ: &Civilization) -> bool {
         for b in self.buildings.values() {
             if b.only_for_civs.contains(&civ.id) {
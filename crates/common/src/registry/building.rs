@@ -12,6 +12,28 @@ pub struct Building {
     #[serde(default)]
     pub only_for_civs: Vec<String>,
     pub replaces: Option<String>,
+    #[serde(default)]
+    pub wonder: Option<WonderKind>,
+}
+
+impl Building {
+    pub fn is_wonder(&self) -> bool {
+        self.wonder.is_some()
+    }
+
+    pub fn is_world_wonder(&self) -> bool {
+        self.wonder == Some(WonderKind::World)
+    }
+}
+
+/// The kind of wonder a building is, if any.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WonderKind {
+    /// Can be built once per player.
+    National,
+    /// Can be built once in the whole world.
+    World,
 }
 
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
@@ -44,4 +66,9 @@ pub enum BuildingEffectType {
     Anger,
     Sickness,
     GranaryFoodStore,
+    StartingExperience,
+    ScientistSlots,
+    MerchantSlots,
+    ArtistSlots,
+    EngineerSlots,
 }
@@ -1,5 +1,7 @@
 use arrayvec::ArrayVec;
 
+use crate::saveload::legacy;
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Civilization {
@@ -12,11 +14,36 @@ pub struct Civilization {
     pub starting_techs: Vec<String>,
     #[serde(default)]
     pub intro_text: String,
+    /// Whether this is the civilization of the barbarian player.
+    ///
+    /// It can't be picked in the lobby.
+    #[serde(default)]
+    pub barbarian: bool,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Leader {
     pub name: String,
-    // personality fields not needed on client
+    /// How much the leader cares about religion, from 0 to 10.
+    ///
+    /// Scales diplomatic modifiers from shared or differing state religions.
+    #[serde(default)]
+    pub religious: u32,
+    // other personality fields not needed on client
+}
+
+impl Leader {
+    /// Converts a leader from an unversioned save file, taking
+    /// the personality from the civilization's current data.
+    pub(crate) fn from_legacy(civ: &Civilization, legacy: legacy::Leader) -> Self {
+        civ.leaders
+            .iter()
+            .find(|leader| leader.name == legacy.name)
+            .cloned()
+            .unwrap_or(Self {
+                name: legacy.name,
+                religious: 0,
+            })
+    }
 }
@@ -0,0 +1,74 @@
+/// A civic a player can adopt, e.g. Hereditary Rule or Serfdom.
+///
+/// Players run exactly one civic in each [`CivicCategory`].
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Civic {
+    pub id: String,
+    pub name: String,
+    pub category: CivicCategory,
+    /// The tech needed to adopt the civic. Civics without
+    /// a required tech are the starting civic of their category.
+    #[serde(default)]
+    pub required_tech: Option<String>,
+    #[serde(default)]
+    pub effects: Vec<CivicEffect>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CivicCategory {
+    Government,
+    Legal,
+    Labor,
+    Religion,
+}
+
+impl CivicCategory {
+    pub fn all() -> [CivicCategory; 4] {
+        [
+            CivicCategory::Government,
+            CivicCategory::Legal,
+            CivicCategory::Labor,
+            CivicCategory::Religion,
+        ]
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CivicCategory::Government => "Government",
+            CivicCategory::Legal => "Legal",
+            CivicCategory::Labor => "Labor",
+            CivicCategory::Religion => "Religion",
+        }
+    }
+}
+
+/// An effect applied to every player running a civic.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CivicEffect {
+    #[serde(rename = "type")]
+    pub typ: CivicEffectType,
+    pub amount: i32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CivicEffectType {
+    /// City maintenance changes by `amount` percent.
+    MaintenancePercent,
+    /// Unit upkeep changes by `amount` percent.
+    UnitUpkeepPercent,
+    /// `amount` happiness in every city.
+    Happiness,
+    /// `amount` happiness per military unit in a city.
+    HappinessPerMilitaryUnit,
+    /// `amount` happiness in cities with the state religion.
+    StateReligionHappiness,
+    /// Workers build improvements `amount` percent faster.
+    WorkerSpeedPercent,
+    /// No economy slider other than gold can be set above `amount` percent.
+    /// The lowest limit among a player's civics applies.
+    MaxSliderPercent,
+}
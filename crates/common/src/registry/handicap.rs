@@ -0,0 +1,36 @@
+/// A difficulty level chosen in the lobby, e.g. Noble or Deity.
+///
+/// Human players and AI players receive separate modifiers,
+/// so that higher levels can make the game harder for humans
+/// while also giving the AI a head start.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Handicap {
+    pub id: String,
+    pub name: String,
+    /// Position of the handicap in the difficulty order, easiest first.
+    pub level: u32,
+    pub human: HandicapModifiers,
+    pub ai: HandicapModifiers,
+    /// Number of barbarian units, and their chance to appear,
+    /// as a percent of the normal amount.
+    pub barbarian_aggression_percent: u32,
+}
+
+/// Modifiers a [`Handicap`] applies to a player.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandicapModifiers {
+    /// Free happiness in each city.
+    pub free_happiness: u32,
+    /// Free health in each city.
+    pub free_health: u32,
+    /// Cost of techs as a percent of their base cost.
+    pub research_percent: u32,
+    /// Cost of units and buildings as a percent of their base cost.
+    pub production_percent: u32,
+    /// Units given to the player in addition to the usual settler
+    /// and scout or warrior.
+    #[serde(default)]
+    pub extra_starting_units: Vec<String>,
+}
@@ -0,0 +1,84 @@
+use crate::{Terrain, Yield};
+
+/// A kind of tile improvement, e.g. a Farm or Road.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImprovementKind {
+    pub id: String,
+    pub name: String,
+    pub required_tech: String,
+    /// Number of worker turns needed to build the improvement.
+    pub worker_turns: u32,
+    #[serde(default)]
+    pub yield_bonus: Yield,
+    /// Extra yield granted once the tile owner has researched a tech.
+    #[serde(default)]
+    pub tech_yield_bonuses: Vec<TechYieldBonus>,
+    #[serde(default)]
+    pub requirements: ImprovementRequirements,
+    /// The improvement this one grows into after being worked.
+    #[serde(default)]
+    pub upgrade: Option<ImprovementUpgrade>,
+    /// Whether the improvement can only be obtained through an upgrade,
+    /// e.g. a Town.
+    #[serde(default)]
+    pub only_by_upgrade: bool,
+    /// Whether units can move along the improvement at reduced cost.
+    /// Routes can coexist with one other improvement on a tile
+    /// and don't clear forests.
+    #[serde(default)]
+    pub is_route: bool,
+    /// Gold gained by an enemy unit that pillages the improvement.
+    #[serde(default)]
+    pub pillage_gold: u32,
+    /// Asset ID of the icon drawn on top of improved tiles.
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TechYieldBonus {
+    pub tech: String,
+    pub yield_bonus: Yield,
+}
+
+/// Conditions a tile must satisfy for an improvement to be built on it.
+///
+/// A revealed resource whose `improvement` names the improvement
+/// bypasses these requirements.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImprovementRequirements {
+    /// Allowed terrains. An empty list allows all land terrains.
+    #[serde(default)]
+    pub terrains: Vec<Terrain>,
+    /// Whether flood plains are allowed even if desert is not in `terrains`.
+    #[serde(default)]
+    pub flood_plains: bool,
+    #[serde(default)]
+    pub hills: HillsRequirement,
+    #[serde(default)]
+    pub fresh_water: bool,
+    /// Whether the improvement can only be built on a matching resource.
+    #[serde(default)]
+    pub resource_only: bool,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HillsRequirement {
+    #[default]
+    Allowed,
+    Required,
+    Forbidden,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImprovementUpgrade {
+    /// ID of the improvement to upgrade to.
+    pub to: String,
+    /// Number of turns a city has to work the tile before the upgrade happens.
+    pub worked_turns: u32,
+}
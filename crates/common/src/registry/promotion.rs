@@ -0,0 +1,38 @@
+use super::UnitCategory;
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Promotion {
+    pub id: String,
+    pub name: String,
+    /// The unit categories that can receive this promotion.
+    pub unit_categories: Vec<UnitCategory>,
+    /// IDs of promotions, one of which the unit must have
+    /// before receiving this promotion.
+    #[serde(default)]
+    pub prerequisites: Vec<String>,
+    pub effects: Vec<PromotionEffect>,
+}
+
+#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromotionEffect {
+    #[serde(rename = "type")]
+    pub typ: PromotionEffectType,
+    pub amount: u32,
+    /// Used for `AgainstUnitCategory`.
+    pub unit_category: Option<UnitCategory>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PromotionEffectType {
+    /// Percent bonus to strength when attacking or defending.
+    StrengthPercent,
+    /// Percent bonus to strength when fighting units of a category.
+    AgainstUnitCategory,
+    /// Extra movement points per turn.
+    Movement,
+    /// Extra percent of health healed per turn.
+    HealingPercent,
+}
@@ -0,0 +1,9 @@
+/// A religion that can be founded by researching a tech.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Religion {
+    pub id: String,
+    pub name: String,
+    /// The tech whose first discoverer founds the religion.
+    pub founded_by: String,
+}
@@ -1,12 +1,14 @@
+use crate::Yield;
+
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Tech {
     pub name: String,
     pub cost: u32,
     #[serde(default)]
-    pub unlocks_improvements: Vec<String>,
-    #[serde(default)]
     pub prerequisites: Vec<String>,
+    #[serde(default)]
+    pub effects: Vec<TechEffect>,
     pub quote: Option<Quote>,
 }
 
@@ -16,3 +18,31 @@ pub struct Quote {
     pub text: String,
     pub attribution: String,
 }
+
+/// An effect applied to every player who has researched a tech.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TechEffect {
+    #[serde(rename = "type")]
+    pub typ: TechEffectType,
+    /// ID of the terrain feature or improvement the effect applies to.
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub yield_bonus: Yield,
+    #[serde(default)]
+    pub amount: u32,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TechEffectType {
+    /// `yield_bonus` on owned tiles with the terrain feature `target`.
+    FeatureYield,
+    /// `yield_bonus` on owned tiles with the improvement `target`.
+    ImprovementYield,
+    /// Workers build improvements `amount` percent faster.
+    WorkerSpeedPercent,
+    /// Routes divide movement costs by `amount` more.
+    RouteMovement,
+}
@@ -0,0 +1,82 @@
+use crate::{Terrain, Yield};
+
+/// Stats of a base terrain type.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerrainKind {
+    pub id: String,
+    pub name: String,
+    /// The terrain type these stats apply to.
+    pub terrain: Terrain,
+    #[serde(default)]
+    pub base_yield: Yield,
+    #[serde(default = "default_movement_cost")]
+    pub movement_cost: u32,
+    /// Percent defense bonus for units on the terrain.
+    #[serde(default)]
+    pub defense_bonus: u32,
+    /// Health given to a city for each owned tile of this terrain
+    /// in its radius.
+    #[serde(default)]
+    pub health: f32,
+    /// Sickness given to a city for each owned tile of this terrain
+    /// in its radius.
+    #[serde(default)]
+    pub sickness: f32,
+}
+
+/// A feature that modifies the tile it lies on, e.g.
+/// a forest, hills or an oasis.
+///
+/// Forests, hills, flood plains and fresh water are built in and
+/// derived from tile state; other features are placed during map
+/// generation according to their `placement`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerrainFeature {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub yield_bonus: Yield,
+    /// Yield subtracted from the tile, saturating at zero.
+    #[serde(default)]
+    pub yield_penalty: Yield,
+    /// Cost to enter the tile. The highest cost among the terrain
+    /// and its features is used.
+    #[serde(default = "default_movement_cost")]
+    pub movement_cost: u32,
+    #[serde(default)]
+    pub defense_bonus: u32,
+    #[serde(default)]
+    pub health: f32,
+    #[serde(default)]
+    pub sickness: f32,
+    /// IDs of features that disable this feature's effects when
+    /// present on the same tile.
+    #[serde(default)]
+    pub suppressed_by: Vec<String>,
+    #[serde(default)]
+    pub placement: Option<FeaturePlacement>,
+    /// Asset ID of a texture drawn over the terrain.
+    #[serde(default)]
+    pub texture: Option<String>,
+}
+
+/// Determines where map generation places a feature.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeaturePlacement {
+    pub terrains: Vec<Terrain>,
+    /// Chance from 0 to 1 that an eligible tile receives the feature.
+    pub chance: f32,
+    #[serde(default)]
+    pub requires_fresh_water: bool,
+    #[serde(default)]
+    pub allow_hills: bool,
+    #[serde(default)]
+    pub allow_forest: bool,
+}
+
+fn default_movement_cost() -> u32 {
+    1
+}
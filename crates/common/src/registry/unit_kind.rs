@@ -5,6 +5,8 @@ pub enum CapabilityType {
     DoWork,
     CarryUnits,
     BombardCityDefenses,
+    SpreadReligion,
+    Espionage,
 }
 
 #[derive(Debug, serde::Deserialize, PartialEq, Eq, Copy, Clone, serde::Serialize)]
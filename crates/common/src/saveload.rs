@@ -1,6 +1,8 @@
 //! Saving and loading infrastructure.
 //!
 //! Riposte encodes save files with `bincode` and compresses them with `zstd`.
+//! The compressed data is preceded by an uncompressed header holding
+//! the save format version.
 
 use std::io::Cursor;
 
@@ -9,12 +11,46 @@ use serde::{Deserialize, Serialize};
 use slotmap::{SecondaryMap, SlotMap};
 
 use crate::{
-    lobby::GameLobby, river::Rivers, worker::WorkerProgressGrid, City, CityId, Grid, Player,
-    PlayerId, Tile, Turn, Unit, UnitId,
+    lobby::GameLobby,
+    registry::{Registry, RegistryItemNotFound},
+    river::Rivers,
+    worker::WorkerProgressGrid,
+    City, CityId, GameSpeed, Grid, Player, PlayerId, Tile, Turn, Unit, UnitId,
 };
 
+pub(crate) mod legacy;
+
 const COMPRESSION_LEVEL: i32 = 10;
 
+/// Marks the start of a save file.
+const MAGIC: &[u8; 8] = b"RIPOSTE\0";
+
+/// Version of the save file format. Must be increased
+/// whenever the saved game state changes in a way that old
+/// saves can't be read correctly.
+///
+/// Saves without a header predate versioning and are converted
+/// from the [`legacy`] layout when loaded.
+///
+/// * Version 1: improvements are loaded from assets.
+/// * Version 2: tiles store terrain features.
+/// * Version 3: worker progress is stored in hundredths of a turn.
+/// * Version 4: players, cities and leaders store religion data.
+/// * Version 5: players store their civics and anarchy turns.
+/// * Version 6: economies track culture and espionage from the sliders.
+/// * Version 7: cities store the turns left of espionage effects.
+/// * Version 8: tiles can hold tribal villages.
+/// * Version 9: players have a difficulty level.
+/// * Version 10: games have a speed.
+/// * Version 11: grids store whether they wrap east-west.
+pub const SAVE_VERSION: u32 = 11;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SaveFileError {
+    #[error("the save file has version {0}, but only version {SAVE_VERSION} is supported")]
+    UnsupportedVersion(u32),
+}
+
 /// The game state, serializable.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SaveFile {
@@ -33,13 +69,15 @@ pub struct SaveFile {
     pub worker_progress: WorkerProgressGrid,
 
     pub turn: Turn,
+    pub speed: GameSpeed,
 
     pub lobby: GameLobby,
 }
 
 impl SaveFile {
     pub fn encode(&self) -> Vec<u8> {
-        let buffer = Vec::new();
+        let mut buffer = MAGIC.to_vec();
+        buffer.extend_from_slice(&SAVE_VERSION.to_le_bytes());
         let mut encoder =
             zstd::Encoder::new(buffer, COMPRESSION_LEVEL).expect("failed to create zstd encoder");
 
@@ -50,15 +88,171 @@ impl SaveFile {
         encoder.finish().expect("zstd failed to compress")
     }
 
-    pub fn decode(bytes: &[u8]) -> anyhow::Result<Self> {
-        let decoder = zstd::Decoder::new(Cursor::new(bytes))?;
+    pub fn decode(bytes: &[u8], registry: &Registry) -> anyhow::Result<Self> {
+        let header = bytes
+            .strip_prefix(MAGIC.as_slice())
+            .and_then(|rest| Some((rest.get(..4)?, rest.get(4..)?)));
+        let (version, compressed) = match header {
+            Some(header) => header,
+            // Saves from before versioning start with the zstd frame.
+            None => return Self::decode_legacy(bytes, registry),
+        };
+        let version = u32::from_le_bytes(version.try_into().unwrap());
+        if version != SAVE_VERSION {
+            return Err(SaveFileError::UnsupportedVersion(version).into());
+        }
+
+        let decoder = zstd::Decoder::new(Cursor::new(compressed))?;
 
         bincode_options()
             .deserialize_from(decoder)
             .map_err(anyhow::Error::from)
     }
+
+    fn decode_legacy(bytes: &[u8], registry: &Registry) -> anyhow::Result<Self> {
+        let decoder = zstd::Decoder::new(Cursor::new(bytes))?;
+        let legacy: legacy::SaveFile = bincode_options().deserialize_from(decoder)?;
+        Ok(Self::from_legacy(legacy, registry)?)
+    }
+
+    fn from_legacy(
+        legacy: legacy::SaveFile,
+        registry: &Registry,
+    ) -> Result<Self, RegistryItemNotFound> {
+        let (lobby, slot_ids) = GameLobby::from_legacy(legacy.lobby);
+        Ok(Self {
+            map: Grid::from_legacy(legacy.map).try_map(|tile| Tile::from_legacy(tile, registry))?,
+            rivers: legacy.rivers,
+            player_ids: legacy.player_ids,
+            city_ids: legacy.city_ids,
+            unit_ids: legacy.unit_ids,
+            players: legacy
+                .players
+                .into_iter()
+                .map(|(id, player)| {
+                    let lobby_id = slot_ids[&player.lobby_id];
+                    Ok((id, Player::from_legacy(player, lobby_id, registry)?))
+                })
+                .collect::<Result<_, _>>()?,
+            cities: legacy
+                .cities
+                .into_iter()
+                .map(|(id, city)| (id, City::from_legacy(city)))
+                .collect(),
+            units: legacy
+                .units
+                .into_iter()
+                .map(|(id, unit)| Ok((id, Unit::from_legacy(unit, registry)?)))
+                .collect::<Result<_, _>>()?,
+            worker_progress: WorkerProgressGrid::from_legacy(legacy.worker_progress, registry)?,
+            turn: legacy.turn,
+            speed: GameSpeed::Normal,
+            lobby,
+        })
+    }
 }
 
 fn bincode_options() -> impl bincode::Options {
     bincode::options()
 }
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+
+    use crate::{player::PlayerKind, testing, worker::WorkerTask, Game, Improvement};
+
+    use super::*;
+
+    /// A save from before versioning: Rome and Greece at war
+    /// on a 10x6 map, three turns in.
+    const BASELINE_SAVE: &[u8] = include_bytes!("../testdata/baseline.rsave");
+
+    #[test]
+    fn save_files_carry_their_version() {
+        let registry = testing::registry();
+        let mut game = testing::new_game(4, 4);
+        testing::add_player(&mut game, "rome");
+
+        let mut encoded = game.to_save_file().encode();
+        assert!(encoded.starts_with(MAGIC));
+        let decoded = SaveFile::decode(&encoded, &registry).unwrap();
+        assert_eq!(decoded.players.len(), 1);
+
+        encoded[MAGIC.len()..MAGIC.len() + 4].copy_from_slice(&(SAVE_VERSION + 1).to_le_bytes());
+        let err = SaveFile::decode(&encoded, &registry).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(SaveFileError::UnsupportedVersion(_))
+        ));
+    }
+
+    #[test]
+    fn baseline_saves_are_migrated() {
+        let registry = testing::registry();
+        let save = SaveFile::decode(BASELINE_SAVE, &registry).unwrap();
+        assert_eq!(save.turn.get(), 3);
+        assert_eq!((save.map.width(), save.map.height()), (10, 6));
+        assert!(!save.map.wraps_x());
+
+        let improvement_ids = |pos| {
+            save.map
+                .get(pos)
+                .unwrap()
+                .improvements()
+                .map(|i| i.kind().id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(improvement_ids(uvec2(3, 2)), ["farm"]);
+        assert_eq!(improvement_ids(uvec2(1, 1)), ["mine"]);
+        assert_eq!(improvement_ids(uvec2(3, 3)), ["road"]);
+        assert_eq!(improvement_ids(uvec2(4, 3)), ["road"]);
+        // Cottages worked for 35 turns have grown into villages.
+        let tile = save.map.get(uvec2(2, 3)).unwrap();
+        let village = tile.improvements().next().unwrap();
+        assert_eq!(village.kind().id, "village");
+        assert_eq!(village.worked_turns(), 5);
+
+        // Players keep their lobby slots and play on Noble.
+        for player in save.players.values() {
+            let slot = save.lobby.slot(player.lobby_id());
+            assert_eq!(slot.player.civ(), Some(player.civ()));
+            assert_eq!(player.handicap().id, "noble");
+        }
+
+        let cottage = WorkerTask::BuildImprovement(Improvement::new(
+            registry.improvement("cottage").unwrap(),
+        ));
+        assert_eq!(
+            save.worker_progress.progress_for(uvec2(1, 3), &cottage),
+            400
+        );
+
+        let mut game = Game::from_save_file(registry, save);
+        let player_of_civ = |game: &Game, civ| {
+            game.players()
+                .find(|p| p.civ().id == civ)
+                .map(|p| p.id())
+                .unwrap()
+        };
+        let rome = player_of_civ(&game, "rome");
+        let greece = player_of_civ(&game, "greece");
+        assert!(matches!(game.player(rome).kind(), PlayerKind::Human { .. }));
+        assert!(matches!(game.player(greece).kind(), PlayerKind::Ai));
+        assert!(game.player(rome).is_at_war_with(greece));
+        assert_eq!(
+            game.player(rome).researching_tech().unwrap().name,
+            "Bronze Working"
+        );
+
+        let city_at = |pos| {
+            let city = game.city_at_pos(pos).unwrap();
+            (city.name().to_owned(), city.owner())
+        };
+        assert_eq!(city_at(uvec2(2, 2)), ("Roma".to_owned(), rome));
+        assert_eq!(city_at(uvec2(8, 3)), ("Athens".to_owned(), greece));
+
+        game.end_turn();
+        assert_eq!(game.turn().get(), 4);
+    }
+}
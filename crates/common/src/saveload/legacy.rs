@@ -0,0 +1,234 @@
+//! The save file layout from before save files were versioned.
+//!
+//! Unversioned saves are decoded into these types and then
+//! converted into the current game state by the `from_legacy`
+//! functions of each type.
+
+use std::num::NonZeroU32;
+
+use ahash::{AHashMap, AHashSet};
+use glam::UVec2;
+use indexmap::IndexSet;
+use serde::Deserialize;
+use slotmap::{SecondaryMap, SlotMap};
+use uuid::Uuid;
+
+use crate::{
+    assets::Handle,
+    city::{
+        AngerSource, BuildTask, HappinessSource, HealthSource, PreviousBuildTask, SicknessSource,
+    },
+    culture::Culture,
+    lobby::SlotId,
+    player::PlayerKind,
+    registry::{Building, BuildingEffectType, Civilization, Resource, Tech, UnitKind},
+    river::Rivers,
+    unit::MovementPoints,
+    CityId, Era, PlayerId, Terrain, Turn, UnitId, Visibility,
+};
+
+#[derive(Deserialize)]
+pub(crate) struct SaveFile {
+    pub(crate) map: Grid<Tile>,
+    pub(crate) rivers: Rivers,
+    pub(crate) player_ids: SlotMap<PlayerId, ()>,
+    pub(crate) city_ids: SlotMap<CityId, ()>,
+    pub(crate) unit_ids: SlotMap<UnitId, ()>,
+    pub(crate) players: SecondaryMap<PlayerId, Player>,
+    pub(crate) cities: SecondaryMap<CityId, City>,
+    pub(crate) units: SecondaryMap<UnitId, Unit>,
+    pub(crate) worker_progress: WorkerProgressGrid,
+    pub(crate) turn: Turn,
+    pub(crate) lobby: GameLobby,
+}
+
+/// A grid from before maps could wrap.
+#[derive(Deserialize)]
+pub(crate) struct Grid<T> {
+    pub(crate) tiles: Box<[T]>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct GameLobby {
+    pub(crate) slots: SlotMap<SlotId, LobbySlot>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct LobbySlot {
+    pub(crate) player: SlotPlayer,
+}
+
+#[derive(Deserialize)]
+pub(crate) enum SlotPlayer {
+    Empty {
+        player_uuid: Option<Uuid>,
+    },
+    Human {
+        player_uuid: Uuid,
+        username: String,
+        civ: Handle<Civilization>,
+        leader: Leader,
+        is_admin: bool,
+    },
+    Ai {
+        civ: Handle<Civilization>,
+        leader: Leader,
+    },
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Leader {
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Player {
+    pub(crate) on_server: bool,
+    pub(crate) id: PlayerId,
+    pub(crate) lobby_id: SlotId,
+    pub(crate) cities: Vec<CityId>,
+    pub(crate) units: Vec<UnitId>,
+    pub(crate) capital: Option<CityId>,
+    pub(crate) is_alive: bool,
+    pub(crate) kind: PlayerKind,
+    pub(crate) at_war_with: AHashSet<PlayerId>,
+    pub(crate) civ: Handle<Civilization>,
+    pub(crate) leader_name: String,
+    pub(crate) gold: u32,
+    pub(crate) economy: PlayerEconomy,
+    pub(crate) economy_settings: EconomySettings,
+    pub(crate) score: u32,
+    pub(crate) era: Era,
+    pub(crate) tech_progress: AHashMap<Handle<Tech>, u32>,
+    pub(crate) research: Option<Handle<Tech>>,
+    pub(crate) unlocked_techs: AHashSet<Handle<Tech>>,
+    pub(crate) visibility: Grid<Visibility>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct PlayerEconomy {
+    pub(crate) base_revenue: u32,
+    pub(crate) gold_revenue: u32,
+    pub(crate) beaker_revenue: u32,
+    pub(crate) expenses: u32,
+    pub(crate) beaker_overflow: u32,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct EconomySettings {
+    pub(crate) beaker_percent: u32,
+    pub(crate) gold_percent: u32,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct City {
+    pub(crate) on_server: bool,
+    pub(crate) id: CityId,
+    pub(crate) owner: PlayerId,
+    pub(crate) pos: UVec2,
+    pub(crate) name: String,
+    pub(crate) population: NonZeroU32,
+    pub(crate) is_capital: bool,
+    pub(crate) is_coastal: bool,
+    pub(crate) culture: Culture,
+    pub(crate) worked_tiles: IndexSet<UVec2, ahash::RandomState>,
+    pub(crate) manually_worked_tiles: IndexSet<UVec2, ahash::RandomState>,
+    pub(crate) stored_food: u32,
+    pub(crate) build_task_progress: AHashMap<BuildTask, u32>,
+    pub(crate) build_task: Option<BuildTask>,
+    pub(crate) culture_defense_bonus: u32,
+    pub(crate) resources: AHashSet<Handle<Resource>>,
+    pub(crate) connected_to_cities: AHashSet<CityId>,
+    pub(crate) buildings: Vec<Handle<Building>>,
+    pub(crate) building_effects: AHashMap<BuildingEffectType, u32>,
+    pub(crate) economy: CityEconomy,
+    pub(crate) previous_build_task: Option<PreviousBuildTask>,
+    pub(crate) happiness_sources: Vec<HappinessSource>,
+    pub(crate) anger_sources: Vec<AngerSource>,
+    pub(crate) health_sources: Vec<HealthSource>,
+    pub(crate) sickness_sources: Vec<SicknessSource>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CityEconomy {
+    pub(crate) gold: f64,
+    pub(crate) beakers: f64,
+    pub(crate) commerce_yield: f64,
+    pub(crate) hammer_yield: u32,
+    pub(crate) food_yield: u32,
+    pub(crate) overflow_hammers: u32,
+    pub(crate) culture_per_turn: u32,
+    pub(crate) maintenance_cost: f64,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Unit {
+    pub(crate) on_server: bool,
+    pub(crate) id: UnitId,
+    pub(crate) owner: PlayerId,
+    pub(crate) kind: Handle<UnitKind>,
+    pub(crate) pos: UVec2,
+    pub(crate) health: f64,
+    pub(crate) movement_left: MovementPoints,
+    pub(crate) is_fortified_forever: bool,
+    pub(crate) is_skipping_turn: bool,
+    pub(crate) is_fortified_until_heal: bool,
+    pub(crate) has_used_attack: bool,
+    pub(crate) capabilities: Vec<Capability>,
+}
+
+#[derive(Deserialize)]
+pub(crate) enum Capability {
+    FoundCity,
+    BombardCity { max_per_turn: u32 },
+    Worker(WorkerCapability),
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WorkerCapability {
+    pub(crate) current_task: Option<WorkerTask>,
+}
+
+#[derive(PartialEq, Eq, Hash, Deserialize)]
+pub(crate) enum WorkerTask {
+    BuildImprovement(Improvement),
+}
+
+/// The hardcoded improvements from before improvements
+/// were loaded from assets.
+#[derive(PartialEq, Eq, Hash, Deserialize)]
+pub(crate) enum Improvement {
+    Farm,
+    Mine,
+    Road,
+    Pasture,
+    Plantation,
+    Cottage(Cottage),
+}
+
+#[derive(PartialEq, Eq, Hash, Deserialize)]
+pub(crate) struct Cottage {
+    /// Worked turns, counted across all cottage levels.
+    pub(crate) worked_turns: u32,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct Tile {
+    pub(crate) terrain: Terrain,
+    pub(crate) is_forested: bool,
+    pub(crate) is_hilled: bool,
+    pub(crate) has_fresh_water: bool,
+    pub(crate) culture: Culture,
+    pub(crate) influencers: Vec<CityId>,
+    pub(crate) worked_by_city: Option<CityId>,
+    pub(crate) resource: Option<Handle<Resource>>,
+    pub(crate) improvements: Vec<Improvement>,
+    pub(crate) owner: Option<PlayerId>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct WorkerProgressGrid {
+    pub(crate) progress: Grid<AHashMap<WorkerTask, u32>>,
+}
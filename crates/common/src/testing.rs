@@ -0,0 +1,163 @@
+//! Helpers for setting up games in unit tests.
+
+use std::{any::Any, cell::RefCell, marker::PhantomData, path::Path, sync::Arc};
+
+use glam::UVec2;
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    assets::{set_global_assets, Assets, Loader},
+    lobby::{GameLobby, SlotId},
+    player::PlayerKind,
+    registry::{
+        Building, Civic, Civilization, Handicap, ImprovementKind, Promotion, Registry, Religion,
+        Resource, Tech, TerrainFeature, TerrainKind, UnitKind,
+    },
+    river::Rivers,
+    City, CityId, Game, Grid, Player, PlayerId, Terrain, Tile, Unit, UnitId,
+};
+
+struct JsonLoader<T>(PhantomData<T>);
+
+impl<T> Loader for JsonLoader<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    fn load_from_bytes(
+        &self,
+        _id: &str,
+        bytes: &[u8],
+    ) -> anyhow::Result<Option<Arc<dyn Any + Send + Sync>>> {
+        let value: T = serde_json::from_slice(bytes)?;
+        Ok(Some(Arc::new(value)))
+    }
+}
+
+/// Skips assets only the client cares about, like textures and sounds.
+struct IgnoreLoader;
+
+impl Loader for IgnoreLoader {
+    fn load_from_path(
+        &self,
+        _id: &str,
+        _path: &Path,
+    ) -> anyhow::Result<Option<Arc<dyn Any + Send + Sync>>> {
+        Ok(None)
+    }
+
+    fn load_from_bytes(
+        &self,
+        _id: &str,
+        _bytes: &[u8],
+    ) -> anyhow::Result<Option<Arc<dyn Any + Send + Sync>>> {
+        Ok(None)
+    }
+}
+
+fn json<T: DeserializeOwned + Send + Sync + 'static>() -> JsonLoader<T> {
+    JsonLoader(PhantomData)
+}
+
+static REGISTRY: Lazy<Arc<Registry>> = Lazy::new(|| {
+    let mut assets = Assets::new();
+    assets
+        .add_loader("image", IgnoreLoader)
+        .add_loader("font", IgnoreLoader)
+        .add_loader("sound", IgnoreLoader)
+        .add_loader("video", IgnoreLoader)
+        .add_loader("civ", json::<Civilization>())
+        .add_loader("unit", json::<UnitKind>())
+        .add_loader("tech", json::<Tech>())
+        .add_loader("building", json::<Building>())
+        .add_loader("resource", json::<Resource>())
+        .add_loader("promotion", json::<Promotion>())
+        .add_loader("improvement", json::<ImprovementKind>())
+        .add_loader("terrain", json::<TerrainKind>())
+        .add_loader("feature", json::<TerrainFeature>())
+        .add_loader("religion", json::<Religion>())
+        .add_loader("civic", json::<Civic>())
+        .add_loader("handicap", json::<Handicap>());
+    assets
+        .load_from_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("../../assets"))
+        .expect("failed to load assets");
+
+    let mut registry = Registry::new();
+    registry
+        .load_from_assets(&assets)
+        .expect("invalid registry");
+    set_global_assets(assets);
+    Arc::new(registry)
+});
+
+/// Gets the registry loaded from the game's assets.
+pub fn registry() -> Arc<Registry> {
+    Arc::clone(&REGISTRY)
+}
+
+/// Creates a game on a map of grassland with no players.
+pub fn new_game(width: u32, height: u32) -> Game {
+    new_game_with_rivers(width, height, Rivers::default())
+}
+
+pub fn new_game_with_rivers(width: u32, height: u32, rivers: Rivers) -> Game {
+    let map = Grid::new(RefCell::new(Tile::new(Terrain::Grassland)), width, height);
+    Game::new(registry(), map, rivers, GameLobby::new())
+}
+
+/// Adds an AI player with the given civilization on the default difficulty.
+pub fn add_player(game: &mut Game, civ: &str) -> PlayerId {
+    add_player_with_kind(game, civ, PlayerKind::Ai, "noble")
+}
+
+pub fn add_player_with_kind(
+    game: &mut Game,
+    civ: &str,
+    kind: PlayerKind,
+    handicap: &str,
+) -> PlayerId {
+    let id = game.new_player_id();
+    let civ = game.registry().civ(civ).unwrap();
+    let leader_name = civ.leaders[0].name.clone();
+    let handicap = game.registry().handicap(handicap).unwrap();
+    let player = Player::new(
+        game,
+        id,
+        SlotId::default(),
+        kind,
+        civ,
+        leader_name,
+        handicap,
+        game.map().width(),
+        game.map().height(),
+    );
+    game.add_player(player);
+    id
+}
+
+/// Adds a unit, revealing the tiles around it to its owner.
+pub fn add_unit(game: &mut Game, owner: PlayerId, kind: &str, pos: UVec2) -> UnitId {
+    let id = game.new_unit_id();
+    let kind = game.registry().unit_kind(kind).unwrap();
+    game.add_unit(Unit::new(id, owner, kind, pos));
+    game.player_mut(owner).update_visibility(game);
+    id
+}
+
+/// Founds a city, letting it claim its surrounding tiles.
+pub fn add_city(game: &mut Game, owner: PlayerId, pos: UVec2) -> CityId {
+    let id = game.new_city_id();
+    let city = {
+        let owner = game.player(owner);
+        City::new(
+            id,
+            &owner,
+            pos,
+            format!("City {}", owner.cities().len()),
+            game,
+        )
+    };
+    game.add_city(city);
+    game.run_deferred_functions();
+    id
+}
@@ -1,6 +1,7 @@
 use std::{fmt::Display, ops::Add};
 
 use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
 /// An era in history.
 ///
@@ -38,35 +39,17 @@ impl Turn {
         self.0
     }
 
-    /// Gets the year corresponding to the current turn number.
-    pub fn year(self) -> Year {
-        // Piecewise year function from (0, 4000 BCE) to (500, 2050 CE).
-        let increments = [
-            (480, 75),
-            (300, 60),
-            (240, 25),
-            (120, 50),
-            (60, 60),
-            (24, 50),
-            (12, 120),
-        ];
-
-        let mut months = 0;
-        let mut current_turn = 0;
-        for (incr, turns) in increments {
-            let mut i = 0;
-            while current_turn < self.0 && i < turns {
-                months += incr;
-                current_turn += 1;
-                i += 1;
-            }
-        }
-
-        // End behavior
-        while current_turn < self.0 {
-            months += 6;
-            current_turn += 1;
-        }
+    /// Gets the year corresponding to the current turn number
+    /// in a game played at the given speed.
+    pub fn year(self, speed: GameSpeed) -> Year {
+        // Stretch the Normal calendar so that every speed
+        // covers the same years, interpolating between turns.
+        let percent = speed.percent() as i32;
+        let normal_turn = self.0 * 100 / percent as u32;
+        let remainder = (self.0 * 100 % percent as u32) as i32;
+        let months = normal_months_elapsed(normal_turn);
+        let next_months = normal_months_elapsed(normal_turn + 1);
+        let months = months + (next_months - months) * remainder / percent;
 
         Year::new(months / 12 - 4000)
     }
@@ -76,12 +59,80 @@ impl Turn {
     }
 }
 
+/// Gets the number of months elapsed after `turn` turns at Normal speed.
+fn normal_months_elapsed(turn: u32) -> i32 {
+    // Piecewise year function from (0, 4000 BCE) to (500, 2050 CE).
+    let increments = [
+        (480, 75),
+        (300, 60),
+        (240, 25),
+        (120, 50),
+        (60, 60),
+        (24, 50),
+        (12, 120),
+    ];
+
+    let mut months = 0;
+    let mut current_turn = 0;
+    for (incr, turns) in increments {
+        let mut i = 0;
+        while current_turn < turn && i < turns {
+            months += incr;
+            current_turn += 1;
+            i += 1;
+        }
+    }
+
+    // End behavior
+    while current_turn < turn {
+        months += 6;
+        current_turn += 1;
+    }
+
+    months
+}
+
 impl Display for Turn {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
     }
 }
 
+/// How fast the game progresses, chosen in the lobby.
+///
+/// Slower speeds stretch the calendar over more turns and
+/// make techs, build tasks, growth and worker tasks take longer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter, Serialize, Deserialize)]
+pub enum GameSpeed {
+    Quick,
+    Normal,
+    Epic,
+    Marathon,
+}
+
+impl GameSpeed {
+    /// Gets costs and durations as a percent of those at Normal speed.
+    pub fn percent(self) -> u32 {
+        match self {
+            GameSpeed::Quick => 67,
+            GameSpeed::Normal => 100,
+            GameSpeed::Epic => 150,
+            GameSpeed::Marathon => 300,
+        }
+    }
+
+    /// Scales a cost or duration given at Normal speed to this speed.
+    pub fn scale(self, amount: u32) -> u32 {
+        amount * self.percent() / 100
+    }
+}
+
+impl Display for GameSpeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// A year in history.
 ///
 /// Can be BCE/BC or CE/AD (negative and positive years, respectively)
@@ -130,6 +181,17 @@ impl Add<Yield> for Yield {
     }
 }
 
+impl Yield {
+    /// Subtracts `rhs` from each component, stopping at zero.
+    pub fn saturating_sub(self, rhs: Yield) -> Yield {
+        Self {
+            hammers: self.hammers.saturating_sub(rhs.hammers),
+            commerce: self.commerce.saturating_sub(rhs.commerce),
+            food: self.food.saturating_sub(rhs.food),
+        }
+    }
+}
+
 /// Determines how a tile is visibile to a player.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Visibility {
@@ -161,7 +223,22 @@ mod tests {
 
     #[test]
     fn turn_to_year() {
-        assert_eq!(Turn::new(0).year().get(), -4000);
-        assert_eq!(Turn::new(500).year().get(), 2050);
+        assert_eq!(Turn::new(0).year(GameSpeed::Normal).get(), -4000);
+        assert_eq!(Turn::new(500).year(GameSpeed::Normal).get(), 2050);
+    }
+
+    #[test]
+    fn turn_to_year_at_other_speeds() {
+        for (speed, last_turn) in [
+            (GameSpeed::Quick, 335),
+            (GameSpeed::Epic, 750),
+            (GameSpeed::Marathon, 1500),
+        ] {
+            assert_eq!(Turn::new(0).year(speed).get(), -4000);
+            assert_eq!(Turn::new(last_turn).year(speed).get(), 2050);
+            assert!(
+                Turn::new(last_turn / 2).year(speed) <= Turn::new(last_turn / 2 + 1).year(speed)
+            );
+        }
     }
 }
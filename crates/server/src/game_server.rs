@@ -1,28 +1,36 @@
 use anyhow::Context;
 use flume::{Receiver, Sender};
-use glam::UVec2;
+use glam::{uvec2, UVec2};
 use riposte_common::{
+    barbarians,
+    city::BuildQueueError,
     event::Event,
+    order,
+    path::{Pathfinder, UnitMovementRules},
     protocol::{
         client::{
-            ClientGamePacket, ClientPacket, ConfigureWorkedTiles, DeclareWar, DoUnitAction,
-            MakePeace, MoveUnits, SetCityBuildTask, SetEconomySettings, SetResearch, SetWorkerTask,
-            UnitAction,
+            AppendBuildQueue, BuyBuildTask, ClientGamePacket, ClientPacket, ConfigureWorkedTiles,
+            DeclareWar, DoUnitAction, InsertBuildQueue, MakePeace, MoveUnits, OrderMoveUnits,
+            PromoteUnit, RemoveBuildQueue, ReorderBuildQueue, SetBuildTaskRepeat, SetCityBuildTask,
+            SetCityGovernor, SetCitySpecialists, SetCivics, SetEconomySettings, SetResearch,
+            SetStateReligion, SetWorkerTask, UnitAction, UpgradeUnit,
         },
         game::server::{InitialGameData, ServerGamePacket, ServerPacket},
         server::{
-            ConfirmMoveUnits, DeleteUnit, GameSaved, PeaceMade, TechUnlocked, UnitsMoved,
-            UpdateCity, UpdatePlayer, UpdateTile, UpdateTurn, UpdateUnit, UpdateWorkerProgressGrid,
-            WarDeclared,
+            ConfirmMoveUnits, DeleteUnit, GameSaved, PeaceMade, TechUnlocked, UnitWoken,
+            UnitsMoved, UpdateCity, UpdatePlayer, UpdateTile, UpdateTurn, UpdateUnit,
+            UpdateWorkerProgressGrid, WarDeclared,
         },
         GenericServerPacket,
     },
-    PlayerId, UnitId,
+    registry::CapabilityType,
+    unit::UnitOrder,
+    CityId, PlayerId, UnitId,
 };
 use slotmap::SecondaryMap;
 
 use crate::connection::{ConnectionId, Connections};
-use crate::game::Game;
+use crate::game::{City, Game};
 
 pub struct GameServer {
     game: Game,
@@ -57,11 +65,16 @@ impl GameServer {
     }
 
     fn conn_for_player(&self, player: PlayerId) -> ConnectionId {
+        self.try_conn_for_player(player).unwrap()
+    }
+
+    /// Gets the connection of a player, or `None` for
+    /// players without one, such as AIs.
+    fn try_conn_for_player(&self, player: PlayerId) -> Option<ConnectionId> {
         self.player_connections
             .iter()
             .find(|(p, _)| *p == player)
-            .unwrap()
-            .1
+            .map(|&(_, conn)| conn)
     }
 
     fn broadcast(&self, conns: &Connections, packet: ServerPacket) {
@@ -76,12 +89,24 @@ impl GameServer {
     pub fn make_initial_game_data(&self, for_player: PlayerId) -> InitialGameData {
         let player = self.game.player(for_player);
 
+        let map = self.game.map().clone();
+        for tile in map.as_slice() {
+            tile.borrow_mut()
+                .hide_unrevealed_resource(self.game.registry(), &player);
+        }
+
         InitialGameData {
             the_player_id: player.id(),
-            map: self.game.map().clone(),
+            map,
             turn: self.game.turn(),
+            speed: self.game.speed(),
             players: self.game.players().map(|p| p.clone()).collect(),
-            units: self.game.units().map(|u| u.clone()).collect(),
+            units: self
+                .game
+                .units()
+                .filter(|u| !u.is_hidden_from(for_player))
+                .map(|u| u.clone())
+                .collect(),
             cities: self.game.cities().map(|c| c.clone()).collect(),
             rivers: self.game.rivers().clone(),
             worker_progress: self.game.worker_progress_grid().clone(),
@@ -105,11 +130,24 @@ impl GameServer {
             ClientPacket::MoveUnits(p) => {
                 self.handle_move_units(player, p, packet.request_id, conns)
             }
+            ClientPacket::OrderMoveUnits(p) => self.handle_order_move_units(player, p),
             ClientPacket::SetCityBuildTask(p) => self.handle_set_city_build_task(p),
+            ClientPacket::BuyBuildTask(p) => self.handle_buy_build_task(player, p),
+            ClientPacket::AppendBuildQueue(p) => self.handle_append_build_queue(player, p),
+            ClientPacket::InsertBuildQueue(p) => self.handle_insert_build_queue(player, p),
+            ClientPacket::RemoveBuildQueue(p) => self.handle_remove_build_queue(player, p),
+            ClientPacket::ReorderBuildQueue(p) => self.handle_reorder_build_queue(player, p),
+            ClientPacket::SetBuildTaskRepeat(p) => self.handle_set_build_task_repeat(player, p),
+            ClientPacket::SetCityGovernor(p) => self.handle_set_city_governor(player, p),
+            ClientPacket::SetCitySpecialists(p) => self.handle_set_city_specialists(player, p),
             ClientPacket::SetWorkerTask(p) => self.handle_set_worker_task(p),
             ClientPacket::SetEconomySettings(p) => self.handle_set_economy_settings(player, p),
             ClientPacket::SetResearch(p) => self.handle_set_research(player, p),
+            ClientPacket::SetStateReligion(p) => self.handle_set_state_religion(player, p),
+            ClientPacket::SetCivics(p) => self.handle_set_civics(player, p),
             ClientPacket::DoUnitAction(p) => self.handle_do_unit_action(p),
+            ClientPacket::UpgradeUnit(p) => self.handle_upgrade_unit(player, p),
+            ClientPacket::PromoteUnit(p) => self.handle_promote_unit(player, p),
             ClientPacket::DeclareWar(p) => self.handle_declare_war(player, p),
             ClientPacket::MakePeace(p) => self.handle_make_peace(player, p),
             ClientPacket::ConfigureWorkedTiles(p) => self.handle_configure_worked_tiles(p),
@@ -163,18 +201,17 @@ impl GameServer {
         }
 
         if success {
-            let mut new_movement_left = Vec::new();
             for &unit in &packet.unit_ids {
-                self.game
-                    .unit_mut(unit)
-                    .move_to(&self.game, packet.target_pos);
-                new_movement_left.push(self.game.unit(unit).movement_left());
+                let mut unit = self.game.unit_mut(unit);
+                unit.move_to(&self.game, packet.target_pos);
+
+                // Moving manually cancels the unit's order.
+                if unit.order().is_some() {
+                    unit.set_order(None);
+                    self.game.push_event(Event::UnitChanged(unit.id()));
+                }
             }
-            conns.broadcast_game_packet(ServerPacket::UnitsMoved(UnitsMoved {
-                units: packet.unit_ids,
-                new_movement_left,
-                new_pos: packet.target_pos,
-            }));
+            self.send_units_moved(conns, &packet.unit_ids, packet.target_pos);
         }
 
         conns.get(self.conn_for_player(player)).send_game_packet(
@@ -183,11 +220,58 @@ impl GameServer {
         );
     }
 
+    fn handle_order_move_units(&mut self, player: PlayerId, packet: OrderMoveUnits) {
+        let pos = match packet.unit_ids.first() {
+            Some(&unit) => self.game.unit(unit).pos(),
+            None => return,
+        };
+        for &unit in &packet.unit_ids {
+            let unit = self.game.unit(unit);
+            if unit.owner() != player || unit.pos() != pos {
+                log::warn!("{:?} sent an invalid move order", player);
+                return;
+            }
+        }
+
+        let rules =
+            UnitMovementRules::new(player, packet.unit_ids.iter().map(|&u| self.game.unit(u)));
+        let path = match Pathfinder::new().compute_shortest_path(
+            &self.game,
+            &rules,
+            pos,
+            packet.target_pos,
+        ) {
+            Some(path) => path,
+            None => {
+                log::info!("No path for move order to {:?}", packet.target_pos);
+                return;
+            }
+        };
+
+        for &unit in &packet.unit_ids {
+            self.game.unit_mut(unit).set_order(Some(UnitOrder::MoveTo {
+                target: packet.target_pos,
+                path: path.clone(),
+            }));
+        }
+        order::execute_unit_orders(&mut self.game, &packet.unit_ids);
+    }
+
     fn handle_do_unit_action(&mut self, packet: DoUnitAction) {
+        self.game.unit_mut(packet.unit_id).set_order(None);
         match packet.action {
             UnitAction::Kill => self.game.remove_unit(packet.unit_id),
             UnitAction::Fortify => self.game.unit_mut(packet.unit_id).fortify_forever(),
             UnitAction::SkipTurn => self.game.unit_mut(packet.unit_id).skip_turn(),
+            UnitAction::Sentry => self.game.unit_mut(packet.unit_id).sentry(),
+            UnitAction::Alert => {
+                let mut unit = self.game.unit_mut(packet.unit_id);
+                if unit.kind().strength > 0. {
+                    unit.alert();
+                } else {
+                    log::info!("Failed to put unit on alert: it cannot fight");
+                }
+            }
             UnitAction::FortifyUntilHealed => {
                 self.game.unit_mut(packet.unit_id).fortify_until_healed()
             }
@@ -196,9 +280,71 @@ impl GameServer {
                     log::info!("Failed to found city: {}", e);
                 }
             }
+            UnitAction::Pillage => {
+                if let Err(e) = self.game.unit_mut(packet.unit_id).pillage(&self.game) {
+                    log::info!("Failed to pillage: {}", e);
+                }
+            }
+            UnitAction::Explore => self
+                .game
+                .unit_mut(packet.unit_id)
+                .set_order(Some(UnitOrder::Explore)),
+            UnitAction::AutomateWorker => {
+                let mut unit = self.game.unit_mut(packet.unit_id);
+                if unit.has_capability(CapabilityType::DoWork) {
+                    unit.set_order(Some(UnitOrder::AutomateWorker));
+                } else {
+                    log::info!("Failed to automate unit: it cannot do work");
+                }
+            }
+            UnitAction::SpreadReligion => {
+                if let Err(e) = self
+                    .game
+                    .unit_mut(packet.unit_id)
+                    .spread_religion(&self.game)
+                {
+                    log::info!("Failed to spread religion: {}", e);
+                }
+            }
+            UnitAction::EspionageMission(mission) => {
+                if let Err(e) = self
+                    .game
+                    .unit_mut(packet.unit_id)
+                    .perform_mission(&self.game, mission)
+                {
+                    log::info!("Failed to perform {}: {}", mission.name(), e);
+                }
+            }
         }
 
         self.game.push_event(Event::UnitChanged(packet.unit_id));
+
+        // Start carrying out a new order right away.
+        order::execute_unit_orders(&mut self.game, &[packet.unit_id]);
+    }
+
+    fn handle_upgrade_unit(&mut self, player: PlayerId, packet: UpgradeUnit) {
+        let mut unit = self.game.unit_mut(packet.unit_id);
+        if unit.owner() != player {
+            log::warn!("{:?} attempted to upgrade a unit they don't own", player);
+            return;
+        }
+
+        if let Err(e) = unit.upgrade_to(&self.game, packet.unit_kind) {
+            log::info!("Failed to upgrade unit: {}", e);
+        }
+    }
+
+    fn handle_promote_unit(&mut self, player: PlayerId, packet: PromoteUnit) {
+        let mut unit = self.game.unit_mut(packet.unit_id);
+        if unit.owner() != player {
+            log::warn!("{:?} attempted to promote a unit they don't own", player);
+            return;
+        }
+
+        if let Err(e) = unit.promote(&self.game, packet.promotion) {
+            log::info!("Failed to promote unit: {}", e);
+        }
     }
 
     fn handle_set_city_build_task(&mut self, p: SetCityBuildTask) {
@@ -206,6 +352,92 @@ impl GameServer {
         self.game.push_event(Event::CityChanged(p.city_id));
     }
 
+    fn handle_buy_build_task(&mut self, player: PlayerId, p: BuyBuildTask) {
+        let mut city = self.game.city_mut(p.city_id);
+        if city.owner() != player {
+            log::warn!("{:?} attempted to buy in a city they don't own", player);
+            return;
+        }
+
+        if let Err(e) = city.buy_build_task(&self.game) {
+            log::info!("Failed to buy build task: {}", e);
+        }
+    }
+
+    fn edit_build_queue(
+        &mut self,
+        player: PlayerId,
+        city_id: CityId,
+        edit: impl FnOnce(&mut City, &Game) -> Result<(), BuildQueueError>,
+    ) {
+        {
+            let mut city = self.game.city_mut(city_id);
+            if city.owner() != player {
+                log::warn!(
+                    "{:?} attempted to edit the build queue of a city they don't own",
+                    player
+                );
+                return;
+            }
+
+            if let Err(e) = edit(&mut city, &self.game) {
+                log::info!("Failed to edit build queue: {}", e);
+                return;
+            }
+        }
+        self.game.push_event(Event::CityChanged(city_id));
+    }
+
+    fn handle_append_build_queue(&mut self, player: PlayerId, p: AppendBuildQueue) {
+        self.edit_build_queue(player, p.city_id, |city, game| {
+            city.append_to_build_queue(game, p.build_task, p.repeat)
+        });
+    }
+
+    fn handle_insert_build_queue(&mut self, player: PlayerId, p: InsertBuildQueue) {
+        self.edit_build_queue(player, p.city_id, |city, game| {
+            city.insert_into_build_queue(game, p.index, p.build_task, p.repeat)
+        });
+    }
+
+    fn handle_remove_build_queue(&mut self, player: PlayerId, p: RemoveBuildQueue) {
+        self.edit_build_queue(player, p.city_id, |city, _| {
+            city.remove_from_build_queue(p.index)
+        });
+    }
+
+    fn handle_reorder_build_queue(&mut self, player: PlayerId, p: ReorderBuildQueue) {
+        self.edit_build_queue(player, p.city_id, |city, _| {
+            city.move_in_build_queue(p.from, p.to)
+        });
+    }
+
+    fn handle_set_build_task_repeat(&mut self, player: PlayerId, p: SetBuildTaskRepeat) {
+        self.edit_build_queue(player, p.city_id, |city, _| {
+            city.set_build_queue_repeat(p.index, p.repeat)
+        });
+    }
+
+    fn handle_set_city_governor(&mut self, player: PlayerId, p: SetCityGovernor) {
+        let mut city = self.game.city_mut(p.city_id);
+        if city.owner() != player {
+            log::warn!("{:?} attempted to configure a city they don't own", player);
+            return;
+        }
+
+        city.set_governor(&self.game, p.settings);
+    }
+
+    fn handle_set_city_specialists(&mut self, player: PlayerId, p: SetCitySpecialists) {
+        let mut city = self.game.city_mut(p.city_id);
+        if city.owner() != player {
+            log::warn!("{:?} attempted to configure a city they don't own", player);
+            return;
+        }
+
+        city.set_manual_specialists(&self.game, p.specialist, p.count);
+    }
+
     fn handle_configure_worked_tiles(&mut self, p: ConfigureWorkedTiles) {
         let mut city = self.game.city_mut(p.city_id);
         city.set_tile_manually_worked(&self.game, p.tile_pos, p.should_manually_work);
@@ -216,11 +448,34 @@ impl GameServer {
         self.game.push_event(Event::PlayerChanged(player));
     }
 
+    fn handle_set_state_religion(&mut self, player: PlayerId, p: SetStateReligion) {
+        if let Err(e) = self
+            .game
+            .player_mut(player)
+            .set_state_religion(&self.game, p.religion)
+        {
+            log::warn!("{:?} failed to set their state religion: {}", player, e);
+        }
+    }
+
+    fn handle_set_civics(&mut self, player: PlayerId, p: SetCivics) {
+        if let Err(e) = self
+            .game
+            .player_mut(player)
+            .set_civics(&self.game, p.civics)
+        {
+            log::warn!("{:?} failed to set their civics: {}", player, e);
+        }
+    }
+
     fn handle_set_economy_settings(&mut self, player: PlayerId, p: SetEconomySettings) {
         {
-            let mut player = self.game.player_mut(player);
-            player.set_economy_settings(p.settings);
-            player.update_economy(&self.game);
+            let mut the_player = self.game.player_mut(player);
+            if let Err(e) = the_player.set_economy_settings(p.settings) {
+                log::warn!("{:?} sent invalid economy settings: {}", player, e);
+                return;
+            }
+            the_player.update_economy(&self.game);
         }
         for city in self.game.player(player).cities() {
             self.game.city_mut(*city).update_economy(&self.game);
@@ -230,9 +485,11 @@ impl GameServer {
     }
 
     fn handle_set_worker_task(&mut self, p: SetWorkerTask) {
-        self.game
-            .unit_mut(p.worker_id)
-            .set_worker_task(Some(p.task));
+        {
+            let mut worker = self.game.unit_mut(p.worker_id);
+            worker.set_worker_task(Some(p.task));
+            worker.set_order(None);
+        }
         self.game.push_event(Event::UnitChanged(p.worker_id));
     }
 
@@ -279,6 +536,12 @@ impl GameServer {
                 turn: self.game.turn(),
             }),
         );
+
+        // Send the turn's updates before units follow their orders,
+        // so that clients receive order moves after the units' new state.
+        self.update(conns);
+        self.game.execute_unit_orders();
+        barbarians::play_turn(&mut self.game);
     }
 
     pub fn update(&mut self, conns: &Connections) {
@@ -286,12 +549,18 @@ impl GameServer {
         self.game.drain_events(|event| match event {
             Event::UnitChanged(id) => {
                 if self.game.is_unit_valid(id) {
-                    self.broadcast(
-                        conns,
-                        ServerPacket::UpdateUnit(UpdateUnit {
-                            unit: (*self.game.unit(id)).clone(),
-                        }),
-                    )
+                    let unit = self.game.unit(id);
+                    for &(player, conn) in &self.player_connections {
+                        // Spies are only shown to their owner.
+                        if !unit.is_hidden_from(player) {
+                            conns.get(conn).send_game_packet(
+                                ServerPacket::UpdateUnit(UpdateUnit {
+                                    unit: (*unit).clone(),
+                                }),
+                                None,
+                            );
+                        }
+                    }
                 }
             }
             Event::CityChanged(id) => self.broadcast(
@@ -306,19 +575,36 @@ impl GameServer {
                     player: (*self.game.player(id)).clone(),
                 }),
             ),
-            Event::TileChanged(pos) => self.broadcast(
-                conns,
-                ServerPacket::UpdateTile(UpdateTile {
-                    pos,
-                    tile: (*self.game.tile(pos).unwrap()).clone(),
-                }),
-            ),
+            Event::TileChanged(pos) => {
+                for &(player, _) in &self.player_connections {
+                    self.send_tile(conns, pos, player);
+                }
+            }
             Event::UnitDeleted(unit) => {
                 self.broadcast(conns, ServerPacket::DeleteUnit(DeleteUnit { unit }))
             }
             Event::TechUnlocked(player, tech) => {
+                let conn = match self.try_conn_for_player(player) {
+                    Some(conn) => conn,
+                    None => return,
+                };
+
+                // Send tiles with resources the tech reveals.
+                for x in 0..self.game.map().width() {
+                    for y in 0..self.game.map().height() {
+                        let pos = uvec2(x, y);
+                        let is_revealed = match self.game.tile(pos).unwrap().resource() {
+                            Some(resource) => resource.revealed_by == tech.name,
+                            None => false,
+                        };
+                        if is_revealed {
+                            self.send_tile(conns, pos, player);
+                        }
+                    }
+                }
+
                 conns
-                    .get(self.conn_for_player(player))
+                    .get(conn)
                     .send_game_packet(ServerPacket::TechUnlocked(TechUnlocked { tech }), None);
             }
             Event::WarDeclared(declarer, declared) => self.broadcast(
@@ -330,15 +616,44 @@ impl GameServer {
             }
             Event::CombatEvent(event) => self.broadcast(conns, ServerPacket::CombatEvent(event)),
             Event::UnitMoved(_, _, _) => {}
+            Event::UnitWoken(unit) => {
+                if self.game.is_unit_valid(unit) {
+                    // AI players have no connection to notify.
+                    let owner = self.game.unit(unit).owner();
+                    if let Some(conn) = self.try_conn_for_player(owner) {
+                        conns
+                            .get(conn)
+                            .send_game_packet(ServerPacket::UnitWoken(UnitWoken { unit }), None);
+                    }
+                }
+            }
+            Event::SpyDiscovered(discovered) => {
+                // AI victims have no connection to notify.
+                if let Some(conn) = self.try_conn_for_player(discovered.victim) {
+                    conns
+                        .get(conn)
+                        .send_game_packet(ServerPacket::SpyDiscovered(discovered), None);
+                }
+            }
+            Event::TribalVillageEntered(entered) => {
+                if let Some(conn) = self.try_conn_for_player(entered.player) {
+                    conns
+                        .get(conn)
+                        .send_game_packet(ServerPacket::TribalVillageEntered(entered), None);
+                }
+            }
+            Event::UnitsMovedByOrder(units, new_pos) => {
+                let units: Vec<UnitId> = units
+                    .into_iter()
+                    .filter(|&u| self.game.is_unit_valid(u))
+                    .collect();
+                self.send_units_moved(conns, &units, new_pos);
+            }
         });
 
         for (unit, success, target_pos, request_id, player) in self.combat_outcomes.try_iter() {
             if success {
-                conns.broadcast_game_packet(ServerPacket::UnitsMoved(UnitsMoved {
-                    units: vec![unit],
-                    new_movement_left: vec![self.game.unit(unit).movement_left()],
-                    new_pos: target_pos,
-                }));
+                self.send_units_moved(conns, &[unit], target_pos);
             }
             conns.get(self.conn_for_player(player)).send_game_packet(
                 ServerPacket::ConfirmMoveUnits(ConfirmMoveUnits { success }),
@@ -347,6 +662,40 @@ impl GameServer {
         }
     }
 
+    /// Sends the new position of moved units to each player,
+    /// leaving out units hidden from them, such as other players' spies.
+    fn send_units_moved(&self, conns: &Connections, units: &[UnitId], new_pos: UVec2) {
+        for &(player, conn) in &self.player_connections {
+            let (units, new_movement_left): (Vec<_>, Vec<_>) = units
+                .iter()
+                .map(|&u| self.game.unit(u))
+                .filter(|u| !u.is_hidden_from(player))
+                .map(|u| (u.id(), u.movement_left()))
+                .unzip();
+            if units.is_empty() {
+                continue;
+            }
+            conns.get(conn).send_game_packet(
+                ServerPacket::UnitsMoved(UnitsMoved {
+                    units,
+                    new_movement_left,
+                    new_pos,
+                }),
+                None,
+            );
+        }
+    }
+
+    /// Sends a tile to a player, hiding resources the player
+    /// hasn't revealed yet.
+    fn send_tile(&self, conns: &Connections, pos: UVec2, player: PlayerId) {
+        let mut tile = (*self.game.tile(pos).unwrap()).clone();
+        tile.hide_unrevealed_resource(self.game.registry(), &self.game.player(player));
+        conns
+            .get(self.conn_for_player(player))
+            .send_game_packet(ServerPacket::UpdateTile(UpdateTile { pos, tile }), None);
+    }
+
     pub fn game(&self) -> &Game {
         &self.game
     }
@@ -64,7 +64,7 @@ impl Server {
         let save = config
             .save
             .as_ref()
-            .map(|bytes| SaveFile::decode(bytes))
+            .map(|bytes| SaveFile::decode(bytes, &config.registry))
             .transpose()?;
 
         let lobby = match &save {
@@ -103,6 +103,9 @@ impl LobbyServer {
                     bail!(AdminRequired);
                 }
 
+                // Reject unknown difficulty levels.
+                self.registry.handicap(&settings.0.difficulty)?;
+
                 self.settings = settings.0;
             }
             ClientLobbyPacket::ChangeCivAndLeader(packet) => {
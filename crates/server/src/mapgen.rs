@@ -8,10 +8,12 @@ use glam::UVec2;
 use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64Mcg;
 use riposte_common::{
+    assets::Handle,
+    barbarians,
     game::player::PlayerKind,
     lobby::{GameLobby, SlotPlayer},
     mapgen::{LandGeneratorSettings, MapgenSettings},
-    registry::Registry,
+    registry::{Handicap, Registry},
     Grid,
 };
 
@@ -21,15 +23,20 @@ use crate::{
 };
 
 use self::{
-    land::{continents::ContinentGenerator, LandGenerator},
+    land::{
+        archipelago::ArchipelagoGenerator, continents::ContinentGenerator,
+        fractal::FractalGenerator, pangaea::PangaeaGenerator, LandGenerator,
+    },
     starting_locations::generate_starting_locations,
     terrain::TerrainGenerator,
 };
 
+mod features;
 mod land;
 mod resources;
 mod starting_locations;
 mod terrain;
+mod villages;
 
 pub struct MapgenContext {
     rng: Pcg64Mcg,
@@ -62,6 +69,7 @@ impl MapGenerator {
             self.settings.size.dimensions().x,
             self.settings.size.dimensions().y,
         );
+        land.set_wrap_x(self.settings.wrap_x);
 
         match &self.settings.land {
             LandGeneratorSettings::Flat(settings) => {
@@ -70,17 +78,42 @@ impl MapGenerator {
             LandGeneratorSettings::Continents(settings) => {
                 ContinentGenerator.generate(&mut self.context, settings, &mut land);
             }
+            LandGeneratorSettings::Archipelago(settings) => {
+                ArchipelagoGenerator.generate(&mut self.context, settings, &mut land);
+            }
+            LandGeneratorSettings::Pangaea(settings) => {
+                PangaeaGenerator.generate(&mut self.context, settings, &mut land);
+            }
+            LandGeneratorSettings::Fractal(settings) => {
+                FractalGenerator.generate(&mut self.context, settings, &mut land);
+            }
         };
 
         let (mut tiles, rivers) = TerrainGenerator::new(land, &mut self.context).generate();
+        features::place_features(&mut self.context, &mut tiles, registry);
         resources::place_resources(&mut self.context, &mut tiles, &registry);
 
         let starting_locations = generate_starting_locations(&tiles, lobby.slots().count());
+        villages::place_tribal_villages(&mut self.context, &mut tiles, &starting_locations);
 
         let tiles = tiles.map(RefCell::new);
 
+        let handicap = registry
+            .handicap(&self.settings.difficulty)
+            .expect("lobby accepted unknown difficulty");
+
         let mut game = Game::new(Arc::clone(registry), tiles, rivers, lobby.clone());
-        self.add_players_and_starting_units(&mut game, registry, lobby, &starting_locations);
+        game.set_speed(self.settings.speed);
+        self.add_players_and_starting_units(
+            &mut game,
+            registry,
+            lobby,
+            &handicap,
+            &starting_locations,
+        );
+        if self.settings.barbarians {
+            barbarians::add_barbarian_player(&mut game, handicap);
+        }
 
         for player in game.players() {
             let id = player.id();
@@ -96,6 +129,7 @@ impl MapGenerator {
         game: &mut Game,
         registry: &Registry,
         lobby: &GameLobby,
+        handicap: &Handle<Handicap>,
         starting_locations: &[UVec2],
     ) {
         let map_width = game.map().width();
@@ -121,6 +155,7 @@ impl MapGenerator {
                 player_kind,
                 player_desc.player.civ().unwrap().clone(),
                 player_desc.player.leader().unwrap().name.clone(),
+                handicap.clone(),
                 map_width,
                 map_height,
             ));
@@ -152,6 +187,22 @@ impl MapGenerator {
 
             let warrior_or_scout = game.new_unit_id();
             game.add_unit(Unit::new(warrior_or_scout, player, unit_kind, unit_pos));
+
+            // Easier difficulty levels give out more units.
+            let extra_units = game
+                .player(player)
+                .handicap_modifiers()
+                .extra_starting_units
+                .clone();
+            for unit_kind in extra_units {
+                let unit_pos = possible_unit_positions
+                    [self.context.rng.gen_range(0..possible_unit_positions.len())];
+                let unit = game.new_unit_id();
+                let unit_kind = registry
+                    .unit_kind(&unit_kind)
+                    .expect("starting units are checked when loading the registry");
+                game.add_unit(Unit::new(unit, player, unit_kind, unit_pos));
+            }
         }
     }
 }
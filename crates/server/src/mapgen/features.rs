@@ -0,0 +1,32 @@
+use glam::uvec2;
+use rand::Rng;
+use riposte_common::{registry::Registry, Grid, Tile};
+
+use super::MapgenContext;
+
+/// Places data-driven terrain features, such as oases,
+/// according to their placement rules.
+pub fn place_features(cx: &mut MapgenContext, grid: &mut Grid<Tile>, registry: &Registry) {
+    for feature in registry.terrain_features() {
+        let placement = match &feature.placement {
+            Some(placement) => placement,
+            None => continue,
+        };
+
+        for x in 0..grid.width() {
+            for y in 0..grid.height() {
+                let tile = grid.get_mut(uvec2(x, y)).unwrap();
+                // A tile holds at most one placed feature.
+                if tile.placed_features().next().is_none()
+                    && placement.terrains.contains(&tile.terrain())
+                    && (!placement.requires_fresh_water || tile.has_fresh_water())
+                    && (placement.allow_hills || !tile.is_hilled())
+                    && (placement.allow_forest || !tile.is_forested())
+                    && cx.rng.gen::<f32>() < placement.chance
+                {
+                    tile.add_feature(feature.clone());
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,13 @@
+use glam::UVec2;
 use riposte_common::Grid;
 
 use super::MapgenContext;
 
+pub mod archipelago;
 pub mod continents;
 pub mod flat;
+pub mod fractal;
+pub mod pangaea;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TileType {
@@ -11,6 +15,15 @@ pub enum TileType {
     Land,
 }
 
+/// Returns whether `pos` lies on the edge of the map and
+/// should be left as ocean. On a wrapping map, only the top
+/// and bottom rows count as edges.
+fn is_map_border(grid: &Grid<TileType>, pos: UVec2) -> bool {
+    pos.y == 0
+        || pos.y == grid.height() - 1
+        || (!grid.wraps_x() && (pos.x == 0 || pos.x == grid.width() - 1))
+}
+
 pub trait LandGenerator {
     type Settings;
 
@@ -0,0 +1,111 @@
+use glam::{ivec2, vec2};
+use noise::{Fbm, MultiFractal, NoiseFn, Seedable};
+use rand::Rng;
+use riposte_common::{mapgen::ArchipelagoSettings, poisson::sample_poisson_points, Grid};
+
+use crate::mapgen::MapgenContext;
+
+use super::{is_map_border, LandGenerator, TileType};
+
+const MIN_ISLAND_SEPARATION: f32 = 7.;
+const BASE_ISLAND_RADIUS: f32 = 2.5;
+
+/// Generates a map consisting of many small islands.
+pub struct ArchipelagoGenerator;
+
+impl LandGenerator for ArchipelagoGenerator {
+    type Settings = ArchipelagoSettings;
+
+    fn generate(
+        &mut self,
+        cx: &mut MapgenContext,
+        _settings: &Self::Settings,
+        target_grid: &mut Grid<TileType>,
+    ) {
+        target_grid.fill(TileType::Ocean);
+
+        // Spread islands across the map using a Poisson distribution to determine island centers.
+        let islands = sample_poisson_points(
+            &mut cx.rng,
+            MIN_ISLAND_SEPARATION,
+            vec2(target_grid.width() as f32, target_grid.height() as f32),
+        );
+
+        for island_center in islands {
+            // Vary island sizes so that some can support
+            // more than one city.
+            let size = cx.rng.gen_range(0.7..1.4);
+            let noise = Fbm::new().set_seed(cx.rng.gen()).set_frequency(0.3);
+
+            let center = island_center.round().as_i32();
+            let max_radius = ((BASE_ISLAND_RADIUS + 2.) * size).ceil() as i32;
+
+            for dx in -max_radius..=max_radius {
+                for dy in -max_radius..=max_radius {
+                    // Islands may cross the edge of a wrapping map.
+                    let pos = match target_grid.wrap(center + ivec2(dx, dy)) {
+                        Some(pos) => pos,
+                        None => continue,
+                    };
+                    if is_map_border(target_grid, pos) {
+                        continue;
+                    }
+
+                    // Sample noise in unwrapped coordinates so that
+                    // islands stay contiguous across the seam.
+                    let unwrapped = (center + ivec2(dx, dy)).as_f64();
+                    let modified_radius = (BASE_ISLAND_RADIUS
+                        + 2. * noise.get([unwrapped.x, unwrapped.y]) as f32)
+                        * size;
+
+                    let offset = vec2(dx as f32, dy as f32);
+                    if offset.length_squared() < modified_radius * modified_radius {
+                        target_grid.set(pos, TileType::Land).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::uvec2;
+    use image::{ImageBuffer, Rgb};
+
+    use super::*;
+
+    #[test]
+    fn generate_archipelago_preview_image() {
+        let size = uvec2(80, 48);
+        let mut grid = Grid::new(TileType::Ocean, size.x, size.y);
+
+        ArchipelagoGenerator.generate(
+            &mut MapgenContext::new(),
+            &ArchipelagoSettings {},
+            &mut grid,
+        );
+
+        let mut image = ImageBuffer::<Rgb<u8>, _>::new(size.x, size.y);
+        let mut land_tiles = 0;
+
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let color = match grid.get(uvec2(x, y)).unwrap() {
+                    TileType::Ocean => Rgb([20, 40, 230]),
+                    TileType::Land => {
+                        land_tiles += 1;
+                        Rgb([20, 200, 60])
+                    }
+                };
+
+                image.put_pixel(x, y, color);
+            }
+        }
+
+        assert!(land_tiles > 0);
+        assert!(land_tiles < size.x * size.y / 2);
+
+        // image.save("archipelago.png").unwrap();
+    }
+}
@@ -24,8 +24,16 @@ impl LandGenerator for ContinentGenerator {
         let space = target_grid.width() - settings.num_continents as u32 * 2;
         let space_per_continent = space / settings.num_continents as u32;
 
+        // On a wrapping map, shift the continents by a random amount
+        // so that the map edge is not always in the ocean.
+        let x_shift = if target_grid.wraps_x() {
+            cx.rng.gen_range(0..target_grid.width())
+        } else {
+            0
+        };
+
         for i in 0..settings.num_continents as u32 {
-            let x_offset = i * space_per_continent + 1;
+            let x_offset = i * space_per_continent + 1 + x_shift;
             generate_continent_into_grid(
                 cx,
                 uvec2(x_offset, 1),
@@ -39,6 +47,9 @@ impl LandGenerator for ContinentGenerator {
 /// Generates a continent into `target_grid`, with origin
 /// `origin` and the given size. The continent is guaranteed
 /// not to extend out of the provided region.
+///
+/// On a wrapping grid, the region may extend past the right edge
+/// and continue from the left edge.
 fn generate_continent_into_grid(
     cx: &mut MapgenContext,
     origin: UVec2,
@@ -64,9 +75,10 @@ fn generate_continent_into_grid(
             let val = (pos * pos) / (modified_radius * modified_radius);
             if val.x + val.y < 1. {
                 // Inside the ellipse; set to land.
-                target_grid
-                    .set(uvec2(x + origin.x, y + origin.y), TileType::Land)
+                let pos = target_grid
+                    .wrap(uvec2(x + origin.x, y + origin.y).as_i32())
                     .unwrap();
+                target_grid.set(pos, TileType::Land).unwrap();
             }
         }
     }
@@ -50,7 +50,14 @@ impl LandGenerator for FlatGenerator {
                         let modified_radius =
                             2. * noise.get([pos.x as f64, pos.y as f64]) as f32 + BASE_LAKE_RADIUS;
 
-                        if pos.distance_squared(lake_center) < modified_radius * modified_radius {
+                        // Lakes may cross the edge of a wrapping map.
+                        let mut offset = pos - lake_center;
+                        if target_grid.wraps_x() {
+                            let width = target_grid.width() as f32;
+                            offset.x = (offset.x + width / 2.).rem_euclid(width) - width / 2.;
+                        }
+
+                        if offset.length_squared() < modified_radius * modified_radius {
                             target_grid.set(uvec2(x, y), TileType::Ocean).unwrap();
                         }
                     }
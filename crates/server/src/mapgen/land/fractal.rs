@@ -0,0 +1,126 @@
+use std::f64::consts::TAU;
+
+use float_ord::FloatOrd;
+use glam::uvec2;
+use noise::{Fbm, MultiFractal, NoiseFn, Seedable};
+use rand::Rng;
+use riposte_common::{mapgen::FractalSettings, Grid};
+
+use crate::mapgen::MapgenContext;
+
+use super::{is_map_border, LandGenerator, TileType};
+
+/// Number of noise features across the width of the map.
+const FEATURES_PER_MAP: f64 = 4.;
+/// Number of tiles over which land fades out near the map edges.
+const EDGE_FALLOFF: f64 = 4.;
+
+/// Generates a map whose land is determined by thresholding
+/// fractal noise, such that a given fraction of the map is land.
+pub struct FractalGenerator;
+
+impl LandGenerator for FractalGenerator {
+    type Settings = FractalSettings;
+
+    fn generate(
+        &mut self,
+        cx: &mut MapgenContext,
+        settings: &Self::Settings,
+        target_grid: &mut Grid<TileType>,
+    ) {
+        target_grid.fill(TileType::Ocean);
+
+        let noise = Fbm::new().set_octaves(6).set_seed(cx.rng.gen());
+        let width = target_grid.width() as f64;
+        let height = target_grid.height() as f64;
+        let frequency = FEATURES_PER_MAP / width;
+
+        let mut heights = Vec::new();
+        for x in 0..target_grid.width() {
+            for y in 0..target_grid.height() {
+                let pos = uvec2(x, y);
+                if is_map_border(target_grid, pos) {
+                    continue;
+                }
+
+                let (fx, fy) = (x as f64 + 0.5, y as f64 + 0.5);
+
+                // On a wrapping map, sample the noise along a cylinder
+                // so that the left and right edges line up.
+                let mut value = if target_grid.wraps_x() {
+                    let angle = fx / width * TAU;
+                    let radius = width / TAU;
+                    noise.get([
+                        angle.cos() * radius * frequency,
+                        angle.sin() * radius * frequency,
+                        fy * frequency,
+                    ])
+                } else {
+                    noise.get([fx * frequency, fy * frequency])
+                };
+
+                // Push land away from the map edges.
+                let mut edge_distance = fy.min(height - fy);
+                if !target_grid.wraps_x() {
+                    edge_distance = edge_distance.min(fx).min(width - fx);
+                }
+                value -= (1. - edge_distance / EDGE_FALLOFF).max(0.);
+
+                heights.push((pos, value));
+            }
+        }
+
+        // The highest tiles become land.
+        heights.sort_unstable_by_key(|(_, value)| FloatOrd(-*value));
+        let num_land = heights.len() * settings.land_percent as usize / 100;
+        for &(pos, _) in &heights[..num_land] {
+            target_grid.set(pos, TileType::Land).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb};
+    use riposte_common::mapgen::LandPercent;
+
+    use super::*;
+
+    #[test]
+    fn generate_fractal_preview_image() {
+        let size = uvec2(80, 48);
+        let mut grid = Grid::new(TileType::Ocean, size.x, size.y);
+
+        FractalGenerator.generate(
+            &mut MapgenContext::new(),
+            &FractalSettings {
+                land_percent: LandPercent::Forty,
+            },
+            &mut grid,
+        );
+
+        let mut image = ImageBuffer::<Rgb<u8>, _>::new(size.x, size.y);
+        let mut land_tiles = 0;
+
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let color = match grid.get(uvec2(x, y)).unwrap() {
+                    TileType::Ocean => Rgb([20, 40, 230]),
+                    TileType::Land => {
+                        land_tiles += 1;
+                        Rgb([20, 200, 60])
+                    }
+                };
+
+                image.put_pixel(x, y, color);
+            }
+        }
+
+        // Border tiles are always ocean, so the map as a whole
+        // is slightly under the requested percentage.
+        let percent = land_tiles * 100 / (size.x * size.y);
+        assert!((35..=40).contains(&percent), "{}% land", percent);
+
+        // image.save("fractal.png").unwrap();
+    }
+}
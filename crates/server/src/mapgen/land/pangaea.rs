@@ -0,0 +1,210 @@
+use glam::{ivec2, uvec2, vec2, UVec2};
+use noise::{Fbm, MultiFractal, NoiseFn, Seedable};
+use rand::Rng;
+use riposte_common::{mapgen::PangaeaSettings, poisson::sample_poisson_points, Grid};
+
+use crate::mapgen::MapgenContext;
+
+use super::{is_map_border, LandGenerator, TileType};
+
+const MIN_SEA_SEPARATION: f32 = 12.;
+const BASE_SEA_RADIUS: f32 = 2.5;
+/// Minimum number of land tiles between an inland sea and the ocean.
+const SEA_SHORE_WIDTH: i32 = 2;
+
+/// Generates a map consisting of a single large continent,
+/// optionally dotted with inland seas.
+pub struct PangaeaGenerator;
+
+impl LandGenerator for PangaeaGenerator {
+    type Settings = PangaeaSettings;
+
+    fn generate(
+        &mut self,
+        cx: &mut MapgenContext,
+        settings: &Self::Settings,
+        target_grid: &mut Grid<TileType>,
+    ) {
+        target_grid.fill(TileType::Ocean);
+
+        generate_landmass(cx, target_grid);
+        retain_largest_landmass(target_grid);
+
+        if settings.inland_seas {
+            generate_inland_seas(cx, target_grid);
+        }
+    }
+}
+
+/// Generates a noisy ellipse of land covering most of the map.
+fn generate_landmass(cx: &mut MapgenContext, target_grid: &mut Grid<TileType>) {
+    // On a wrapping map, shift the continent by a random amount
+    // so that the ocean gap is not always at the map edge.
+    let x_shift = if target_grid.wraps_x() {
+        cx.rng.gen_range(0..target_grid.width())
+    } else {
+        0
+    };
+
+    let size = uvec2(target_grid.width(), target_grid.height()).as_f32();
+    let noise = Fbm::new().set_octaves(6).set_seed(cx.rng.gen());
+    let frequency = 3. / size;
+
+    for x in 0..target_grid.width() {
+        for y in 0..target_grid.height() {
+            let noise_value =
+                noise.get([x as f64 * frequency.x as f64, y as f64 * frequency.y as f64]) as f32;
+
+            // Ellipse
+            let radius = size / 2. * (0.95 + noise_value * 0.3);
+            let pos = vec2(x as f32, y as f32) + 0.5 - size / 2.;
+            let val = (pos * pos) / (radius * radius);
+            if val.x + val.y < 1. {
+                let pos = target_grid
+                    .wrap(ivec2((x + x_shift) as i32, y as i32))
+                    .unwrap();
+                if !is_map_border(target_grid, pos) {
+                    target_grid.set(pos, TileType::Land).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// Removes any land not connected to the largest landmass,
+/// so that the map contains exactly one continent.
+fn retain_largest_landmass(target_grid: &mut Grid<TileType>) {
+    let mut landmass_ids =
+        Grid::<Option<usize>>::new(None, target_grid.width(), target_grid.height());
+    let mut landmass_sizes = Vec::new();
+    let mut stack = Vec::new();
+
+    for x in 0..target_grid.width() {
+        for y in 0..target_grid.height() {
+            let pos = uvec2(x, y);
+            if *target_grid.get(pos).unwrap() != TileType::Land
+                || landmass_ids.get(pos).unwrap().is_some()
+            {
+                continue;
+            }
+
+            let id = landmass_sizes.len();
+            let mut size = 0;
+            stack.push(pos);
+            landmass_ids.set(pos, Some(id)).unwrap();
+
+            while let Some(pos) = stack.pop() {
+                size += 1;
+                for apos in target_grid.adjacent(pos) {
+                    if *target_grid.get(apos).unwrap() == TileType::Land
+                        && landmass_ids.get(apos).unwrap().is_none()
+                    {
+                        landmass_ids.set(apos, Some(id)).unwrap();
+                        stack.push(apos);
+                    }
+                }
+            }
+
+            landmass_sizes.push(size);
+        }
+    }
+
+    let largest = match (0..landmass_sizes.len()).max_by_key(|&id| landmass_sizes[id]) {
+        Some(id) => id,
+        None => return,
+    };
+
+    for x in 0..target_grid.width() {
+        for y in 0..target_grid.height() {
+            let pos = uvec2(x, y);
+            if let Some(id) = *landmass_ids.get(pos).unwrap() {
+                if id != largest {
+                    target_grid.set(pos, TileType::Ocean).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// Carves seas out of the interior of the continent. A sea is
+/// only placed where it is surrounded by land on all sides.
+fn generate_inland_seas(cx: &mut MapgenContext, target_grid: &mut Grid<TileType>) {
+    let sea_centers = sample_poisson_points(
+        &mut cx.rng,
+        MIN_SEA_SEPARATION,
+        vec2(target_grid.width() as f32, target_grid.height() as f32),
+    );
+
+    for sea_center in sea_centers {
+        // Small chance of sea not appearing
+        if cx.rng.gen_bool(0.3) {
+            continue;
+        }
+
+        let center = sea_center.round().as_i32();
+        let size = cx.rng.gen_range(0.8..1.3);
+        let max_radius = ((BASE_SEA_RADIUS + 1.5) * size).ceil() as i32;
+
+        let is_inland = |pos: Option<UVec2>| match pos {
+            Some(pos) => *target_grid.get(pos).unwrap() == TileType::Land,
+            None => false,
+        };
+        let reach = max_radius + SEA_SHORE_WIDTH;
+        let surrounded_by_land = (-reach..=reach)
+            .flat_map(|dx| (-reach..=reach).map(move |dy| ivec2(dx, dy)))
+            .all(|offset| is_inland(target_grid.wrap(center + offset)));
+        if !surrounded_by_land {
+            continue;
+        }
+
+        let noise = Fbm::new().set_seed(cx.rng.gen()).set_frequency(0.3);
+        for dx in -max_radius..=max_radius {
+            for dy in -max_radius..=max_radius {
+                let unwrapped = center + ivec2(dx, dy);
+                let modified_radius = (BASE_SEA_RADIUS
+                    + 1.5 * noise.get([unwrapped.x as f64, unwrapped.y as f64]) as f32)
+                    * size;
+
+                let offset = vec2(dx as f32, dy as f32);
+                if offset.length_squared() < modified_radius * modified_radius {
+                    let pos = target_grid.wrap(unwrapped).unwrap();
+                    target_grid.set(pos, TileType::Ocean).unwrap();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{ImageBuffer, Rgb};
+
+    use super::*;
+
+    #[test]
+    fn generate_pangaea_preview_image() {
+        let size = uvec2(80, 48);
+        let mut grid = Grid::new(TileType::Ocean, size.x, size.y);
+
+        PangaeaGenerator.generate(
+            &mut MapgenContext::new(),
+            &PangaeaSettings { inland_seas: true },
+            &mut grid,
+        );
+
+        let mut image = ImageBuffer::<Rgb<u8>, _>::new(size.x, size.y);
+
+        for x in 0..size.x {
+            for y in 0..size.y {
+                let color = match grid.get(uvec2(x, y)).unwrap() {
+                    TileType::Ocean => Rgb([20, 40, 230]),
+                    TileType::Land => Rgb([20, 200, 60]),
+                };
+
+                image.put_pixel(x, y, color);
+            }
+        }
+
+        // image.save("pangaea.png").unwrap();
+    }
+}
@@ -125,7 +125,7 @@ fn score_tile(pos: UVec2, tiles: &Grid<Tile>, existing_starting_locations: &[UVe
 
     // Prefer farther away from other starting locations
     for &other_pos in existing_starting_locations {
-        score -= 100. / other_pos.as_f64().distance(pos.as_f64());
+        score -= 100. / tiles.distance(other_pos, pos);
     }
 
     // Score each tile in the big fat cross of this tile
@@ -38,11 +38,11 @@ pub struct TerrainGenerator<'a> {
 impl<'a> TerrainGenerator<'a> {
     pub fn new(land_map: Grid<TileType>, cx: &'a mut MapgenContext) -> Self {
         Self {
-            elevation: Grid::new(0., land_map.width(), land_map.height()),
-            rainfall: Grid::new(0., land_map.width(), land_map.height()),
-            distance_to_ocean: Grid::new(0., land_map.width(), land_map.height()),
-            vector_to_ocean: Grid::new(DVec2::ZERO, land_map.width(), land_map.height()),
-            temperature: Grid::new(0., land_map.width(), land_map.height()),
+            elevation: grid_like(&land_map, 0.),
+            rainfall: grid_like(&land_map, 0.),
+            distance_to_ocean: grid_like(&land_map, 0.),
+            vector_to_ocean: grid_like(&land_map, DVec2::ZERO),
+            temperature: grid_like(&land_map, 0.),
             land_map,
 
             rivers: Rivers::default(),
@@ -70,7 +70,14 @@ impl<'a> TerrainGenerator<'a> {
         let mut queue = VecDeque::new();
         let mut visited = Grid::<bool>::new(false, self.land_map.width(), self.land_map.height());
 
-        for x in 1..self.land_map.width() - 1 {
+        // Tiles on the edges of the map are skipped, except
+        // along a wrapping X axis, which has no edges.
+        let x_range = if self.land_map.wraps_x() {
+            0..self.land_map.width()
+        } else {
+            1..self.land_map.width() - 1
+        };
+        for x in x_range {
             for y in 1..self.land_map.height() - 1 {
                 let pos = uvec2(x, y);
 
@@ -87,10 +94,10 @@ impl<'a> TerrainGenerator<'a> {
         }
 
         while let Some((pos, origin)) = queue.pop_front() {
-            let distance = pos.as_f64().distance(origin.as_f64());
+            let distance = self.land_map.distance(pos, origin);
             self.distance_to_ocean.set(pos, distance).unwrap();
             self.vector_to_ocean
-                .set(pos, origin.as_f64() - pos.as_f64())
+                .set(pos, self.land_map.offset(pos, origin).as_f64())
                 .unwrap();
 
             for apos in self.land_map.straight_adjacent(pos) {
@@ -293,11 +300,7 @@ impl<'a> TerrainGenerator<'a> {
     }
 
     fn finish(self) -> (Grid<Tile>, Rivers) {
-        let mut tiles = Grid::new(
-            Tile::new(Terrain::Ocean),
-            self.land_map.width(),
-            self.land_map.height(),
-        );
+        let mut tiles = grid_like(&self.land_map, Tile::new(Terrain::Ocean));
 
         let forest_noise = Fbm::new().set_seed(self.cx.rng.gen()).set_frequency(0.3);
 
@@ -345,6 +348,13 @@ impl<'a> TerrainGenerator<'a> {
     }
 }
 
+/// Creates a grid with the same size and wrapping as `land_map`.
+fn grid_like<T: Clone>(land_map: &Grid<TileType>, initial_value: T) -> Grid<T> {
+    let mut grid = Grid::new(initial_value, land_map.width(), land_map.height());
+    grid.set_wrap_x(land_map.wraps_x());
+    grid
+}
+
 /// Normalizes a grid to [0, 1].
 fn normalize_grid(grid: &mut Grid<f64>) {
     let min = grid
@@ -0,0 +1,45 @@
+use glam::{vec2, UVec2};
+use riposte_common::{poisson::sample_poisson_points, Grid, Tile};
+
+use super::MapgenContext;
+
+/// Minimum distance between tribal villages.
+const VILLAGE_SPACING: f32 = 8.;
+/// Minimum distance of tribal villages from starting locations, in tiles.
+const MIN_DISTANCE_FROM_START: u32 = 4;
+
+/// Scatters tribal villages over land away from starting locations.
+pub fn place_tribal_villages(
+    cx: &mut MapgenContext,
+    grid: &mut Grid<Tile>,
+    starting_locations: &[UVec2],
+) {
+    let points = sample_poisson_points(
+        &mut cx.rng,
+        VILLAGE_SPACING,
+        vec2(grid.width() as f32, grid.height() as f32),
+    );
+
+    // The sampled points don't know about the wrapped X axis,
+    // so villages on either side of the seam are spaced here.
+    let mut placed: Vec<UVec2> = Vec::new();
+    for point in points {
+        let pos = point.floor().as_u32();
+        let is_near_start = starting_locations
+            .iter()
+            .any(|&start| grid.tile_distance(start, pos) < MIN_DISTANCE_FROM_START);
+        let is_near_village = placed
+            .iter()
+            .any(|&village| grid.offset(village, pos).as_f32().length() < VILLAGE_SPACING);
+        if is_near_start || is_near_village {
+            continue;
+        }
+
+        if let Ok(tile) = grid.get_mut(pos) {
+            if tile.terrain().is_passable() {
+                tile.add_tribal_village();
+                placed.push(pos);
+            }
+        }
+    }
+}